@@ -0,0 +1,176 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, ExprLit, Fields, Lit, Meta, parse_macro_input};
+
+/// Implements `surrealdb_component_sdk::Table` for a struct with an `id: RecordId` field.
+///
+/// The table name is taken from `#[table = "name"]`.
+#[proc_macro_derive(Table, attributes(table))]
+pub fn derive_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let table = table_name(&input)?;
+    ensure_id_field(&input)?;
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::surrealdb_component_sdk::Table for #name #type_generics #where_clause {
+            const TABLE: &'static str = #table;
+
+            fn id(&self) -> &::surrealdb_component_sdk::RecordId {
+                &self.id
+            }
+        }
+    })
+}
+
+fn table_name(input: &DeriveInput) -> syn::Result<String> {
+    let mut table = None;
+    for attribute in input
+        .attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("table"))
+    {
+        if table.is_some() {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "duplicate #[table = \"...\"] attribute",
+            ));
+        }
+
+        let Meta::NameValue(meta) = &attribute.meta else {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "expected #[table = \"...\"]",
+            ));
+        };
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) = &meta.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &meta.value,
+                "table name must be a string literal",
+            ));
+        };
+
+        let name = value.value();
+        if !is_valid_table_name(&name) {
+            return Err(syn::Error::new_spanned(
+                value,
+                "table name must be non empty and contain only ASCII letters, digits, and underscores",
+            ));
+        }
+        table = Some(name);
+    }
+
+    table.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Table)] requires a #[table = \"...\"] attribute",
+        )
+    })
+}
+
+fn is_valid_table_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn ensure_id_field(input: &DeriveInput) -> syn::Result<()> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Table)] only supports structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[derive(Table)] requires a struct with named fields",
+        ));
+    };
+
+    if fields
+        .named
+        .iter()
+        .any(|field| field.ident.as_ref().is_some_and(|ident| ident == "id"))
+    {
+        return Ok(());
+    }
+
+    Err(syn::Error::new_spanned(
+        &input.ident,
+        "#[derive(Table)] requires an `id: RecordId` field",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::expand;
+
+    #[test]
+    fn expands_table_name_and_id_accessor() {
+        let tokens = expand(parse_quote! {
+            #[table = "person"]
+            struct Person {
+                id: RecordId,
+                name: String,
+            }
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(tokens.contains("const TABLE : & 'static str = \"person\""));
+        assert!(tokens.contains("& self . id"));
+    }
+
+    #[test]
+    fn rejects_missing_table_attribute() {
+        let error = expand(parse_quote! {
+            struct Person {
+                id: RecordId,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.to_string().contains("requires a #[table"));
+    }
+
+    #[test]
+    fn rejects_invalid_table_names() {
+        let error = expand(parse_quote! {
+            #[table = "person; REMOVE TABLE person"]
+            struct Person {
+                id: RecordId,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.to_string().contains("table name must be"));
+    }
+
+    #[test]
+    fn rejects_structs_without_id_field() {
+        let error = expand(parse_quote! {
+            #[table = "person"]
+            struct Person {
+                name: String,
+            }
+        })
+        .unwrap_err();
+
+        assert!(error.to_string().contains("`id: RecordId`"));
+    }
+}
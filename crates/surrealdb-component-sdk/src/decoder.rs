@@ -5,6 +5,8 @@ use serde::de::DeserializeOwned;
 use serde_cbor::Value as CborValue;
 use serde_json::{Value, json};
 
+use crate::types::{WireEncoding, wire};
+
 const MAX_DEPTH: usize = 6;
 const MAX_MAP_FIELDS: usize = 12;
 const MAX_ARRAY_INSPECTION: usize = 16;
@@ -13,8 +15,12 @@ const MAX_KEY_LENGTH: usize = 64;
 const MAX_DIAGNOSTIC_INPUT_LENGTH: usize = 262_144;
 const MAX_DIAGNOSTIC_LENGTH: usize = 8_192;
 
-pub(crate) fn decode<D: DeserializeOwned>(bytes: &[u8], operation: &str) -> Result<D> {
-    match serde_cbor::from_slice::<D>(bytes) {
+pub(crate) fn decode<D: DeserializeOwned>(
+    bytes: &[u8],
+    encoding: WireEncoding,
+    operation: &str,
+) -> Result<D> {
+    match wire::from_slice::<D>(bytes, encoding) {
         Ok(value) => Ok(value),
         Err(error) => {
             let diagnostic = diagnostic(bytes);
@@ -182,7 +188,7 @@ mod tests {
 
     fn failed_diagnostic_as<D: DeserializeOwned>(value: &CborValue) -> String {
         let bytes = serde_cbor::to_vec(value).unwrap();
-        match decode::<D>(&bytes, "failed to decode test") {
+        match decode::<D>(&bytes, WireEncoding::Legacy, "failed to decode test") {
             Ok(_) => panic!("expected decoding to fail"),
             Err(error) => format!("{error:#}"),
         }
@@ -298,7 +304,8 @@ mod tests {
             expected: "ok".into(),
         })
         .unwrap();
-        let value: Expected = decode(&bytes, "failed to decode test").unwrap();
+        let value: Expected =
+            decode(&bytes, WireEncoding::Legacy, "failed to decode test").unwrap();
         assert_eq!(value.expected, "ok");
     }
 }
@@ -1,12 +1,21 @@
+extern crate self as surrealdb_component_sdk;
+
 mod bindings;
 mod decoder;
 mod live;
 mod query;
 mod result;
+mod stream;
+mod table;
+mod transaction;
 mod types;
 
 pub use bindings::*;
 pub use live::*;
 pub use query::*;
 pub use result::*;
+pub use stream::*;
+pub use surrealdb_component_sdk_macros::Table;
+pub use table::*;
+pub use transaction::*;
 pub use types::*;
@@ -1,12 +1,21 @@
 use std::any::type_name;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow};
+use futures_util::{Stream, StreamExt};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use wit_bindgen::rt::async_support::{StreamReader, StreamReaderStream};
 
 use crate::bindings::current_parent_context;
 use crate::bindings::seamlezz::surrealdb::call;
 use crate::decoder;
+use crate::query::timeout_ms;
+use crate::result::QueryError;
+use crate::types::{WireEncoding, wire};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LiveAction {
@@ -14,6 +23,8 @@ pub enum LiveAction {
     Update,
     Delete,
     Killed,
+    /// The host ended the subscription because the component fell too far behind.
+    Lagged,
 }
 
 #[derive(Debug, Clone)]
@@ -22,11 +33,48 @@ pub struct LiveEvent {
     pub query_id: String,
     pub action: LiveAction,
     pub data: Vec<u8>,
+    /// Encoding of `data`, the one the live query was started with.
+    pub encoding: WireEncoding,
 }
 
 impl LiveEvent {
+    /// Takes the event the host sent for a live query started with `encoding`.
+    fn from_host(value: call::LiveEvent, encoding: WireEncoding) -> Self {
+        Self {
+            subscription_id: value.subscription_id,
+            query_id: value.query_id,
+            action: value.action.into(),
+            data: value.data,
+            encoding,
+        }
+    }
+
     pub fn parse<D: DeserializeOwned>(&self) -> Result<D> {
-        decoder::decode(&self.data, "failed to parse live event data")
+        decoder::decode(&self.data, self.encoding, "failed to parse live event data")
+    }
+}
+
+/// Live event with its payload decoded into `T`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedLiveEvent<T> {
+    Created(T),
+    Updated(T),
+    Deleted(T),
+    Killed,
+    Lagged,
+}
+
+impl<T: DeserializeOwned> TryFrom<LiveEvent> for TypedLiveEvent<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(event: LiveEvent) -> Result<Self> {
+        Ok(match event.action {
+            LiveAction::Create => Self::Created(event.parse()?),
+            LiveAction::Update => Self::Updated(event.parse()?),
+            LiveAction::Delete => Self::Deleted(event.parse()?),
+            LiveAction::Killed => Self::Killed,
+            LiveAction::Lagged => Self::Lagged,
+        })
     }
 }
 
@@ -37,52 +85,134 @@ impl From<call::LiveAction> for LiveAction {
             call::LiveAction::Update => Self::Update,
             call::LiveAction::Delete => Self::Delete,
             call::LiveAction::Killed => Self::Killed,
+            call::LiveAction::Lagged => Self::Lagged,
         }
     }
 }
 
-impl From<call::LiveEvent> for LiveEvent {
-    fn from(value: call::LiveEvent) -> Self {
-        Self {
-            subscription_id: value.subscription_id,
-            query_id: value.query_id,
-            action: value.action.into(),
-            data: value.data,
-        }
-    }
-}
-
+/// Live query subscription on the host.
+///
+/// Dropping the subscription cancels it on the host unless [`LiveSubscription::detach`] was
+/// called.
 pub struct LiveSubscription {
     subscription_id: u64,
-    stream: wit_bindgen::rt::async_support::StreamReader<call::LiveEvent>,
+    stream: StreamReaderStream<call::LiveEvent>,
+    encoding: WireEncoding,
+    cancel_on_drop: bool,
 }
 
 impl LiveSubscription {
+    fn new(
+        subscription_id: u64,
+        stream: StreamReader<call::LiveEvent>,
+        encoding: WireEncoding,
+    ) -> Self {
+        Self {
+            subscription_id,
+            stream: StreamReaderStream::new(stream),
+            encoding,
+            cancel_on_drop: true,
+        }
+    }
+
     pub fn id(&self) -> u64 {
         self.subscription_id
     }
 
     pub async fn next_event(&mut self) -> Result<Option<LiveEvent>> {
-        let Some(event) = self.stream.next().await else {
-            return Ok(None);
-        };
-
-        Ok(Some(event.into()))
+        self.next().await.transpose()
     }
 
-    pub async fn cancel(self) -> Result<()> {
+    pub async fn cancel(mut self) -> Result<()> {
+        self.cancel_on_drop = false;
         call::cancel(current_parent_context(), self.subscription_id)
             .await
             .map_err(|error| anyhow!(error))?;
 
         Ok(())
     }
+
+    /// Keeps the host subscription running after this handle is dropped.
+    pub fn detach(mut self) -> Self {
+        self.cancel_on_drop = false;
+        self
+    }
+
+    pub fn typed<T: DeserializeOwned>(self) -> TypedLiveSubscription<T> {
+        TypedLiveSubscription {
+            inner: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Stream for LiveSubscription {
+    type Item = Result<LiveEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let encoding = self.encoding;
+        self.stream
+            .poll_next_unpin(cx)
+            .map(|event| event.map(|event| Ok(LiveEvent::from_host(event, encoding))))
+    }
+}
+
+impl Drop for LiveSubscription {
+    fn drop(&mut self) {
+        if !self.cancel_on_drop {
+            return;
+        }
+
+        let subscription_id = self.subscription_id;
+        wit_bindgen::spawn(async move {
+            let _ = call::cancel(current_parent_context(), subscription_id).await;
+        });
+    }
+}
+
+/// [`LiveSubscription`] that decodes every event payload into `T`.
+pub struct TypedLiveSubscription<T> {
+    inner: LiveSubscription,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> TypedLiveSubscription<T> {
+    pub fn id(&self) -> u64 {
+        self.inner.id()
+    }
+
+    pub async fn next_event(&mut self) -> Result<Option<TypedLiveEvent<T>>> {
+        self.next().await.transpose()
+    }
+
+    pub async fn cancel(self) -> Result<()> {
+        self.inner.cancel().await
+    }
+
+    pub fn detach(self) -> Self {
+        Self {
+            inner: self.inner.detach(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Stream for TypedLiveSubscription<T> {
+    type Item = Result<TypedLiveEvent<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.inner
+            .poll_next_unpin(cx)
+            .map(|event| event.map(|event| event.and_then(TypedLiveEvent::try_from)))
+    }
 }
 
 pub struct LiveQuery<'a> {
     query_str: &'a str,
     params: Vec<(String, Vec<u8>)>,
     bind_error: Option<anyhow::Error>,
+    timeout: Option<Duration>,
+    encoding: WireEncoding,
 }
 
 impl<'a> LiveQuery<'a> {
@@ -92,7 +222,7 @@ impl<'a> LiveQuery<'a> {
         }
 
         let key = key.into();
-        let serialized = match serde_cbor::to_vec(&value)
+        let serialized = match wire::to_vec(&value, self.encoding)
             .with_context(|| format!("failed to bind key {} with type {}", key, type_name::<T>()))
         {
             Ok(serialized) => serialized,
@@ -106,6 +236,30 @@ impl<'a> LiveQuery<'a> {
         self
     }
 
+    /// Bounds how long the host may take to start the live query.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Encodes params and event payloads with `encoding`. Call it before [`LiveQuery::bind`].
+    pub fn encoding(mut self, encoding: WireEncoding) -> Self {
+        if !self.params.is_empty() && self.bind_error.is_none() {
+            self.bind_error = Some(anyhow!("set the wire encoding before binding params"));
+        }
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn typed<T: DeserializeOwned>(self) -> TypedLiveQuery<'a, T> {
+        TypedLiveQuery {
+            query: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Starts the live query. A rejected, unparsable, or timed out query fails with a
+    /// [`QueryError`].
     pub async fn execute(self) -> Result<LiveSubscription> {
         if let Some(error) = self.bind_error {
             return Err(error);
@@ -115,12 +269,27 @@ impl<'a> LiveQuery<'a> {
             current_parent_context(),
             self.query_str.to_string(),
             self.params,
+            timeout_ms(self.timeout),
+            self.encoding.into(),
         )
-        .await;
-        Ok(LiveSubscription {
+        .await
+        .map_err(QueryError::from)?;
+        Ok(LiveSubscription::new(
             subscription_id,
             stream,
-        })
+            self.encoding,
+        ))
+    }
+}
+
+pub struct TypedLiveQuery<'a, T> {
+    query: LiveQuery<'a>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> TypedLiveQuery<'_, T> {
+    pub async fn execute(self) -> Result<TypedLiveSubscription<T>> {
+        Ok(self.query.execute().await?.typed())
     }
 }
 
@@ -129,5 +298,79 @@ pub fn subscribe(query_str: &str) -> LiveQuery<'_> {
         query_str,
         params: Vec::new(),
         bind_error: None,
+        timeout: None,
+        encoding: WireEncoding::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{LiveAction, LiveEvent, TypedLiveEvent};
+    use crate::types::WireEncoding;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Person {
+        name: String,
+    }
+
+    fn event(action: LiveAction, data: Vec<u8>) -> LiveEvent {
+        LiveEvent {
+            subscription_id: 1,
+            query_id: "query".into(),
+            action,
+            data,
+            encoding: WireEncoding::Legacy,
+        }
+    }
+
+    #[test]
+    fn typed_events_decode_payload_per_action() {
+        let data = serde_cbor::to_vec(&Person {
+            name: "demo".into(),
+        })
+        .unwrap();
+
+        for (action, expected) in [
+            (
+                LiveAction::Create,
+                TypedLiveEvent::Created(Person {
+                    name: "demo".into(),
+                }),
+            ),
+            (
+                LiveAction::Update,
+                TypedLiveEvent::Updated(Person {
+                    name: "demo".into(),
+                }),
+            ),
+            (
+                LiveAction::Delete,
+                TypedLiveEvent::Deleted(Person {
+                    name: "demo".into(),
+                }),
+            ),
+        ] {
+            let typed = TypedLiveEvent::<Person>::try_from(event(action, data.clone())).unwrap();
+            assert_eq!(typed, expected);
+        }
+    }
+
+    #[test]
+    fn killed_events_skip_payload_decoding() {
+        let typed = TypedLiveEvent::<Person>::try_from(event(LiveAction::Killed, Vec::new()));
+        assert_eq!(typed.unwrap(), TypedLiveEvent::Killed);
+    }
+
+    #[test]
+    fn typed_event_decode_errors_include_diagnostics() {
+        let data = serde_cbor::to_vec(&42).unwrap();
+        let error = TypedLiveEvent::<Person>::try_from(event(LiveAction::Create, data))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("failed to parse live event data"));
+        assert!(error.contains("CBOR shape"));
     }
 }
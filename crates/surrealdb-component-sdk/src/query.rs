@@ -1,16 +1,23 @@
 use std::any::type_name;
+use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 
 use crate::bindings::current_parent_context;
 use crate::bindings::seamlezz::surrealdb::call;
-use crate::result::QueryResultHolder;
+use crate::result::{QueryError, QueryResultHolder};
+use crate::stream::RowStream;
+use crate::types::{WireEncoding, wire};
 
 pub struct Query<'a> {
     query_str: &'a str,
     params: Vec<(String, Vec<u8>)>,
     bind_error: Option<anyhow::Error>,
+    timeout: Option<Duration>,
+    encoding: WireEncoding,
+    transaction: Option<&'a call::Transaction>,
 }
 
 impl<'a> Query<'a> {
@@ -20,7 +27,7 @@ impl<'a> Query<'a> {
         }
 
         let key = key.into();
-        let serialized = match serde_cbor::to_vec(&value)
+        let serialized = match wire::to_vec(&value, self.encoding)
             .with_context(|| format!("failed to bind key {key} with type {}", type_name::<T>()))
         {
             Ok(serialized) => serialized,
@@ -34,25 +41,120 @@ impl<'a> Query<'a> {
         self
     }
 
+    /// Fails the statement set with a timeout error once `timeout` elapses. The host may enforce
+    /// a shorter limit, and bounds the statements that take a SurrealQL `TIMEOUT` by the same
+    /// deadline so they stop in the database too.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Encodes params and decodes results with `encoding`. Call it before [`Query::bind`], since
+    /// params are encoded as they are bound.
+    pub fn encoding(mut self, encoding: WireEncoding) -> Self {
+        if !self.params.is_empty() && self.bind_error.is_none() {
+            self.bind_error = Some(anyhow!("set the wire encoding before binding params"));
+        }
+        self.encoding = encoding;
+        self
+    }
+
+    /// Runs the statement set on the host. Dropping the returned future cancels the host call,
+    /// but the database only stops the statement set once its [`Query::timeout`] elapses.
     pub async fn execute(self) -> Result<QueryResultHolder> {
         if let Some(error) = self.bind_error {
             return Err(error);
         }
 
-        let results = call::query(
+        let parent_context = current_parent_context();
+        let query_str = self.query_str.to_string();
+        let timeout_ms = timeout_ms(self.timeout);
+        let encoding = self.encoding.into();
+        let results = match self.transaction {
+            Some(transaction) => {
+                transaction
+                    .query(parent_context, query_str, self.params, timeout_ms, encoding)
+                    .await
+            }
+            None => call::query(parent_context, query_str, self.params, timeout_ms, encoding).await,
+        };
+        Ok(QueryResultHolder::new(
+            results
+                .into_iter()
+                .map(|result| result.map_err(QueryError::from))
+                .collect(),
+        )
+        .with_encoding(self.encoding))
+    }
+
+    /// Runs the statement set on the host and streams result rows in chunks instead of holding
+    /// every statement result in guest memory. The host still runs the whole statement set and
+    /// holds its results until the stream is dropped, so page very large reads with `START` and
+    /// `LIMIT`. Not available inside a transaction.
+    pub async fn stream<T: DeserializeOwned>(self) -> Result<RowStream<T>> {
+        if let Some(error) = self.bind_error {
+            return Err(error);
+        }
+        if self.transaction.is_some() {
+            bail!("streaming query results is not supported inside a transaction");
+        }
+
+        let chunks = call::query_stream(
             current_parent_context(),
             self.query_str.to_string(),
             self.params,
+            timeout_ms(self.timeout),
+            self.encoding.into(),
         )
         .await;
-        Ok(QueryResultHolder::new(results))
+        Ok(RowStream::new(chunks, self.encoding))
     }
 }
 
 pub fn query(query_str: &str) -> Query<'_> {
-    Query {
-        query_str,
-        params: Vec::new(),
-        bind_error: None,
+    Query::new(query_str, None)
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(query_str: &'a str, transaction: Option<&'a call::Transaction>) -> Self {
+        Self {
+            query_str,
+            params: Vec::new(),
+            bind_error: None,
+            timeout: None,
+            encoding: WireEncoding::default(),
+            transaction,
+        }
+    }
+}
+
+impl From<WireEncoding> for call::WireEncoding {
+    fn from(value: WireEncoding) -> Self {
+        match value {
+            WireEncoding::Legacy => Self::Legacy,
+            WireEncoding::Native => Self::Native,
+        }
+    }
+}
+
+/// Whole milliseconds, rounded up so a sub-millisecond timeout never becomes zero.
+pub(crate) fn timeout_ms(timeout: Option<Duration>) -> Option<u64> {
+    timeout.map(|timeout| u64::try_from(timeout.as_nanos().div_ceil(1_000_000)).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::timeout_ms;
+
+    #[test]
+    fn rounds_timeouts_up_to_whole_milliseconds() {
+        assert_eq!(timeout_ms(None), None);
+        assert_eq!(timeout_ms(Some(Duration::ZERO)), Some(0));
+        assert_eq!(timeout_ms(Some(Duration::from_nanos(1))), Some(1));
+        assert_eq!(timeout_ms(Some(Duration::from_micros(1500))), Some(2));
+        assert_eq!(timeout_ms(Some(Duration::from_secs(2))), Some(2000));
+        assert_eq!(timeout_ms(Some(Duration::MAX)), Some(u64::MAX));
     }
 }
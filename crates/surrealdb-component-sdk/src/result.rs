@@ -1,37 +1,110 @@
+use std::fmt;
+
 use anyhow::{Result, anyhow};
 use serde::de::DeserializeOwned;
 
+use crate::bindings::seamlezz::surrealdb::call;
 use crate::decoder;
+use crate::types::WireEncoding;
+
+/// Statement failure reported by the host, classified from the SurrealDB error kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    ParseError(String),
+    NotAllowed(String),
+    TransactionCascade(String),
+    Thrown(String),
+    Timeout(String),
+    SchemaViolation(String),
+    NotFound(String),
+    /// A bound param could not be decoded, the message names its JSON pointer.
+    InvalidParam(String),
+    Internal(String),
+}
+
+impl QueryError {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ParseError(message)
+            | Self::NotAllowed(message)
+            | Self::TransactionCascade(message)
+            | Self::Thrown(message)
+            | Self::Timeout(message)
+            | Self::SchemaViolation(message)
+            | Self::NotFound(message)
+            | Self::InvalidParam(message)
+            | Self::Internal(message) => message,
+        }
+    }
+
+    pub fn is_transaction_cascade(&self) -> bool {
+        matches!(self, Self::TransactionCascade(_))
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<call::QueryError> for QueryError {
+    fn from(value: call::QueryError) -> Self {
+        match value {
+            call::QueryError::ParseError(message) => Self::ParseError(message),
+            call::QueryError::NotAllowed(message) => Self::NotAllowed(message),
+            call::QueryError::TransactionCascade(message) => Self::TransactionCascade(message),
+            call::QueryError::Thrown(message) => Self::Thrown(message),
+            call::QueryError::Timeout(message) => Self::Timeout(message),
+            call::QueryError::SchemaViolation(message) => Self::SchemaViolation(message),
+            call::QueryError::NotFound(message) => Self::NotFound(message),
+            call::QueryError::InvalidParam(message) => Self::InvalidParam(message),
+            call::QueryError::Internal(message) => Self::Internal(message),
+        }
+    }
+}
 
 pub trait SingleQueryResultExtractor: Sized {
-    fn from_bytes(bytes: &[u8]) -> Result<Self>;
+    fn from_bytes(bytes: &[u8], encoding: WireEncoding) -> Result<Self>;
 }
 
-fn parse<D: DeserializeOwned>(bytes: &[u8]) -> Result<D> {
-    decoder::decode(bytes, "failed to parse query result")
+fn parse<D: DeserializeOwned>(bytes: &[u8], encoding: WireEncoding) -> Result<D> {
+    decoder::decode(bytes, encoding, "failed to parse query result")
 }
 
 impl<D: DeserializeOwned> SingleQueryResultExtractor for Vec<D> {
-    fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        parse(bytes)
+    fn from_bytes(bytes: &[u8], encoding: WireEncoding) -> Result<Self> {
+        parse(bytes, encoding)
     }
 }
 
 impl<D: DeserializeOwned> SingleQueryResultExtractor for Option<D> {
-    fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        let values: Vec<D> = parse(bytes)?;
+    fn from_bytes(bytes: &[u8], encoding: WireEncoding) -> Result<Self> {
+        let values: Vec<D> = parse(bytes, encoding)?;
         Ok(values.into_iter().next())
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct QueryResultHolder {
-    results: Vec<Result<Vec<u8>, String>>,
+    results: Vec<Result<Vec<u8>, QueryError>>,
+    encoding: WireEncoding,
 }
 
 impl QueryResultHolder {
-    pub fn new(results: Vec<Result<Vec<u8>, String>>) -> Self {
-        Self { results }
+    pub fn new(results: Vec<Result<Vec<u8>, QueryError>>) -> Self {
+        Self {
+            results,
+            encoding: WireEncoding::default(),
+        }
+    }
+
+    /// Decodes the results as `encoding`, the encoding the query ran with.
+    pub fn with_encoding(mut self, encoding: WireEncoding) -> Self {
+        self.encoding = encoding;
+        self
     }
 
     pub fn len(&self) -> usize {
@@ -50,13 +123,13 @@ impl QueryResultHolder {
             .as_ref()
             .map_err(|error| anyhow!(error.clone()))?;
 
-        T::from_bytes(bytes)
+        T::from_bytes(bytes, self.encoding)
     }
 
     pub fn take_result<T: SingleQueryResultExtractor>(
         &self,
         index: usize,
-    ) -> Result<Result<T, String>> {
+    ) -> Result<Result<T, QueryError>> {
         let Some(result) = self.results.get(index) else {
             return Err(anyhow!("result index {index} out of bounds"));
         };
@@ -66,7 +139,7 @@ impl QueryResultHolder {
         }
 
         match result {
-            Ok(bytes) => Ok(Ok(T::from_bytes(bytes)?)),
+            Ok(bytes) => Ok(Ok(T::from_bytes(bytes, self.encoding)?)),
             Err(error) => Ok(Err(error.clone())),
         }
     }
@@ -79,10 +152,10 @@ impl QueryResultHolder {
             .as_ref()
             .map_err(|error| anyhow!(error.clone()))?;
 
-        parse(bytes)
+        parse(bytes, self.encoding)
     }
 
-    pub fn parse_result<D: DeserializeOwned>(&self, index: usize) -> Result<Result<D, String>> {
+    pub fn parse_result<D: DeserializeOwned>(&self, index: usize) -> Result<Result<D, QueryError>> {
         let Some(result) = self.results.get(index) else {
             return Err(anyhow!("result index {index} out of bounds"));
         };
@@ -92,46 +165,39 @@ impl QueryResultHolder {
         }
 
         match result {
-            Ok(bytes) => Ok(Ok(parse(bytes)?)),
+            Ok(bytes) => Ok(Ok(parse(bytes, self.encoding)?)),
             Err(error) => Ok(Err(error.clone())),
         }
     }
 
-    pub fn find_user_error(&self) -> Option<String> {
-        let errors: Vec<String> = self
-            .results
+    /// Returns the first statement error that is not a transaction cascade.
+    pub fn find_user_error(&self) -> Option<QueryError> {
+        self.results
             .iter()
-            .filter_map(|result| result.as_ref().err().cloned())
-            .filter(|error| {
-                !error.contains("The query was not executed due to a failed transaction")
-                    && !error.contains("The query was not executed due to a cancelled transaction")
-                    && !error.contains("the transaction was aborted due to a prior error")
-            })
-            .collect();
-
-        if errors.is_empty() {
-            return None;
-        }
-
-        Some(errors.join("; "))
+            .filter_map(|result| result.as_ref().err())
+            .find(|error| !error.is_transaction_cascade())
+            .cloned()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::QueryResultHolder;
+    use super::{QueryError, QueryResultHolder};
 
     const FAILED_TRANSACTION: &str = "The query was not executed due to a failed transaction";
     const CANCELLED_TRANSACTION: &str = "The query was not executed due to a cancelled transaction";
 
+    fn cascade(message: &str) -> QueryError {
+        QueryError::TransactionCascade(message.into())
+    }
+
     #[test]
     fn parse_result_returns_meaningful_transaction_errors() {
         let results = QueryResultHolder::new(vec![
             Ok(serde_cbor::to_vec(&serde_cbor::Value::Null).unwrap()),
-            Err(FAILED_TRANSACTION.into()),
-            Err("Specify a database to use".into()),
-            Err(CANCELLED_TRANSACTION.into()),
-            Err("Another statement error".into()),
+            Err(cascade(FAILED_TRANSACTION)),
+            Err(QueryError::Thrown("An error occurred: boom".into())),
+            Err(cascade(CANCELLED_TRANSACTION)),
         ]);
 
         let error = results
@@ -139,27 +205,28 @@ mod tests {
             .unwrap()
             .unwrap_err();
 
-        assert_eq!(error, "Specify a database to use; Another statement error");
+        assert_eq!(error, QueryError::Thrown("An error occurred: boom".into()));
     }
 
     #[test]
     fn take_result_returns_meaningful_transaction_errors() {
         let results = QueryResultHolder::new(vec![
             Ok(serde_cbor::to_vec(&Vec::<i32>::new()).unwrap()),
-            Err(CANCELLED_TRANSACTION.into()),
-            Err("Specify a database to use".into()),
+            Err(cascade(CANCELLED_TRANSACTION)),
+            Err(QueryError::NotAllowed("Specify a database to use".into())),
         ]);
 
         let error = results.take_result::<Option<i32>>(0).unwrap().unwrap_err();
 
-        assert_eq!(error, "Specify a database to use");
+        assert!(matches!(error, QueryError::NotAllowed(_)));
+        assert_eq!(error.to_string(), "Specify a database to use");
     }
 
     #[test]
     fn result_methods_preserve_transaction_error_without_meaningful_error() {
         let results = QueryResultHolder::new(vec![
-            Err(FAILED_TRANSACTION.into()),
-            Err(CANCELLED_TRANSACTION.into()),
+            Err(cascade(FAILED_TRANSACTION)),
+            Err(cascade(CANCELLED_TRANSACTION)),
         ]);
 
         let parse_error = results
@@ -168,7 +235,8 @@ mod tests {
             .unwrap_err();
         let take_error = results.take_result::<Option<i32>>(1).unwrap().unwrap_err();
 
-        assert_eq!(parse_error, FAILED_TRANSACTION);
-        assert_eq!(take_error, CANCELLED_TRANSACTION);
+        assert_eq!(parse_error, cascade(FAILED_TRANSACTION));
+        assert_eq!(take_error, cascade(CANCELLED_TRANSACTION));
+        assert!(results.find_user_error().is_none());
     }
 }
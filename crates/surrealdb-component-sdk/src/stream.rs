@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll, ready};
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use wit_bindgen::rt::async_support::{StreamReader, StreamReaderStream};
+
+use crate::bindings::seamlezz::surrealdb::call;
+use crate::decoder;
+use crate::result::QueryError;
+use crate::types::WireEncoding;
+
+/// Rows of a streamed statement set, decoded into `T` one at a time.
+///
+/// Rows of every statement are yielded in order. A failed statement is yielded as a single `Err`
+/// holding its [`QueryError`], and the stream continues with the next statement. Dropping the
+/// stream tells the host to stop producing chunks.
+pub struct RowStream<T> {
+    chunks: StreamReaderStream<call::RowChunk>,
+    rows: Rows<T>,
+}
+
+impl<T: DeserializeOwned> RowStream<T> {
+    pub(crate) fn new(chunks: StreamReader<call::RowChunk>, encoding: WireEncoding) -> Self {
+        Self {
+            chunks: StreamReaderStream::new(chunks),
+            rows: Rows {
+                encoding,
+                ..Rows::default()
+            },
+        }
+    }
+
+    /// Index of the statement the most recently yielded row or error belongs to.
+    pub fn statement(&self) -> Option<usize> {
+        self.rows.statement
+    }
+
+    pub async fn next_row(&mut self) -> Result<Option<T>> {
+        self.next().await.transpose()
+    }
+}
+
+impl<T: DeserializeOwned> Stream for RowStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(row) = self.rows.next_row() {
+                return Poll::Ready(Some(row));
+            }
+
+            match ready!(self.chunks.poll_next_unpin(cx)) {
+                Some(chunk) => {
+                    if let Err(error) = self.rows.push(chunk) {
+                        return Poll::Ready(Some(Err(error.into())));
+                    }
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Encoded rows of the current chunk, decoded lazily as the stream is polled.
+struct Rows<T> {
+    statement: Option<usize>,
+    pending: std::vec::IntoIter<Vec<u8>>,
+    encoding: WireEncoding,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for Rows<T> {
+    fn default() -> Self {
+        Self {
+            statement: None,
+            pending: Vec::new().into_iter(),
+            encoding: WireEncoding::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Rows<T> {
+    fn push(&mut self, chunk: call::RowChunk) -> Result<(), QueryError> {
+        self.statement = Some(chunk.statement as usize);
+        self.pending = chunk.rows.map_err(QueryError::from)?.into_iter();
+        Ok(())
+    }
+
+    fn next_row(&mut self) -> Option<Result<T>> {
+        let row = self.pending.next()?;
+        Some(decoder::decode(
+            &row,
+            self.encoding,
+            "failed to parse streamed row",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rows;
+    use crate::bindings::seamlezz::surrealdb::call;
+    use crate::result::QueryError;
+
+    fn chunk(statement: u32, rows: &[i64]) -> call::RowChunk {
+        call::RowChunk {
+            statement,
+            rows: Ok(rows
+                .iter()
+                .map(|row| serde_cbor::to_vec(row).unwrap())
+                .collect()),
+        }
+    }
+
+    #[test]
+    fn rows_decode_per_chunk_and_surface_statement_errors() {
+        let mut rows = Rows::<i64>::default();
+
+        rows.push(chunk(0, &[1, 2])).unwrap();
+        assert_eq!(rows.next_row().unwrap().unwrap(), 1);
+        assert_eq!(rows.next_row().unwrap().unwrap(), 2);
+        assert!(rows.next_row().is_none());
+        assert_eq!(rows.statement, Some(0));
+
+        let error = rows
+            .push(call::RowChunk {
+                statement: 1,
+                rows: Err(call::QueryError::Thrown("boom".into())),
+            })
+            .unwrap_err();
+        assert_eq!(error, QueryError::Thrown("boom".into()));
+        assert_eq!(rows.statement, Some(1));
+
+        rows.push(chunk(2, &[3])).unwrap();
+        assert_eq!(rows.next_row().unwrap().unwrap(), 3);
+        assert_eq!(rows.statement, Some(2));
+    }
+}
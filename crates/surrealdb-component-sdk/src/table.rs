@@ -0,0 +1,163 @@
+use std::future::Future;
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::live::{TypedLiveSubscription, subscribe};
+use crate::query::query;
+use crate::types::{RecordId, RecordIdKey};
+
+/// A SurrealDB table model, usually implemented with `#[derive(Table)]`.
+pub trait Table: Serialize + DeserializeOwned {
+    const TABLE: &'static str;
+
+    fn id(&self) -> &RecordId;
+
+    fn record_id(key: impl Into<RecordIdKey>) -> RecordId {
+        RecordId::new(Self::TABLE, key)
+    }
+
+    /// Fails without querying when `self.id()` belongs to another table than [`Table::TABLE`].
+    fn create(&self) -> impl Future<Output = Result<Self>> {
+        async move {
+            let result = query("CREATE $id CONTENT $content")
+                .bind("id", table_id(self)?)
+                .bind("content", self)
+                .execute()
+                .await?;
+
+            result
+                .take::<Option<Self>>(0)?
+                .ok_or_else(|| anyhow!("CREATE {} returned no record", self.id()))
+        }
+    }
+
+    fn select(key: impl Into<RecordIdKey>) -> impl Future<Output = Result<Option<Self>>> {
+        let id = Self::record_id(key);
+        async move {
+            query("SELECT * FROM $id")
+                .bind("id", id)
+                .execute()
+                .await?
+                .take(0)
+        }
+    }
+
+    fn select_all() -> impl Future<Output = Result<Vec<Self>>> {
+        async move {
+            let statement = format!("SELECT * FROM `{}`", Self::TABLE);
+            query(&statement).execute().await?.take(0)
+        }
+    }
+
+    /// Fails without querying when `self.id()` belongs to another table than [`Table::TABLE`].
+    fn update(&self) -> impl Future<Output = Result<Option<Self>>> {
+        async move {
+            query("UPDATE $id CONTENT $content")
+                .bind("id", table_id(self)?)
+                .bind("content", self)
+                .execute()
+                .await?
+                .take(0)
+        }
+    }
+
+    fn merge<P: Serialize>(
+        key: impl Into<RecordIdKey>,
+        patch: P,
+    ) -> impl Future<Output = Result<Option<Self>>> {
+        let id = Self::record_id(key);
+        async move {
+            query("UPDATE $id MERGE $patch")
+                .bind("id", id)
+                .bind("patch", patch)
+                .execute()
+                .await?
+                .take(0)
+        }
+    }
+
+    fn delete(key: impl Into<RecordIdKey>) -> impl Future<Output = Result<()>> {
+        let id = Self::record_id(key);
+        async move {
+            query("DELETE $id")
+                .bind("id", id)
+                .execute()
+                .await?
+                .take::<Vec<Self>>(0)?;
+            Ok(())
+        }
+    }
+
+    fn live() -> impl Future<Output = Result<TypedLiveSubscription<Self>>> {
+        async move {
+            let statement = format!("LIVE SELECT * FROM `{}`", Self::TABLE);
+            subscribe(&statement).typed().execute().await
+        }
+    }
+}
+
+fn table_id<T: Table>(record: &T) -> Result<&RecordId> {
+    let id = record.id();
+    if id.table != T::TABLE {
+        return Err(anyhow!(
+            "record id {id} does not belong to table {}",
+            T::TABLE
+        ));
+    }
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{RecordId, Table};
+
+    #[derive(Debug, Serialize, Deserialize, Table)]
+    #[table = "person"]
+    struct Person {
+        id: RecordId,
+        name: String,
+    }
+
+    #[test]
+    fn derive_sets_table_and_id_accessor() {
+        let person = Person {
+            id: Person::record_id("demo"),
+            name: "demo".into(),
+        };
+
+        assert_eq!(Person::TABLE, "person");
+        assert_eq!(person.id(), &RecordId::new("person", "demo"));
+        assert_eq!(person.name, "demo");
+    }
+
+    #[test]
+    fn create_and_update_reject_ids_of_other_tables() {
+        let person = Person {
+            id: RecordId::new("secret", "demo"),
+            name: "demo".into(),
+        };
+
+        let Poll::Ready(Err(error)) =
+            pin!(person.create()).poll(&mut Context::from_waker(Waker::noop()))
+        else {
+            panic!("expected create to fail before querying");
+        };
+        assert!(
+            error
+                .to_string()
+                .ends_with("does not belong to table person"),
+            "got {error}"
+        );
+        assert!(matches!(
+            pin!(person.update()).poll(&mut Context::from_waker(Waker::noop())),
+            Poll::Ready(Err(_))
+        ));
+    }
+}
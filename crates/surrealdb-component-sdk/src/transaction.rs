@@ -0,0 +1,37 @@
+use anyhow::{Result, anyhow};
+
+use crate::bindings::current_parent_context;
+use crate::bindings::seamlezz::surrealdb::call;
+use crate::query::Query;
+
+/// An open SurrealDB transaction held by the host.
+///
+/// Dropping a transaction without calling [`Transaction::commit`] cancels it on the host.
+pub struct Transaction {
+    inner: call::Transaction,
+}
+
+impl Transaction {
+    pub fn query<'a>(&'a self, query_str: &'a str) -> Query<'a> {
+        Query::new(query_str, Some(&self.inner))
+    }
+
+    pub async fn commit(self) -> Result<()> {
+        call::Transaction::commit(current_parent_context(), self.inner)
+            .await
+            .map_err(|error| anyhow!(error))
+    }
+
+    pub async fn cancel(self) -> Result<()> {
+        call::Transaction::cancel(current_parent_context(), self.inner)
+            .await
+            .map_err(|error| anyhow!(error))
+    }
+}
+
+pub async fn begin() -> Result<Transaction> {
+    let inner = call::Transaction::begin(current_parent_context())
+        .await
+        .map_err(|error| anyhow!(error))?;
+    Ok(Transaction { inner })
+}
@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::ops::Deref;
 
-use chrono::{DateTime, TimeZone, Utc};
-use serde::de::{Error, Visitor};
-use serde::{Deserialize, Deserializer, Serialize};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, WireEncoding};
+
+const DATETIME_TAG: &str = "$surrealdb::datetime";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Datetime(pub DateTime<Utc>);
 
 impl Datetime {
@@ -52,13 +58,37 @@ impl From<std::time::SystemTime> for Datetime {
     }
 }
 
+impl Serialize for Datetime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Datetime {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            let compact = (self.0.timestamp(), self.0.timestamp_subsec_nanos());
+            return wire::serialize_tagged(serializer, wire::DATETIME_TAG, &compact);
+        }
+
+        let value = self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        serialize_tagged_scalar(serializer, DATETIME_TAG, &value)
+    }
+}
+
 struct DatetimeVisitor;
 
-impl Visitor<'_> for DatetimeVisitor {
+impl<'de> Visitor<'de> for DatetimeVisitor {
     type Value = Datetime;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("a RFC3339 datetime string or unix timestamp")
+        formatter.write_str("a tagged datetime, RFC3339 datetime string or unix timestamp")
     }
 
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -98,6 +128,49 @@ impl Visitor<'_> for DatetimeVisitor {
             .map(Datetime)
             .ok_or_else(|| Error::custom("invalid floating point unix timestamp"))
     }
+
+    /// CBOR tags 0, 1, and 12 carry RFC 3339 text, epoch seconds, and `[seconds, nanoseconds]`.
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let seconds: i64 = seq.next_element()?.unwrap_or_default();
+        let nanos: u32 = seq.next_element()?.unwrap_or_default();
+        Utc.timestamp_opt(seconds, nanos)
+            .single()
+            .map(Datetime)
+            .ok_or_else(|| Error::custom("invalid [seconds, nanoseconds] datetime"))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut values: HashMap<String, String> = HashMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            values.insert(key, value);
+        }
+
+        if let Some(value) = values.remove(DATETIME_TAG) {
+            return self.visit_str(&value);
+        }
+        if let Some(value) = values.remove("datetime") {
+            return self.visit_str(&value);
+        }
+        if values.len() == 1 {
+            let (_, value) = values.into_iter().next().expect("single entry map");
+            return self.visit_str(&value);
+        }
+
+        Err(Error::custom("invalid datetime representation"))
+    }
 }
 
 impl<'de> Deserialize<'de> for Datetime {
@@ -108,3 +181,67 @@ impl<'de> Deserialize<'de> for Datetime {
         deserializer.deserialize_any(DatetimeVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use super::Datetime;
+    use crate::types::wire::{self, WireEncoding};
+
+    fn expected() -> Datetime {
+        Datetime::from(Utc.with_ymd_and_hms(2024, 3, 15, 12, 34, 56).unwrap())
+    }
+
+    #[test]
+    fn serializes_as_tagged_map() {
+        let value = serde_json::to_value(expected()).unwrap();
+        assert_eq!(
+            value,
+            json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})
+        );
+    }
+
+    #[test]
+    fn deserializes_tagged_map() {
+        let value: Datetime =
+            serde_json::from_value(json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"}))
+                .unwrap();
+        assert_eq!(value, expected());
+    }
+
+    #[test]
+    fn deserializes_legacy_string() {
+        let value: Datetime = serde_json::from_value(json!("2024-03-15T12:34:56+00:00")).unwrap();
+        assert_eq!(value, expected());
+    }
+
+    #[test]
+    fn round_trips_through_native_tag() {
+        let datetime = Datetime::from(Utc.timestamp_opt(1_710_506_096, 500).unwrap());
+        let bytes = wire::to_vec(&datetime, WireEncoding::Native).unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<serde_cbor::Value>(&bytes).unwrap(),
+            serde_cbor::Value::Tag(
+                12,
+                Box::new(serde_cbor::Value::Array(vec![
+                    serde_cbor::Value::Integer(1_710_506_096),
+                    serde_cbor::Value::Integer(500),
+                ]))
+            )
+        );
+        assert_eq!(
+            serde_cbor::from_slice::<Datetime>(&bytes).unwrap(),
+            datetime
+        );
+    }
+
+    #[test]
+    fn round_trips_through_cbor() {
+        let bytes = serde_cbor::to_vec(&expected()).unwrap();
+        let value: Datetime = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(value, expected());
+    }
+}
@@ -1,13 +1,18 @@
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::fmt;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, Native, WireEncoding};
 
 const DECIMAL_TAG: &str = "$surrealdb::decimal";
 
+/// Decimal kept as its SurrealDB text. The `rust_decimal` feature adds `ExactDecimal` for validated
+/// values with arithmetic and ordering.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Decimal(pub String);
 
@@ -49,11 +54,32 @@ impl From<&str> for Decimal {
     }
 }
 
+impl FromStr for Decimal {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(value.to_string()))
+    }
+}
+
 impl Serialize for Decimal {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Decimal {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(serializer, wire::DECIMAL_TAG, &self.0);
+        }
+
         serialize_tagged_scalar(serializer, DECIMAL_TAG, &self.0)
     }
 }
@@ -68,11 +94,12 @@ impl<'de> Deserialize<'de> for Decimal {
         enum Repr {
             String(String),
             Tagged(HashMap<String, String>),
+            Native(Native<String>),
         }
 
         let repr = Repr::deserialize(deserializer)?;
         match repr {
-            Repr::String(value) => Ok(Decimal(value)),
+            Repr::String(value) | Repr::Native(Native(value)) => Ok(Decimal(value)),
             Repr::Tagged(mut values) => {
                 if let Some(value) = values.remove(DECIMAL_TAG) {
                     return Ok(Decimal(value));
@@ -96,10 +123,26 @@ mod tests {
     use serde_json::json;
 
     use super::Decimal;
+    use crate::types::wire::{self, WireEncoding};
 
     #[test]
     fn serializes_as_tagged_map() {
         let value = serde_json::to_value(Decimal::from("12.34dec")).unwrap();
         assert_eq!(value, json!({"$surrealdb::decimal": "12.34dec"}));
     }
+
+    #[test]
+    fn round_trips_through_native_tag_10() {
+        let decimal = Decimal::from("12.50");
+        let bytes = wire::to_vec(&decimal, WireEncoding::Native).unwrap();
+
+        assert_eq!(&bytes[..1], &[0xca]);
+        assert_eq!(serde_cbor::from_slice::<Decimal>(&bytes).unwrap(), decimal);
+        assert_eq!(
+            wire::to_vec(&[&decimal], WireEncoding::Native)
+                .map(|bytes| serde_cbor::from_slice::<Vec<Decimal>>(&bytes).unwrap())
+                .unwrap(),
+            vec![decimal]
+        );
+    }
 }
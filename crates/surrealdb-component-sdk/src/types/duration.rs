@@ -1,51 +1,266 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::ops::Deref;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+use std::str::FromStr;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, Native, WireEncoding};
 
 const DURATION_TAG: &str = "$surrealdb::duration";
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Duration(pub String);
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+
+/// Units in the order SurrealDB prints them, with their length in nanoseconds.
+const UNITS: [(&str, u128); 10] = [
+    ("y", SECONDS_PER_YEAR as u128 * 1_000_000_000),
+    ("w", SECONDS_PER_WEEK as u128 * 1_000_000_000),
+    ("d", SECONDS_PER_DAY as u128 * 1_000_000_000),
+    ("h", SECONDS_PER_HOUR as u128 * 1_000_000_000),
+    ("m", SECONDS_PER_MINUTE as u128 * 1_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("µs", 1_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// SurrealDB duration such as `1h30m`, stored as seconds and nanoseconds.
+///
+/// Parses every SurrealDB unit from `ns` to `y`, where a year is 365 days, and displays the
+/// canonical form SurrealDB prints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(std::time::Duration);
 
 impl Duration {
-    pub fn into_inner(self) -> String {
+    pub const ZERO: Self = Self(std::time::Duration::ZERO);
+
+    pub const fn new(secs: u64, nanos: u32) -> Self {
+        Self(std::time::Duration::new(secs, nanos))
+    }
+
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(std::time::Duration::from_secs(secs))
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(std::time::Duration::from_millis(millis))
+    }
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(std::time::Duration::from_nanos(nanos))
+    }
+
+    pub const fn as_secs(&self) -> u64 {
+        self.0.as_secs()
+    }
+
+    pub const fn subsec_nanos(&self) -> u32 {
+        self.0.subsec_nanos()
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn into_inner(self) -> std::time::Duration {
         self.0
     }
 }
 
-impl Deref for Duration {
-    type Target = str;
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_zero() {
+            return f.write_str("0ns");
+        }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+        let parts = [
+            (secs / SECONDS_PER_YEAR, "y"),
+            (secs % SECONDS_PER_YEAR / SECONDS_PER_WEEK, "w"),
+            (
+                secs % SECONDS_PER_YEAR % SECONDS_PER_WEEK / SECONDS_PER_DAY,
+                "d",
+            ),
+            (secs % SECONDS_PER_DAY / SECONDS_PER_HOUR, "h"),
+            (secs % SECONDS_PER_HOUR / SECONDS_PER_MINUTE, "m"),
+            (secs % SECONDS_PER_MINUTE, "s"),
+            (u64::from(nanos / 1_000_000), "ms"),
+            (u64::from(nanos / 1_000 % 1_000), "µs"),
+            (u64::from(nanos % 1_000), "ns"),
+        ];
+        for (amount, unit) in parts {
+            if amount > 0 {
+                write!(f, "{amount}{unit}")?;
+            }
+        }
+        Ok(())
     }
 }
 
-impl fmt::Display for Duration {
+/// Failure to parse SurrealDB duration text, pointing at the byte where parsing stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDurationError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseDurationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0)
+        write!(
+            f,
+            "invalid duration at position {}: {}",
+            self.position, self.message
+        )
     }
 }
 
-impl From<Duration> for String {
+impl std::error::Error for ParseDurationError {}
+
+impl FromStr for Duration {
+    type Err = ParseDurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |position: usize, message: &str| ParseDurationError {
+            position,
+            message: message.to_string(),
+        };
+
+        if value.is_empty() {
+            return Err(error(0, "expected a number"));
+        }
+
+        let mut total: u128 = 0;
+        let mut position = 0;
+        while position < value.len() {
+            let rest = &value[position..];
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return Err(error(position, "expected a number"));
+            }
+            let amount: u128 = rest[..digits]
+                .parse()
+                .map_err(|_| error(position, "number is too large"))?;
+            position += digits;
+
+            let rest = &value[position..];
+            let Some((unit, nanos)) = UNITS
+                .iter()
+                .filter(|(unit, _)| rest.starts_with(unit))
+                .max_by_key(|(unit, _)| unit.len())
+            else {
+                return Err(error(position, "expected a unit from ns to y"));
+            };
+            position += unit.len();
+
+            total = amount
+                .checked_mul(*nanos)
+                .and_then(|nanos| total.checked_add(nanos))
+                .ok_or_else(|| error(position, "duration is too large"))?;
+        }
+
+        let secs = u64::try_from(total / 1_000_000_000)
+            .map_err(|_| error(value.len(), "duration is too large"))?;
+        Ok(Self::new(secs, (total % 1_000_000_000) as u32))
+    }
+}
+
+impl TryFrom<&str> for Duration {
+    type Error = ParseDurationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Duration {
+    type Error = ParseDurationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Fails for negative time deltas, which SurrealDB durations cannot represent.
+impl TryFrom<chrono::TimeDelta> for Duration {
+    type Error = chrono::OutOfRangeError;
+
+    fn try_from(value: chrono::TimeDelta) -> Result<Self, Self::Error> {
+        value.to_std().map(Self)
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(value: std::time::Duration) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Duration> for std::time::Duration {
     fn from(value: Duration) -> Self {
         value.0
     }
 }
 
-impl From<String> for Duration {
-    fn from(value: String) -> Self {
-        Self(value)
+impl Add for Duration {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Duration {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+/// Panics when `other` is longer than `self`. Use [`Duration::checked_sub`] to handle it.
+impl Sub for Duration {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self(self.0 - other.0)
     }
 }
 
-impl From<&str> for Duration {
-    fn from(value: &str) -> Self {
-        Self(value.to_string())
+impl SubAssign for Duration {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl Mul<u32> for Duration {
+    type Output = Self;
+
+    fn mul(self, factor: u32) -> Self::Output {
+        Self(self.0 * factor)
+    }
+}
+
+impl Div<u32> for Duration {
+    type Output = Self;
+
+    fn div(self, divisor: u32) -> Self::Output {
+        Self(self.0 / divisor)
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|duration| duration.0).sum())
     }
 }
 
@@ -54,7 +269,40 @@ impl Serialize for Duration {
     where
         S: Serializer,
     {
-        serialize_tagged_scalar(serializer, DURATION_TAG, &self.0)
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Duration {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            let compact = (self.0.as_secs(), self.0.subsec_nanos());
+            return wire::serialize_tagged(serializer, wire::DURATION_TAG, &compact);
+        }
+
+        serialize_tagged_scalar(serializer, DURATION_TAG, &self.to_string())
+    }
+}
+
+impl Duration {
+    /// Reads the `[seconds, nanoseconds]` content of CBOR tag 14, where trailing zeros may be
+    /// left out.
+    pub(crate) fn from_compact<E: serde::de::Error>(parts: &[u64]) -> Result<Self, E> {
+        match parts {
+            [] => Ok(Self::ZERO),
+            [secs] => Ok(Self::from_secs(*secs)),
+            [secs, nanos] => u32::try_from(*nanos)
+                .ok()
+                .filter(|nanos| *nanos < 1_000_000_000)
+                .map(|nanos| Self::new(*secs, nanos))
+                .ok_or_else(|| E::custom("invalid duration nanoseconds")),
+            _ => Err(E::custom(
+                "invalid duration, expected [seconds, nanoseconds]",
+            )),
+        }
     }
 }
 
@@ -68,26 +316,35 @@ impl<'de> Deserialize<'de> for Duration {
         enum Repr {
             String(String),
             Tagged(HashMap<String, String>),
+            Native(Native<NativeRepr>),
+        }
+
+        /// CBOR tag 13 carries duration text, tag 14 `[seconds, nanoseconds]`.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NativeRepr {
+            Text(String),
+            Compact(Vec<u64>),
         }
 
-        let repr = Repr::deserialize(deserializer)?;
-        match repr {
-            Repr::String(value) => Ok(Duration(value)),
+        let value = match Repr::deserialize(deserializer)? {
+            Repr::String(value) | Repr::Native(Native(NativeRepr::Text(value))) => value,
+            Repr::Native(Native(NativeRepr::Compact(parts))) => return Self::from_compact(&parts),
             Repr::Tagged(mut values) => {
                 if let Some(value) = values.remove(DURATION_TAG) {
-                    return Ok(Duration(value));
-                }
-                if let Some(value) = values.remove("duration") {
-                    return Ok(Duration(value));
-                }
-                if values.len() == 1 {
+                    value
+                } else if let Some(value) = values.remove("duration") {
+                    value
+                } else if values.len() == 1 {
                     let (_, value) = values.into_iter().next().expect("single entry map");
-                    return Ok(Duration(value));
+                    value
+                } else {
+                    return Err(serde::de::Error::custom("invalid duration representation"));
                 }
-
-                Err(serde::de::Error::custom("invalid duration representation"))
             }
-        }
+        };
+
+        value.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -95,11 +352,72 @@ impl<'de> Deserialize<'de> for Duration {
 mod tests {
     use serde_json::json;
 
-    use super::Duration;
+    use super::{Duration, ParseDurationError};
+    use crate::types::wire::{self, WireEncoding};
 
     #[test]
     fn serializes_as_tagged_map() {
-        let value = serde_json::to_value(Duration::from("1h30m")).unwrap();
+        let value = serde_json::to_value("1h30m".parse::<Duration>().unwrap()).unwrap();
         assert_eq!(value, json!({"$surrealdb::duration": "1h30m"}));
+        assert_eq!(
+            serde_json::from_value::<Duration>(value).unwrap(),
+            Duration::from_secs(5_400)
+        );
+    }
+
+    #[test]
+    fn round_trips_through_native_tags() {
+        let duration = Duration::new(90, 5);
+        let bytes = wire::to_vec(&duration, WireEncoding::Native).unwrap();
+
+        assert_eq!(&bytes[..2], &[0xce, 0x82]);
+        assert_eq!(
+            serde_cbor::from_slice::<Duration>(&bytes).unwrap(),
+            duration
+        );
+
+        let text = serde_cbor::to_vec(&serde_cbor::Value::Tag(
+            13,
+            Box::new(serde_cbor::Value::Text("1m30s5ns".to_string())),
+        ))
+        .unwrap();
+        assert_eq!(serde_cbor::from_slice::<Duration>(&text).unwrap(), duration);
+    }
+
+    #[test]
+    fn parses_every_unit_and_displays_canonical_form() {
+        let duration: Duration = "1y2w3d4h5m6s7ms8us9ns".parse().unwrap();
+
+        assert_eq!(duration.to_string(), "1y2w3d4h5m6s7ms8µs9ns");
+        assert_eq!(duration.to_string().parse::<Duration>().unwrap(), duration);
+        assert_eq!("90m".parse::<Duration>().unwrap().to_string(), "1h30m");
+        assert_eq!(
+            "1500ms".parse::<Duration>().unwrap(),
+            Duration::from_millis(1_500)
+        );
+        assert_eq!(Duration::ZERO.to_string(), "0ns");
+    }
+
+    #[test]
+    fn reports_the_position_of_invalid_input() {
+        assert_eq!(
+            "1h30".parse::<Duration>(),
+            Err(ParseDurationError {
+                position: 4,
+                message: "expected a unit from ns to y".to_string(),
+            })
+        );
+        assert_eq!("1h x".parse::<Duration>().unwrap_err().position, 2);
+        assert!("".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    fn converts_to_and_from_std_durations() {
+        let duration = Duration::from(std::time::Duration::from_secs(90));
+
+        assert_eq!(std::time::Duration::from(duration).as_secs(), 90);
+        assert_eq!(duration + duration, Duration::from_secs(180));
+        assert_eq!(duration.checked_sub(duration * 2), None);
+        assert!(Duration::try_from(chrono::TimeDelta::seconds(-1)).is_err());
     }
 }
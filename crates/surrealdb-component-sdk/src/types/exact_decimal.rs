@@ -0,0 +1,279 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{
+    Add, AddAssign, Deref, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+use rust_decimal::Decimal as RustDecimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::Decimal;
+
+/// Decimal validated as a `rust_decimal::Decimal`, ordered and compared by value.
+///
+/// Serializes like [`Decimal`]. Deserializing fails when the text is not a valid decimal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExactDecimal(pub RustDecimal);
+
+impl ExactDecimal {
+    /// `num * 10^-scale`, failing when `scale` exceeds 28.
+    pub fn try_new(num: i64, scale: u32) -> Result<Self, rust_decimal::Error> {
+        RustDecimal::try_new(num, scale).map(Self)
+    }
+
+    pub fn into_inner(self) -> RustDecimal {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Self)
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.0.checked_div(other.0).map(Self)
+    }
+
+    pub fn round_dp(self, dp: u32) -> Self {
+        Self(self.0.round_dp(dp))
+    }
+}
+
+/// Parses decimal text, with or without SurrealQL's `dec` suffix, and scientific notation.
+fn parse(value: &str) -> Result<RustDecimal, rust_decimal::Error> {
+    let value = value.strip_suffix("dec").unwrap_or(value);
+    if value.contains(['e', 'E']) {
+        return RustDecimal::from_scientific(value);
+    }
+
+    RustDecimal::from_str(value)
+}
+
+impl Deref for ExactDecimal {
+    type Target = RustDecimal;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for ExactDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for ExactDecimal {
+    type Err = rust_decimal::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse(value).map(Self)
+    }
+}
+
+impl From<RustDecimal> for ExactDecimal {
+    fn from(value: RustDecimal) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ExactDecimal> for RustDecimal {
+    fn from(value: ExactDecimal) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<&Decimal> for ExactDecimal {
+    type Error = rust_decimal::Error;
+
+    fn try_from(value: &Decimal) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<Decimal> for ExactDecimal {
+    type Error = rust_decimal::Error;
+
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+impl From<ExactDecimal> for Decimal {
+    fn from(value: ExactDecimal) -> Self {
+        Self(value.0.to_string())
+    }
+}
+
+impl From<RustDecimal> for Decimal {
+    fn from(value: RustDecimal) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl TryFrom<f64> for ExactDecimal {
+    type Error = rust_decimal::Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        RustDecimal::try_from(value).map(Self)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($integer:ty),* $(,)?) => {
+        $(
+            impl From<$integer> for ExactDecimal {
+                fn from(value: $integer) -> Self {
+                    Self(RustDecimal::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+// Operators panic on overflow, like the integer operators. Use the `checked_*` methods to handle
+// overflow instead.
+macro_rules! impl_operator {
+    ($($trait:ident::$method:ident, $assign_trait:ident::$assign_method:ident);* $(;)?) => {
+        $(
+            impl $trait for ExactDecimal {
+                type Output = Self;
+
+                fn $method(self, other: Self) -> Self::Output {
+                    Self(self.0.$method(other.0))
+                }
+            }
+
+            impl $assign_trait for ExactDecimal {
+                fn $assign_method(&mut self, other: Self) {
+                    self.0.$assign_method(other.0);
+                }
+            }
+        )*
+    };
+}
+
+impl_operator! {
+    Add::add, AddAssign::add_assign;
+    Sub::sub, SubAssign::sub_assign;
+    Mul::mul, MulAssign::mul_assign;
+    Div::div, DivAssign::div_assign;
+    Rem::rem, RemAssign::rem_assign;
+}
+
+impl Neg for ExactDecimal {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl Sum for ExactDecimal {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|value| value.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a ExactDecimal> for ExactDecimal {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        Self(iter.map(|value| value.0).sum())
+    }
+}
+
+impl Serialize for ExactDecimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Decimal::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExactDecimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Decimal::deserialize(deserializer)?;
+        Self::try_from(&value).map_err(|error| {
+            serde::de::Error::custom(format!("invalid decimal `{}`: {error}", value.0))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal as RustDecimal;
+    use serde_json::json;
+
+    use super::ExactDecimal;
+    use crate::types::Decimal;
+    use crate::types::wire::{self, WireEncoding};
+
+    #[test]
+    fn validates_the_text_decimal_and_keeps_its_wire_format() {
+        let price: ExactDecimal = "12.50dec".parse().unwrap();
+
+        assert_eq!(
+            price,
+            ExactDecimal::try_from(Decimal::from("12.5")).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(price).unwrap(),
+            json!({"$surrealdb::decimal": "12.50"})
+        );
+        assert_eq!(
+            wire::to_vec(&price, WireEncoding::Native).unwrap(),
+            wire::to_vec(&Decimal::from("12.50"), WireEncoding::Native).unwrap()
+        );
+        assert_eq!(
+            "1.5e2".parse::<ExactDecimal>().unwrap(),
+            ExactDecimal::from(150)
+        );
+        assert!(ExactDecimal::try_from(Decimal::from("12.5.0")).is_err());
+        assert!(
+            serde_json::from_value::<ExactDecimal>(json!({"$surrealdb::decimal": "abc"})).is_err()
+        );
+    }
+
+    #[test]
+    fn orders_by_value() {
+        let mut values: Vec<ExactDecimal> = ["10", "9.50", "-1", "9.5"]
+            .into_iter()
+            .map(|value| value.parse().unwrap())
+            .collect();
+        values.sort();
+
+        assert_eq!(values[0], ExactDecimal::from(-1));
+        assert_eq!(values[1], values[2]);
+        assert_eq!(values[3], ExactDecimal::from(10));
+    }
+
+    #[test]
+    fn supports_exact_arithmetic() {
+        let tenth = ExactDecimal::try_new(1, 1).unwrap();
+        let total: ExactDecimal = [&tenth, &tenth, &tenth].into_iter().sum();
+
+        assert_eq!(total, ExactDecimal::try_new(3, 1).unwrap());
+        assert_eq!(
+            Decimal::from(total * ExactDecimal::from(10)),
+            Decimal::from("3.0")
+        );
+        assert_eq!(RustDecimal::from(-total), RustDecimal::new(-3, 1));
+        assert_eq!(
+            ExactDecimal::from(i64::MAX).checked_mul(ExactDecimal::from(i64::MAX)),
+            None
+        );
+    }
+}
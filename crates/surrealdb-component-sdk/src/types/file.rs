@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, Native, WireEncoding};
+
+const FILE_TAG: &str = "$surrealdb::file";
+
+/// Reference to a file in a SurrealDB bucket, written `f"bucket:/key"` in SurrealQL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileRef {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl FileRef {
+    /// Creates a reference, adding the leading `/` to `key` when it is missing.
+    pub fn new(bucket: impl Into<String>, key: impl Into<String>) -> Self {
+        let key = key.into();
+        let key = if key.starts_with('/') {
+            key
+        } else {
+            format!("/{key}")
+        };
+
+        Self {
+            bucket: bucket.into(),
+            key,
+        }
+    }
+}
+
+impl fmt::Display for FileRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.bucket, self.key)
+    }
+}
+
+impl FromStr for FileRef {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            Some((bucket, key)) if !bucket.is_empty() && key.starts_with('/') => {
+                Ok(Self::new(bucket, key))
+            }
+            _ => Err(format!(
+                "invalid file reference `{value}`, expected `bucket:/key`"
+            )),
+        }
+    }
+}
+
+impl Serialize for FileRef {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for FileRef {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(serializer, wire::FILE_TAG, &(&self.bucket, &self.key));
+        }
+
+        serialize_tagged_scalar(serializer, FILE_TAG, &self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for FileRef {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            String(String),
+            Tagged(HashMap<String, String>),
+            Native(Native<(String, String)>),
+        }
+
+        let value = match Repr::deserialize(deserializer)? {
+            Repr::String(value) => value,
+            Repr::Native(Native((bucket, key))) => return Ok(Self::new(bucket, key)),
+            Repr::Tagged(mut values) => values
+                .remove(FILE_TAG)
+                .ok_or_else(|| serde::de::Error::custom("invalid file representation"))?,
+        };
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::FileRef;
+    use crate::types::wire::{self, WireEncoding};
+
+    #[test]
+    fn serializes_as_tagged_map() {
+        let file = FileRef::new("avatars", "users/demo.png");
+        let value = serde_json::to_value(&file).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"$surrealdb::file": "avatars:/users/demo.png"})
+        );
+        assert_eq!(serde_json::from_value::<FileRef>(value).unwrap(), file);
+
+        let bytes = wire::to_vec(&file, WireEncoding::Native).unwrap();
+        assert_eq!(&bytes[..3], &[0xd8, 55, 0x82]);
+        assert_eq!(serde_cbor::from_slice::<FileRef>(&bytes).unwrap(), file);
+    }
+}
@@ -1,39 +1,504 @@
 use std::fmt;
-use std::ops::Deref;
 
-use serde::{Deserialize, Serialize};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Geometry(pub serde_json::Value);
+use crate::types::wire::{self, Encode, Native, WireEncoding};
 
-impl Geometry {
-    pub fn into_inner(self) -> serde_json::Value {
-        self.0
+const GEOMETRY_TAG: &str = "$surrealdb::geometry";
+
+/// Position with `x` as longitude and `y` as latitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
     }
 }
 
-impl Deref for Geometry {
-    type Target = serde_json::Value;
+impl From<(f64, f64)> for Point {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::new(x, y)
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl From<[f64; 2]> for Point {
+    fn from([x, y]: [f64; 2]) -> Self {
+        Self::new(x, y)
     }
 }
 
-impl From<serde_json::Value> for Geometry {
-    fn from(value: serde_json::Value) -> Self {
-        Self(value)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineString(pub Vec<Point>);
+
+impl<P: Into<Point>> FromIterator<P> for LineString {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
     }
 }
 
-impl From<Geometry> for serde_json::Value {
-    fn from(value: Geometry) -> Self {
-        value.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub exterior: LineString,
+    pub interiors: Vec<LineString>,
+}
+
+impl Polygon {
+    pub fn new(exterior: LineString, interiors: Vec<LineString>) -> Self {
+        Self {
+            exterior,
+            interiors,
+        }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPoint(pub Vec<Point>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiLineString(pub Vec<LineString>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPolygon(pub Vec<Polygon>);
+
+/// SurrealDB geometry, serialized as GeoJSON inside a `$surrealdb::geometry` tagged map so the
+/// host binds it as a native geometry. Under [`WireEncoding::Native`](crate::WireEncoding::Native)
+/// it uses CBOR tags 88 to 94, with every member point, line, and polygon tagged as well.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Point),
+    LineString(LineString),
+    Polygon(Polygon),
+    MultiPoint(MultiPoint),
+    MultiLineString(MultiLineString),
+    MultiPolygon(MultiPolygon),
+    GeometryCollection(Vec<Geometry>),
+}
+
 impl fmt::Display for Geometry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        let geojson = serde_json::to_value(GeoJson::from(self)).map_err(|_| fmt::Error)?;
+        write!(f, "{geojson}")
+    }
+}
+
+macro_rules! impl_from_geometry {
+    ($($source:ident),* $(,)?) => {
+        $(
+            impl From<$source> for Geometry {
+                fn from(value: $source) -> Self {
+                    Self::$source(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_geometry!(
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+);
+
+impl From<Vec<Geometry>> for Geometry {
+    fn from(value: Vec<Geometry>) -> Self {
+        Self::GeometryCollection(value)
+    }
+}
+
+type Position = [f64; 2];
+
+/// GeoJSON geometry object, the payload of the `$surrealdb::geometry` tag.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum GeoJson {
+    Point {
+        coordinates: Position,
+    },
+    LineString {
+        coordinates: Vec<Position>,
+    },
+    Polygon {
+        coordinates: Vec<Vec<Position>>,
+    },
+    MultiPoint {
+        coordinates: Vec<Position>,
+    },
+    MultiLineString {
+        coordinates: Vec<Vec<Position>>,
+    },
+    MultiPolygon {
+        coordinates: Vec<Vec<Vec<Position>>>,
+    },
+    GeometryCollection {
+        geometries: Vec<GeoJson>,
+    },
+}
+
+fn position(point: &Point) -> Position {
+    [point.x, point.y]
+}
+
+fn line_positions(line: &LineString) -> Vec<Position> {
+    line.0.iter().map(position).collect()
+}
+
+fn polygon_positions(polygon: &Polygon) -> Vec<Vec<Position>> {
+    std::iter::once(&polygon.exterior)
+        .chain(&polygon.interiors)
+        .map(line_positions)
+        .collect()
+}
+
+fn line(positions: Vec<Position>) -> LineString {
+    positions.into_iter().collect()
+}
+
+fn polygon(rings: Vec<Vec<Position>>) -> Polygon {
+    let mut rings = rings.into_iter().map(line);
+    let exterior = rings.next().unwrap_or(LineString(Vec::new()));
+    Polygon::new(exterior, rings.collect())
+}
+
+impl From<&Geometry> for GeoJson {
+    fn from(value: &Geometry) -> Self {
+        match value {
+            Geometry::Point(point) => Self::Point {
+                coordinates: position(point),
+            },
+            Geometry::LineString(line) => Self::LineString {
+                coordinates: line_positions(line),
+            },
+            Geometry::Polygon(polygon) => Self::Polygon {
+                coordinates: polygon_positions(polygon),
+            },
+            Geometry::MultiPoint(points) => Self::MultiPoint {
+                coordinates: points.0.iter().map(position).collect(),
+            },
+            Geometry::MultiLineString(lines) => Self::MultiLineString {
+                coordinates: lines.0.iter().map(line_positions).collect(),
+            },
+            Geometry::MultiPolygon(polygons) => Self::MultiPolygon {
+                coordinates: polygons.0.iter().map(polygon_positions).collect(),
+            },
+            Geometry::GeometryCollection(geometries) => Self::GeometryCollection {
+                geometries: geometries.iter().map(Self::from).collect(),
+            },
+        }
+    }
+}
+
+impl From<GeoJson> for Geometry {
+    fn from(value: GeoJson) -> Self {
+        match value {
+            GeoJson::Point { coordinates } => Self::Point(coordinates.into()),
+            GeoJson::LineString { coordinates } => Self::LineString(line(coordinates)),
+            GeoJson::Polygon { coordinates } => Self::Polygon(polygon(coordinates)),
+            GeoJson::MultiPoint { coordinates } => Self::MultiPoint(MultiPoint(
+                coordinates.into_iter().map(Point::from).collect(),
+            )),
+            GeoJson::MultiLineString { coordinates } => {
+                Self::MultiLineString(MultiLineString(coordinates.into_iter().map(line).collect()))
+            }
+            GeoJson::MultiPolygon { coordinates } => {
+                Self::MultiPolygon(MultiPolygon(coordinates.into_iter().map(polygon).collect()))
+            }
+            GeoJson::GeometryCollection { geometries } => {
+                Self::GeometryCollection(geometries.into_iter().map(Self::from).collect())
+            }
+        }
+    }
+}
+
+/// Native CBOR form of a geometry or of one of its members.
+enum NativeRef<'a> {
+    Point(&'a Point),
+    Line(&'a LineString),
+    Polygon(&'a Polygon),
+    Geometry(&'a Geometry),
+}
+
+impl Serialize for NativeRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fn members<'a, T>(
+            items: &'a [T],
+            member: impl Fn(&'a T) -> NativeRef<'a>,
+        ) -> Vec<NativeRef<'a>> {
+            items.iter().map(member).collect()
+        }
+
+        let (tag, members) = match *self {
+            Self::Point(point) => {
+                return wire::serialize_tagged(
+                    serializer,
+                    wire::GEOMETRY_POINT_TAG,
+                    &position(point),
+                );
+            }
+            Self::Line(line) => (wire::GEOMETRY_LINE_TAG, members(&line.0, NativeRef::Point)),
+            Self::Polygon(polygon) => {
+                let rings = std::iter::once(&polygon.exterior)
+                    .chain(&polygon.interiors)
+                    .map(NativeRef::Line)
+                    .collect();
+                (wire::GEOMETRY_POLYGON_TAG, rings)
+            }
+            Self::Geometry(Geometry::Point(point)) => {
+                return Self::Point(point).serialize(serializer);
+            }
+            Self::Geometry(Geometry::LineString(line)) => {
+                return Self::Line(line).serialize(serializer);
+            }
+            Self::Geometry(Geometry::Polygon(polygon)) => {
+                return Self::Polygon(polygon).serialize(serializer);
+            }
+            Self::Geometry(Geometry::MultiPoint(points)) => (
+                wire::GEOMETRY_MULTIPOINT_TAG,
+                members(&points.0, NativeRef::Point),
+            ),
+            Self::Geometry(Geometry::MultiLineString(lines)) => (
+                wire::GEOMETRY_MULTILINE_TAG,
+                members(&lines.0, NativeRef::Line),
+            ),
+            Self::Geometry(Geometry::MultiPolygon(polygons)) => (
+                wire::GEOMETRY_MULTIPOLYGON_TAG,
+                members(&polygons.0, NativeRef::Polygon),
+            ),
+            Self::Geometry(Geometry::GeometryCollection(geometries)) => (
+                wire::GEOMETRY_COLLECTION_TAG,
+                members(geometries, NativeRef::Geometry),
+            ),
+        };
+        wire::serialize_tagged(serializer, tag, &members)
+    }
+}
+
+type NativeLine = Vec<Native<Position>>;
+
+fn native_line(points: NativeLine) -> LineString {
+    points.into_iter().map(|Native(point)| point).collect()
+}
+
+fn native_polygon(rings: Vec<Native<NativeLine>>) -> Polygon {
+    polygon(
+        rings
+            .into_iter()
+            .map(|Native(ring)| ring.into_iter().map(|Native(point)| point).collect())
+            .collect(),
+    )
+}
+
+/// Decodes the content of native geometry `tag`, which the caller has already read.
+pub(crate) fn deserialize_native<'de, D>(tag: u64, deserializer: D) -> Result<Geometry, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match tag {
+        wire::GEOMETRY_POINT_TAG => Geometry::Point(Position::deserialize(deserializer)?.into()),
+        wire::GEOMETRY_LINE_TAG => {
+            Geometry::LineString(native_line(Deserialize::deserialize(deserializer)?))
+        }
+        wire::GEOMETRY_POLYGON_TAG => {
+            Geometry::Polygon(native_polygon(Deserialize::deserialize(deserializer)?))
+        }
+        wire::GEOMETRY_MULTIPOINT_TAG => {
+            let points: NativeLine = Deserialize::deserialize(deserializer)?;
+            Geometry::MultiPoint(MultiPoint(
+                points
+                    .into_iter()
+                    .map(|Native(point)| point.into())
+                    .collect(),
+            ))
+        }
+        wire::GEOMETRY_MULTILINE_TAG => {
+            let lines: Vec<Native<NativeLine>> = Deserialize::deserialize(deserializer)?;
+            Geometry::MultiLineString(MultiLineString(
+                lines
+                    .into_iter()
+                    .map(|Native(line)| native_line(line))
+                    .collect(),
+            ))
+        }
+        wire::GEOMETRY_MULTIPOLYGON_TAG => {
+            let polygons: Vec<Native<Vec<Native<NativeLine>>>> =
+                Deserialize::deserialize(deserializer)?;
+            Geometry::MultiPolygon(MultiPolygon(
+                polygons
+                    .into_iter()
+                    .map(|Native(rings)| native_polygon(rings))
+                    .collect(),
+            ))
+        }
+        wire::GEOMETRY_COLLECTION_TAG => {
+            Geometry::GeometryCollection(Deserialize::deserialize(deserializer)?)
+        }
+        _ => {
+            return Err(D::Error::custom(format!(
+                "invalid geometry representation, unexpected CBOR tag {tag}"
+            )));
+        }
+    })
+}
+
+impl Serialize for Geometry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Geometry {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return NativeRef::Geometry(self).serialize(serializer);
+        }
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(GEOMETRY_TAG, &GeoJson::from(self))?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Geometry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(rename = "$surrealdb::geometry")]
+            geometry: GeoJson,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Tagged(Tagged),
+            GeoJson(GeoJson),
+        }
+
+        struct GeometryVisitor;
+
+        impl<'de> Visitor<'de> for GeometryVisitor {
+            type Value = Geometry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    formatter,
+                    "a {GEOMETRY_TAG} tagged map, GeoJSON, or a tag 88 to 94 geometry"
+                )
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let tag = serde_cbor::tags::current_cbor_tag().unwrap_or_default();
+                deserialize_native(tag, deserializer)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                match Repr::deserialize(MapAccessDeserializer::new(map)) {
+                    Ok(Repr::Tagged(Tagged { geometry }) | Repr::GeoJson(geometry)) => {
+                        Ok(geometry.into())
+                    }
+                    Err(_) => Err(A::Error::custom(format!(
+                        "invalid geometry representation, expected a {GEOMETRY_TAG} tagged map or GeoJSON"
+                    ))),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(GeometryVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Geometry, LineString, MultiPolygon, Point, Polygon};
+    use crate::types::wire::{self, WireEncoding};
+
+    #[test]
+    fn serializes_as_tagged_geojson() {
+        let geometry = Geometry::Polygon(Polygon::new(
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]
+                .into_iter()
+                .collect(),
+            Vec::new(),
+        ));
+        let value = serde_json::to_value(&geometry).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"$surrealdb::geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]],
+            }})
+        );
+        assert_eq!(serde_json::from_value::<Geometry>(value).unwrap(), geometry);
+    }
+
+    #[test]
+    fn deserializes_plain_geojson_collections_through_cbor() {
+        let bytes = serde_cbor::to_vec(&json!({
+            "type": "GeometryCollection",
+            "geometries": [
+                {"type": "Point", "coordinates": [-0.1, 51]},
+                {"type": "LineString", "coordinates": [[0, 0], [1, 1]]},
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<Geometry>(&bytes).unwrap(),
+            Geometry::GeometryCollection(vec![
+                Geometry::Point(Point::new(-0.1, 51.0)),
+                Geometry::LineString(LineString(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])),
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_native_tags() {
+        let square = Polygon::new(
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]
+                .into_iter()
+                .collect(),
+            Vec::new(),
+        );
+        let geometry = Geometry::GeometryCollection(vec![
+            Geometry::Point(Point::new(-0.1, 51.0)),
+            Geometry::MultiPolygon(MultiPolygon(vec![square])),
+        ]);
+        let bytes = wire::to_vec(&geometry, WireEncoding::Native).unwrap();
+
+        assert_eq!(bytes[..3], [0xd8, 94, 0x82]);
+        assert_eq!(bytes[3..5], [0xd8, 88]);
+        assert_eq!(
+            serde_cbor::from_slice::<Geometry>(&bytes).unwrap(),
+            geometry
+        );
     }
 }
@@ -0,0 +1,172 @@
+use serde::de::{DeserializeOwned, Error};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::Value;
+use crate::types::wire::{self, Encode, WireEncoding};
+
+const NONE_TAG: &str = "$surrealdb::none";
+
+/// Value that keeps SurrealDB's `NONE` apart from `NULL`, for `option<T>` fields.
+///
+/// The host omits object fields that are `NONE`, so mark struct fields `#[serde(default)]` to
+/// read a missing field as [`MaybeNone::None`]. [`MaybeNone::None`] serializes as a
+/// `$surrealdb::none` tagged map, which SurrealDB accepts for `option<T>` fields where it rejects
+/// `NULL`. Add `#[serde(skip_serializing_if = "MaybeNone::is_none")]` to leave the field out
+/// instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MaybeNone<T> {
+    #[default]
+    None,
+    Null,
+    Some(T),
+}
+
+impl<T> MaybeNone<T> {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn is_some(&self) -> bool {
+        matches!(self, Self::Some(_))
+    }
+
+    pub fn as_ref(&self) -> MaybeNone<&T> {
+        match self {
+            Self::None => MaybeNone::None,
+            Self::Null => MaybeNone::Null,
+            Self::Some(value) => MaybeNone::Some(value),
+        }
+    }
+
+    /// The value, treating both `NONE` and `NULL` as absent.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Self::Some(value) => Some(value),
+            Self::None | Self::Null => None,
+        }
+    }
+}
+
+/// Maps `Option::None` to `NONE`, the only empty value SurrealDB accepts for `option<T>`.
+impl<T> From<Option<T>> for MaybeNone<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::None, Self::Some)
+    }
+}
+
+impl<T: Serialize> Serialize for MaybeNone<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::None => serialize_none(serializer),
+            Self::Null => serializer.serialize_unit(),
+            Self::Some(value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// Decodes through [`Value`] to see the `$surrealdb::none` tag, then decodes `T` from the rest.
+impl<'de, T: DeserializeOwned> Deserialize<'de> for MaybeNone<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::None => Ok(Self::None),
+            Value::Null => Ok(Self::Null),
+            value => wire::to_vec(&value, WireEncoding::Legacy)
+                .and_then(|bytes| wire::from_slice(&bytes, WireEncoding::Legacy))
+                .map(Self::Some)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+pub(crate) fn serialize_none<S>(serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    wire::serialize(serializer, &NoneValue)
+}
+
+struct NoneValue;
+
+impl Encode for NoneValue {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(serializer, wire::NONE_TAG, &());
+        }
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(NONE_TAG, &())?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::MaybeNone;
+    use crate::types::Uuid;
+    use crate::types::wire::{self, WireEncoding};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Profile {
+        #[serde(default)]
+        nickname: MaybeNone<String>,
+        #[serde(default)]
+        token: MaybeNone<Uuid>,
+    }
+
+    fn decode(value: serde_json::Value) -> Profile {
+        let bytes = wire::to_vec(&value, WireEncoding::Legacy).unwrap();
+        wire::from_slice(&bytes, WireEncoding::Legacy).unwrap()
+    }
+
+    #[test]
+    fn tells_missing_fields_from_null_and_values() {
+        assert_eq!(
+            decode(json!({"nickname": null})),
+            Profile {
+                nickname: MaybeNone::Null,
+                token: MaybeNone::None,
+            }
+        );
+        assert_eq!(
+            decode(json!({
+                "nickname": {"$surrealdb::none": null},
+                "token": {"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"},
+            })),
+            Profile {
+                nickname: MaybeNone::None,
+                token: MaybeNone::Some("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d".parse().unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn serializes_none_as_tagged_map() {
+        let value = serde_json::to_value(Profile {
+            nickname: MaybeNone::from(None),
+            token: MaybeNone::Null,
+        })
+        .unwrap();
+
+        assert_eq!(
+            value,
+            json!({"nickname": {"$surrealdb::none": null}, "token": null})
+        );
+    }
+}
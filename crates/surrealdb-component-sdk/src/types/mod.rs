@@ -2,17 +2,33 @@ mod bytes;
 mod datetime;
 mod decimal;
 mod duration;
+#[cfg(feature = "rust_decimal")]
+mod exact_decimal;
+mod file;
 mod geometry;
+mod maybe_none;
+mod range;
 mod record_id;
 mod regex;
+mod set;
 mod tagged_scalar;
 mod uuid;
+mod value;
+pub(crate) mod wire;
 
 pub use bytes::*;
 pub use datetime::*;
 pub use decimal::*;
 pub use duration::*;
+#[cfg(feature = "rust_decimal")]
+pub use exact_decimal::*;
+pub use file::*;
 pub use geometry::*;
+pub use maybe_none::*;
+pub use range::*;
 pub use record_id::*;
 pub use regex::*;
+pub use set::*;
 pub use uuid::*;
+pub use value::*;
+pub use wire::WireEncoding;
@@ -0,0 +1,293 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Bound;
+
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::wire::{self, Encode, WireEncoding};
+
+const RANGE_TAG: &str = "$surrealdb::range";
+
+/// SurrealDB range such as `1..10` or `..=5`, serialized as a `$surrealdb::range` tagged map, or
+/// as CBOR tag 49 around tag 50 and 51 bounds under
+/// [`WireEncoding::Native`](crate::WireEncoding::Native).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    pub start: Bound<T>,
+    pub end: Bound<T>,
+}
+
+impl<T> Range<T> {
+    pub fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Range<U> {
+        Range {
+            start: map_bound(self.start, &mut f),
+            end: map_bound(self.end, &mut f),
+        }
+    }
+}
+
+fn map_bound<T, U>(bound: Bound<T>, f: &mut impl FnMut(T) -> U) -> Bound<U> {
+    match bound {
+        Bound::Included(value) => Bound::Included(f(value)),
+        Bound::Excluded(value) => Bound::Excluded(f(value)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<T> From<std::ops::Range<T>> for Range<T> {
+    fn from(value: std::ops::Range<T>) -> Self {
+        Self::new(Bound::Included(value.start), Bound::Excluded(value.end))
+    }
+}
+
+impl<T> From<std::ops::RangeInclusive<T>> for Range<T> {
+    fn from(value: std::ops::RangeInclusive<T>) -> Self {
+        let (start, end) = value.into_inner();
+        Self::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+/// Writes SurrealQL range syntax: `1..5`, `1..=5`, `1>..5`, `..5`, or `..`.
+impl<T: fmt::Display> fmt::Display for Range<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.start {
+            Bound::Included(value) => write!(f, "{value}")?,
+            Bound::Excluded(value) => write!(f, "{value}>")?,
+            Bound::Unbounded => {}
+        }
+        match &self.end {
+            Bound::Included(value) => write!(f, "..={value}"),
+            Bound::Excluded(value) => write!(f, "..{value}"),
+            Bound::Unbounded => write!(f, ".."),
+        }
+    }
+}
+
+/// Wire form of one range bound. An unbounded side is `null`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum WireBound<T> {
+    Included(T),
+    Excluded(T),
+}
+
+fn to_wire<T>(bound: &Bound<T>) -> Option<WireBound<&T>> {
+    match bound {
+        Bound::Included(value) => Some(WireBound::Included(value)),
+        Bound::Excluded(value) => Some(WireBound::Excluded(value)),
+        Bound::Unbounded => None,
+    }
+}
+
+fn from_wire<T>(bound: Option<WireBound<T>>) -> Bound<T> {
+    match bound {
+        Some(WireBound::Included(value)) => Bound::Included(value),
+        Some(WireBound::Excluded(value)) => Bound::Excluded(value),
+        None => Bound::Unbounded,
+    }
+}
+
+#[derive(Serialize)]
+struct WireRangeRef<'a, T> {
+    start: Option<WireBound<&'a T>>,
+    end: Option<WireBound<&'a T>>,
+}
+
+#[derive(Deserialize)]
+struct WireRange<T> {
+    start: Option<WireBound<T>>,
+    end: Option<WireBound<T>>,
+}
+
+/// Native form of one range bound: tag 50 when included, tag 51 when excluded, `null` when
+/// unbounded.
+struct NativeBound<T>(Bound<T>);
+
+impl<T: Serialize> Serialize for NativeBound<&T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Bound::Included(value) => {
+                wire::serialize_tagged(serializer, wire::BOUND_INCLUDED_TAG, value)
+            }
+            Bound::Excluded(value) => {
+                wire::serialize_tagged(serializer, wire::BOUND_EXCLUDED_TAG, value)
+            }
+            Bound::Unbounded => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NativeBound<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BoundVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for BoundVisitor<T> {
+            type Value = NativeBound<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("null or a tag 50 or 51 range bound")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(NativeBound(Bound::Unbounded))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match serde_cbor::tags::current_cbor_tag() {
+                    Some(wire::BOUND_INCLUDED_TAG) => T::deserialize(deserializer)
+                        .map(|value| NativeBound(Bound::Included(value))),
+                    Some(wire::BOUND_EXCLUDED_TAG) => T::deserialize(deserializer)
+                        .map(|value| NativeBound(Bound::Excluded(value))),
+                    _ => Err(D::Error::custom(
+                        "invalid range bound, expected tag 50 or 51",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(BoundVisitor(PhantomData))
+    }
+}
+
+impl<T: Serialize> Serialize for Range<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl<T: Serialize> Encode for Range<T> {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            let bounds = (
+                NativeBound(self.start.as_ref()),
+                NativeBound(self.end.as_ref()),
+            );
+            return wire::serialize_tagged(serializer, wire::RANGE_TAG, &bounds);
+        }
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(
+            RANGE_TAG,
+            &WireRangeRef {
+                start: to_wire(&self.start),
+                end: to_wire(&self.end),
+            },
+        )?;
+        map.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Range<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Tagged<T> {
+            #[serde(rename = "$surrealdb::range")]
+            range: WireRange<T>,
+        }
+
+        struct RangeVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for RangeVisitor<T> {
+            type Value = Range<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a $surrealdb::range tagged map or a tag 49 range")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let (Some(NativeBound(start)), Some(NativeBound(end))) =
+                    (seq.next_element()?, seq.next_element()?)
+                else {
+                    return Err(A::Error::custom(
+                        "invalid range representation, expected [start, end] bounds",
+                    ));
+                };
+                Ok(Range::new(start, end))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let Tagged { range } = Tagged::deserialize(MapAccessDeserializer::new(map))
+                    .map_err(|error| {
+                        A::Error::custom(format!("invalid range representation: {error}"))
+                    })?;
+                Ok(Range::new(from_wire(range.start), from_wire(range.end)))
+            }
+        }
+
+        deserializer.deserialize_any(RangeVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use serde_json::json;
+
+    use super::Range;
+    use crate::types::wire::{self, WireEncoding};
+
+    #[test]
+    fn round_trips_through_tagged_map() {
+        let range = Range::new(Bound::Included(1), Bound::Unbounded);
+        let value = serde_json::to_value(&range).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"$surrealdb::range": {"start": {"included": 1}, "end": null}})
+        );
+        assert_eq!(serde_json::from_value::<Range<i64>>(value).unwrap(), range);
+
+        let bytes = wire::to_vec(&range, WireEncoding::Native).unwrap();
+        assert_eq!(bytes, [0xd8, 49, 0x82, 0xd8, 50, 0x01, 0xf6]);
+        assert_eq!(serde_cbor::from_slice::<Range<i64>>(&bytes).unwrap(), range);
+        assert_eq!(
+            Range::from(1..5),
+            Range::new(Bound::Included(1), Bound::Excluded(5))
+        );
+        assert_eq!(Range::from(1..=5).to_string(), "1..=5");
+        assert_eq!(range.to_string(), "1..");
+        assert_eq!(
+            Range::new(Bound::Excluded(1), Bound::Excluded(5)).to_string(),
+            "1>..5"
+        );
+    }
+}
@@ -1,13 +1,20 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
-use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::uuid::CBOR_UUID_TAG;
+use crate::types::wire::{self, Encode, WireEncoding};
+use crate::types::{Range, Uuid};
 
 const UUID_TAG: &str = "$surrealdb::uuid";
+const RECORD_ID_TAG: &str = "$surrealdb::record_id";
 
 // =============================================================================
 // Display helpers
@@ -31,24 +38,31 @@ fn fmt_string_key(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     if is_simple_id(s) {
         write!(f, "{s}")
     } else {
-        write!(f, "`{s}`")
+        fmt_quoted(s, '`', f)
     }
 }
 
-/// Format a string as a record ID value: single-quoted with internal single quotes escaped.
+/// Format a string as a record ID value: single-quoted.
 fn fmt_string_value(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "'")?;
+    fmt_quoted(s, '\'', f)
+}
+
+/// Wrap a string in `quote`, escaping the quote and backslashes with a backslash.
+fn fmt_quoted(s: &str, quote: char, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{quote}")?;
     for c in s.chars() {
-        if c == '\'' {
-            write!(f, "\\'")?;
-        } else {
-            write!(f, "{c}")?;
+        if c == quote || c == '\\' {
+            write!(f, "\\")?;
         }
+        write!(f, "{c}")?;
     }
-    write!(f, "'")
+    write!(f, "{quote}")
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+/// Record id such as `person:tobie`, serialized as a `$surrealdb::record_id` tagged
+/// `{ table, key }` map, or as CBOR tag 8 around `[table, key]` under
+/// [`WireEncoding::Native`](crate::WireEncoding::Native).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RecordId {
     pub table: String,
     pub key: RecordIdKey,
@@ -63,6 +77,49 @@ impl RecordId {
     }
 }
 
+impl Serialize for RecordId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for RecordId {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(
+                serializer,
+                wire::RECORD_ID_TAG,
+                &(&self.table, &self.key),
+            );
+        }
+
+        serialize_record_id_map(serializer, &self.table, &self.key)
+    }
+}
+
+/// Fields of a `$surrealdb::record_id` tagged map.
+#[derive(Serialize)]
+struct RecordIdFields<'a, K> {
+    table: &'a str,
+    key: &'a K,
+}
+
+fn serialize_record_id_map<S, K>(serializer: S, table: &str, key: &K) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize,
+{
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(RECORD_ID_TAG, &RecordIdFields { table, key })?;
+    map.end()
+}
+
 impl fmt::Display for RecordId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt_string_key(&self.table, f)?;
@@ -136,12 +193,25 @@ impl Hash for RecordIdKey {
 
 impl Serialize for RecordIdKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for RecordIdKey {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         match self {
             Self::Number(value) => value.serialize(serializer),
             Self::String(value) => value.serialize(serializer),
+            Self::Uuid(value) if encoding == WireEncoding::Native => match value.parse::<Uuid>() {
+                Ok(uuid) => uuid.serialize(serializer),
+                Err(_) => wire::serialize_tagged(serializer, wire::UUID_TEXT_TAG, value),
+            },
             Self::Uuid(value) => serialize_tagged_scalar(serializer, UUID_TAG, value),
             Self::Array(value) => value.serialize(serializer),
             Self::Object(value) => value.serialize(serializer),
@@ -419,155 +489,363 @@ impl<'de> Deserialize<'de> for RecordIdValue {
     }
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TaggedValue {
+    String(String),
+    Number(i64),
+    Uuid(String),
+    Array(Vec<RecordIdValue>),
+    Object(HashMap<String, RecordIdValue>),
+    Bool(bool),
+    Float(f64),
+    Null,
+}
+
+impl TaggedValue {
+    fn into_record_value(self) -> RecordIdValue {
+        match self {
+            Self::String(v) => RecordIdValue::String(v),
+            Self::Number(v) => RecordIdValue::Number(v),
+            Self::Uuid(v) => RecordIdValue::String(v),
+            Self::Array(v) => RecordIdValue::Array(v),
+            Self::Object(v) => RecordIdValue::Object(v),
+            Self::Bool(v) => RecordIdValue::Bool(v),
+            Self::Float(v) => RecordIdValue::Float(v),
+            Self::Null => RecordIdValue::Null,
+        }
+    }
+}
+
+struct RecordIdKeyVisitor;
+
+impl<'de> Visitor<'de> for RecordIdKeyVisitor {
+    type Value = RecordIdKey;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a valid record id key")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RecordIdKey::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(RecordIdKey::String(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(RecordIdKey::Number(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(RecordIdKey::Number(value as i64))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match serde_cbor::tags::current_cbor_tag() {
+            Some(CBOR_UUID_TAG | wire::UUID_TEXT_TAG) => {
+                Uuid::deserialize(deserializer).map(|uuid| RecordIdKey::Uuid(uuid.to_string()))
+            }
+            _ => deserializer.deserialize_any(self),
+        }
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element::<RecordIdValue>()? {
+            values.push(value);
+        }
+        Ok(RecordIdKey::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut fields: HashMap<String, TaggedValue> = HashMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            fields.insert(key, value);
+        }
+
+        if fields.len() == 1 {
+            let (key, value) = fields.into_iter().next().expect("single entry map");
+            return Ok(match (key.as_str(), value) {
+                ("String", TaggedValue::String(v)) => RecordIdKey::String(v),
+                ("Number", TaggedValue::Number(v)) => RecordIdKey::Number(v),
+                ("Integer", TaggedValue::Number(v)) => RecordIdKey::Number(v),
+                ("Uuid", TaggedValue::Uuid(v)) => RecordIdKey::Uuid(v),
+                ("$surrealdb::uuid", TaggedValue::String(v)) => RecordIdKey::Uuid(v),
+                ("uuid", TaggedValue::String(v)) => RecordIdKey::Uuid(v),
+                ("Array", TaggedValue::Array(v)) => RecordIdKey::Array(v),
+                ("Object", TaggedValue::Object(v)) => RecordIdKey::Object(v),
+                (tag, other) => {
+                    let mut object = HashMap::new();
+                    object.insert(tag.to_string(), other.into_record_value());
+                    RecordIdKey::Object(object)
+                }
+            });
+        }
+
+        let mapped = fields
+            .into_iter()
+            .map(|(k, v)| (k, v.into_record_value()))
+            .collect();
+        Ok(RecordIdKey::Object(mapped))
+    }
+}
+
 impl<'de> Deserialize<'de> for RecordIdKey {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
+        deserializer.deserialize_any(RecordIdKeyVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct CanonicalRecordId {
+            table: String,
+            key: RecordIdKey,
+        }
+
+        #[derive(Deserialize)]
+        struct LegacyRecordId {
+            tb: String,
+            id: RecordIdKey,
+        }
+
+        #[derive(Deserialize)]
+        struct TaggedRecordId {
+            #[serde(rename = "$surrealdb::record_id")]
+            record_id: CanonicalRecordId,
+        }
+
         #[derive(Deserialize)]
         #[serde(untagged)]
-        enum TaggedValue {
-            String(String),
-            Number(i64),
-            Uuid(String),
-            Array(Vec<RecordIdValue>),
-            Object(HashMap<String, RecordIdValue>),
-            Bool(bool),
-            Float(f64),
-            Null,
-        }
-
-        impl TaggedValue {
-            fn into_record_value(self) -> RecordIdValue {
-                match self {
-                    Self::String(v) => RecordIdValue::String(v),
-                    Self::Number(v) => RecordIdValue::Number(v),
-                    Self::Uuid(v) => RecordIdValue::String(v),
-                    Self::Array(v) => RecordIdValue::Array(v),
-                    Self::Object(v) => RecordIdValue::Object(v),
-                    Self::Bool(v) => RecordIdValue::Bool(v),
-                    Self::Float(v) => RecordIdValue::Float(v),
-                    Self::Null => RecordIdValue::Null,
+        enum RecordIdInner {
+            Tagged(TaggedRecordId),
+            Canonical(CanonicalRecordId),
+            Legacy(LegacyRecordId),
+        }
+
+        struct RecordIdVisitor;
+
+        impl<'de> Visitor<'de> for RecordIdVisitor {
+            type Value = RecordId;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a $surrealdb::record_id tagged map or a tag 8 record id")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match NativeRecord::deserialize(deserializer)? {
+                    NativeRecord::Id(id) => Ok(id),
+                    NativeRecord::Range(_) => Err(D::Error::custom(
+                        "expected a record id, found a record id range",
+                    )),
+                }
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                match RecordIdInner::deserialize(MapAccessDeserializer::new(map))? {
+                    RecordIdInner::Tagged(TaggedRecordId { record_id: inner })
+                    | RecordIdInner::Canonical(inner) => Ok(RecordId {
+                        table: inner.table,
+                        key: inner.key,
+                    }),
+                    RecordIdInner::Legacy(inner) => Ok(RecordId {
+                        table: inner.tb,
+                        key: inner.id,
+                    }),
                 }
             }
         }
 
-        struct RecordIdKeyVisitor;
+        deserializer.deserialize_any(RecordIdVisitor)
+    }
+}
+
+/// Table name of a native record id, as text or as a tag 7 table.
+struct TableName(String);
+
+impl<'de> Deserialize<'de> for TableName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TableNameVisitor;
 
-        impl<'de> Visitor<'de> for RecordIdKeyVisitor {
-            type Value = RecordIdKey;
+        impl<'de> Visitor<'de> for TableNameVisitor {
+            type Value = TableName;
 
             fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.write_str("a valid record id key")
+                formatter.write_str("a table name")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(TableName(value.to_string()))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+                Ok(TableName(value))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_any(TableNameVisitor)
+    }
+}
+
+/// Key of a native record id, which is a tag 49 range for record id ranges.
+enum NativeKey {
+    Key(RecordIdKey),
+    Range(Range<RecordIdKey>),
+}
+
+impl<'de> Deserialize<'de> for NativeKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NativeKeyVisitor;
+
+        impl<'de> Visitor<'de> for NativeKeyVisitor {
+            type Value = NativeKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a record id key or a tag 49 range")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                if serde_cbor::tags::current_cbor_tag() == Some(wire::RANGE_TAG) {
+                    return Range::deserialize(deserializer).map(NativeKey::Range);
+                }
+                RecordIdKeyVisitor
+                    .visit_newtype_struct(deserializer)
+                    .map(NativeKey::Key)
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
             where
-                E: serde::de::Error,
+                E: Error,
             {
-                Ok(RecordIdKey::String(value.to_string()))
+                RecordIdKeyVisitor.visit_str(value).map(NativeKey::Key)
             }
 
-            fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
-                Ok(RecordIdKey::String(value))
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                RecordIdKeyVisitor.visit_string(value).map(NativeKey::Key)
             }
 
-            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
-                Ok(RecordIdKey::Number(value))
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                RecordIdKeyVisitor.visit_i64(value).map(NativeKey::Key)
             }
 
             fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
             where
-                E: serde::de::Error,
+                E: Error,
             {
-                Ok(RecordIdKey::Number(value as i64))
+                RecordIdKeyVisitor.visit_u64(value).map(NativeKey::Key)
             }
 
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
             where
                 A: SeqAccess<'de>,
             {
-                let mut values = Vec::new();
-                while let Some(value) = seq.next_element::<RecordIdValue>()? {
-                    values.push(value);
-                }
-                Ok(RecordIdKey::Array(values))
+                RecordIdKeyVisitor.visit_seq(seq).map(NativeKey::Key)
             }
 
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
             where
                 A: MapAccess<'de>,
             {
-                let mut fields: HashMap<String, TaggedValue> = HashMap::new();
-                while let Some((key, value)) = map.next_entry()? {
-                    fields.insert(key, value);
-                }
-
-                if fields.len() == 1 {
-                    let (key, value) = fields.into_iter().next().expect("single entry map");
-                    return Ok(match (key.as_str(), value) {
-                        ("String", TaggedValue::String(v)) => RecordIdKey::String(v),
-                        ("Number", TaggedValue::Number(v)) => RecordIdKey::Number(v),
-                        ("Integer", TaggedValue::Number(v)) => RecordIdKey::Number(v),
-                        ("Uuid", TaggedValue::Uuid(v)) => RecordIdKey::Uuid(v),
-                        ("$surrealdb::uuid", TaggedValue::String(v)) => RecordIdKey::Uuid(v),
-                        ("uuid", TaggedValue::String(v)) => RecordIdKey::Uuid(v),
-                        ("Array", TaggedValue::Array(v)) => RecordIdKey::Array(v),
-                        ("Object", TaggedValue::Object(v)) => RecordIdKey::Object(v),
-                        (tag, other) => {
-                            let mut object = HashMap::new();
-                            object.insert(tag.to_string(), other.into_record_value());
-                            RecordIdKey::Object(object)
-                        }
-                    });
-                }
-
-                let mapped = fields
-                    .into_iter()
-                    .map(|(k, v)| (k, v.into_record_value()))
-                    .collect();
-                Ok(RecordIdKey::Object(mapped))
+                RecordIdKeyVisitor.visit_map(map).map(NativeKey::Key)
             }
         }
 
-        deserializer.deserialize_any(RecordIdKeyVisitor)
+        deserializer.deserialize_any(NativeKeyVisitor)
     }
 }
 
-impl<'de> Deserialize<'de> for RecordId {
+/// Content of a tag 8 record id, `[table, key]`, which is a record id range when the key is a
+/// tag 49 range.
+pub(crate) enum NativeRecord {
+    Id(RecordId),
+    Range(RecordIdRange),
+}
+
+impl<'de> Deserialize<'de> for NativeRecord {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        struct CanonicalRecordId {
-            table: String,
-            key: RecordIdKey,
-        }
+        struct NativeRecordVisitor;
 
-        #[derive(Deserialize)]
-        struct LegacyRecordId {
-            tb: String,
-            id: RecordIdKey,
-        }
+        impl<'de> Visitor<'de> for NativeRecordVisitor {
+            type Value = NativeRecord;
 
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum RecordIdInner {
-            Canonical(CanonicalRecordId),
-            Legacy(LegacyRecordId),
-        }
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a [table, key] record id")
+            }
 
-        match RecordIdInner::deserialize(deserializer)? {
-            RecordIdInner::Canonical(inner) => Ok(Self {
-                table: inner.table,
-                key: inner.key,
-            }),
-            RecordIdInner::Legacy(inner) => Ok(Self {
-                table: inner.tb,
-                key: inner.id,
-            }),
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let (Some(TableName(table)), Some(key)) =
+                    (seq.next_element()?, seq.next_element()?)
+                else {
+                    return Err(A::Error::custom(
+                        "invalid record id representation, expected [table, key]",
+                    ));
+                };
+                Ok(match key {
+                    NativeKey::Key(key) => NativeRecord::Id(RecordId { table, key }),
+                    NativeKey::Range(range) => NativeRecord::Range(RecordIdRange { table, range }),
+                })
+            }
         }
+
+        deserializer.deserialize_any(NativeRecordVisitor)
     }
 }
 
@@ -601,17 +879,525 @@ impl From<HashMap<String, RecordIdValue>> for RecordIdKey {
     }
 }
 
-impl From<crate::types::Uuid> for RecordIdKey {
-    fn from(value: crate::types::Uuid) -> Self {
-        Self::Uuid(value.into_inner())
+impl From<Uuid> for RecordIdKey {
+    fn from(value: Uuid) -> Self {
+        Self::Uuid(value.to_string())
+    }
+}
+
+/// Range of record ids in one table, such as `person:1..5`.
+///
+/// Serializes as a `$surrealdb::record_id` tagged map whose key is a `$surrealdb::range` tagged
+/// map, or as tag 8 around a tag 49 range under
+/// [`WireEncoding::Native`](crate::WireEncoding::Native), so binding it lets `SELECT * FROM $ids`
+/// scan the range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecordIdRange {
+    pub table: String,
+    pub range: Range<RecordIdKey>,
+}
+
+impl Serialize for RecordIdRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for RecordIdRange {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(
+                serializer,
+                wire::RECORD_ID_TAG,
+                &(&self.table, &self.range),
+            );
+        }
+
+        serialize_record_id_map(serializer, &self.table, &self.range)
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordIdRange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Fields {
+            table: String,
+            key: Range<RecordIdKey>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Tagged {
+                #[serde(rename = "$surrealdb::record_id")]
+                record_id: Fields,
+            },
+            Plain(Fields),
+        }
+
+        struct RecordIdRangeVisitor;
+
+        impl<'de> Visitor<'de> for RecordIdRangeVisitor {
+            type Value = RecordIdRange;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(
+                    "a $surrealdb::record_id tagged map with a range key or a tag 8 record id",
+                )
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match NativeRecord::deserialize(deserializer)? {
+                    NativeRecord::Range(range) => Ok(range),
+                    NativeRecord::Id(_) => Err(D::Error::custom(
+                        "expected a record id range, found a record id",
+                    )),
+                }
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let (Repr::Tagged {
+                    record_id: Fields { table, key },
+                }
+                | Repr::Plain(Fields { table, key })) =
+                    Repr::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(RecordIdRange { table, range: key })
+            }
+        }
+
+        deserializer.deserialize_any(RecordIdRangeVisitor)
+    }
+}
+
+impl RecordIdRange {
+    pub fn new<K: Into<RecordIdKey>>(table: impl Into<String>, range: impl Into<Range<K>>) -> Self {
+        Self {
+            table: table.into(),
+            range: range.into().map(Into::into),
+        }
+    }
+}
+
+impl fmt::Display for RecordIdRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_string_key(&self.table, f)?;
+        write!(f, ":{}", self.range)
+    }
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+/// Failure to parse a record id, pointing at the byte where parsing stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecordIdError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseRecordIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid record id at position {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseRecordIdError {}
+
+/// Parses `table:key` in every form `Display` produces.
+impl FromStr for RecordId {
+    type Err = ParseRecordIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(value);
+        let table = parser.ident("a table name")?;
+        if !parser.eat(':') {
+            return Err(parser.error("expected `:` after the table name"));
+        }
+        let key = parser.key()?;
+        parser.finish()?;
+        Ok(Self { table, key })
+    }
+}
+
+/// Parses a key on its own, such as `42`, `` `a b` ``, `u'...'`, `[...]`, or `{...}`.
+impl FromStr for RecordIdKey {
+    type Err = ParseRecordIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(value);
+        let key = parser.key()?;
+        parser.finish()?;
+        Ok(key)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseRecordIdError {
+        ParseRecordIdError {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn finish(&mut self) -> Result<(), ParseRecordIdError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected trailing input")),
+        }
+    }
+
+    /// Takes the longest run of characters accepted by `accept`, given the char index.
+    fn take_while(&mut self, accept: impl Fn(usize, char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|(index, c)| !accept(*index, *c))
+            .map_or(rest.len(), |(index, _)| index);
+        self.position += len;
+        &rest[..len]
+    }
+
+    /// Reads text up to the closing `quote`, undoing backslash escapes.
+    fn quoted(&mut self, quote: char) -> Result<String, ParseRecordIdError> {
+        let start = self.position;
+        self.position += quote.len_utf8();
+
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            let c = match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => escaped,
+                    None => break,
+                },
+                c if c == quote => {
+                    self.position += index + quote.len_utf8();
+                    return Ok(value);
+                }
+                c => c,
+            };
+            value.push(c);
+        }
+
+        self.position = start;
+        Err(self.error(format!("unterminated {quote} quote")))
+    }
+
+    /// Table name or object key: a simple identifier or backtick-quoted text.
+    fn ident(&mut self, expected: &str) -> Result<String, ParseRecordIdError> {
+        if self.peek() == Some('`') {
+            return self.quoted('`');
+        }
+
+        let ident = self.take_while(|_, c| c.is_ascii_alphanumeric() || c == '_');
+        if ident.is_empty() {
+            return Err(self.error(format!("expected {expected}")));
+        }
+        Ok(ident.to_string())
+    }
+
+    fn key(&mut self) -> Result<RecordIdKey, ParseRecordIdError> {
+        match self.peek() {
+            Some('`') => return self.quoted('`').map(RecordIdKey::String),
+            Some('[') => return self.array().map(RecordIdKey::Array),
+            Some('{') => return self.object().map(RecordIdKey::Object),
+            Some('u') if self.rest().starts_with("u'") => {
+                self.position += 1;
+                let start = self.position + 1;
+                let value = self.quoted('\'')?;
+                return match value.parse::<Uuid>() {
+                    Ok(_) => Ok(RecordIdKey::Uuid(value)),
+                    Err(error) => Err(ParseRecordIdError {
+                        position: start + error.position,
+                        message: error.message,
+                    }),
+                };
+            }
+            _ => {}
+        }
+
+        let start = self.position;
+        let token = self.take_while(|index, c| {
+            c.is_ascii_alphanumeric() || c == '_' || (index == 0 && c == '-')
+        });
+        if let Ok(value) = token.parse::<i64>() {
+            return Ok(RecordIdKey::Number(value));
+        }
+        if token.is_empty() || token.starts_with('-') {
+            self.position = start;
+            return Err(self.error("expected a record key"));
+        }
+        Ok(RecordIdKey::String(token.to_string()))
+    }
+
+    fn array(&mut self) -> Result<Vec<RecordIdValue>, ParseRecordIdError> {
+        self.eat('[');
+        let mut values = Vec::new();
+        if self.eat(']') {
+            return Ok(values);
+        }
+
+        loop {
+            values.push(self.value()?);
+            if self.eat(']') {
+                return Ok(values);
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected `,` or `]`"));
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<HashMap<String, RecordIdValue>, ParseRecordIdError> {
+        self.eat('{');
+        let mut values = HashMap::new();
+        if self.eat('}') {
+            return Ok(values);
+        }
+
+        loop {
+            let key = self.ident("an object key")?;
+            if !self.eat(':') {
+                return Err(self.error("expected `:` after the object key"));
+            }
+            values.insert(key, self.value()?);
+            if self.eat('}') {
+                return Ok(values);
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected `,` or `}`"));
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<RecordIdValue, ParseRecordIdError> {
+        match self.peek() {
+            Some('\'') => return self.quoted('\'').map(RecordIdValue::String),
+            Some('[') => return self.array().map(RecordIdValue::Array),
+            Some('{') => return self.object().map(RecordIdValue::Object),
+            _ => {}
+        }
+
+        let start = self.position;
+        let token = self.take_while(|index, c| {
+            c.is_ascii_alphanumeric() || c == '_' || c == '.' || (index == 0 && c == '-')
+        });
+        let value = match token {
+            "NONE" | "NULL" => Some(RecordIdValue::Null),
+            "true" => Some(RecordIdValue::Bool(true)),
+            "false" => Some(RecordIdValue::Bool(false)),
+            _ => match token.strip_suffix('f') {
+                Some(float) => float.parse().ok().map(RecordIdValue::Float),
+                None => token.parse().ok().map(RecordIdValue::Number),
+            },
+        };
+
+        value.ok_or_else(|| {
+            self.position = start;
+            if token.is_empty() {
+                self.error("expected a value")
+            } else {
+                self.error(format!("invalid value `{token}`"))
+            }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Bound;
+
     use serde_json::json;
 
-    use super::{RecordId, RecordIdKey, RecordIdValue};
+    use proptest::prelude::*;
+
+    use super::{ParseRecordIdError, RecordId, RecordIdKey, RecordIdRange, RecordIdValue};
+    use crate::types::wire::{self, WireEncoding};
+    use crate::types::{Range, Uuid};
+
+    fn record_id_value() -> impl Strategy<Value = RecordIdValue> {
+        let leaf = prop_oneof![
+            Just(RecordIdValue::Null),
+            any::<bool>().prop_map(RecordIdValue::Bool),
+            any::<i64>().prop_map(RecordIdValue::Number),
+            any::<f64>()
+                .prop_filter("NaN never equals itself", |value| !value.is_nan())
+                .prop_map(RecordIdValue::Float),
+            any::<String>().prop_map(RecordIdValue::String),
+        ];
+        leaf.prop_recursive(3, 24, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(RecordIdValue::Array),
+                prop::collection::hash_map(any::<String>(), inner, 0..4)
+                    .prop_map(RecordIdValue::Object),
+            ]
+        })
+    }
+
+    fn record_id_key() -> impl Strategy<Value = RecordIdKey> {
+        prop_oneof![
+            any::<i64>().prop_map(RecordIdKey::Number),
+            any::<String>().prop_map(RecordIdKey::String),
+            "[a-z0-9_]{1,12}".prop_map(RecordIdKey::String),
+            any::<[u8; 16]>().prop_map(|bytes| RecordIdKey::from(Uuid::from_bytes(bytes))),
+            prop::collection::vec(record_id_value(), 0..4).prop_map(RecordIdKey::Array),
+            prop::collection::hash_map(any::<String>(), record_id_value(), 0..4)
+                .prop_map(RecordIdKey::Object),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn parses_what_display_produces(table in any::<String>(), key in record_id_key()) {
+            let id = RecordId::new(table, key);
+
+            prop_assert_eq!(id.to_string().parse::<RecordId>(), Ok(id.clone()));
+            prop_assert_eq!(id.key.to_string().parse::<RecordIdKey>(), Ok(id.key));
+        }
+    }
+
+    #[test]
+    fn serializes_record_id_ranges_with_tagged_range_keys() {
+        let ids = RecordIdRange::new("person", 1_i64..=5);
+        let value = serde_json::to_value(&ids).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"$surrealdb::record_id": {"table": "person", "key": {"$surrealdb::range": {
+                "start": {"included": 1},
+                "end": {"included": 5},
+            }}}})
+        );
+        assert_eq!(serde_json::from_value::<RecordIdRange>(value).unwrap(), ids);
+        assert_eq!(ids.to_string(), "person:1..=5");
+    }
+
+    #[test]
+    fn round_trips_through_native_tag_8() {
+        let id = RecordId::new(
+            "person",
+            RecordIdKey::uuid("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"),
+        );
+        let ids = RecordIdRange::new("person", 1_i64..=5);
+        let id_bytes = wire::to_vec(&id, WireEncoding::Native).unwrap();
+        let ids_bytes = wire::to_vec(&ids, WireEncoding::Native).unwrap();
+
+        assert_eq!(
+            id_bytes[..10],
+            [0xc8, 0x82, 0x66, b'p', b'e', b'r', b's', b'o', b'n', 0xd8]
+        );
+        assert_eq!(id_bytes[10], 37);
+        assert_eq!(serde_cbor::from_slice::<RecordId>(&id_bytes).unwrap(), id);
+        assert_eq!(
+            ids_bytes[9..],
+            [0xd8, 49, 0x82, 0xd8, 50, 0x01, 0xd8, 50, 0x05]
+        );
+        assert_eq!(
+            serde_cbor::from_slice::<RecordIdRange>(&ids_bytes).unwrap(),
+            ids
+        );
+        assert!(serde_cbor::from_slice::<RecordId>(&ids_bytes).is_err());
+    }
+
+    #[test]
+    fn parses_record_id_text() {
+        assert_eq!(
+            "person:tobie".parse::<RecordId>().unwrap(),
+            RecordId::new("person", "tobie")
+        );
+        assert_eq!(
+            "person:-42".parse::<RecordId>().unwrap(),
+            RecordId::new("person", -42_i64)
+        );
+        assert_eq!(
+            "`user data`:`a\\`b`".parse::<RecordId>().unwrap(),
+            RecordId::new("user data", "a`b")
+        );
+        assert_eq!(
+            "temperature:['london', 1.5f, {at: NONE, ok: true}]"
+                .parse::<RecordId>()
+                .unwrap(),
+            RecordId::new(
+                "temperature",
+                RecordIdKey::array([
+                    RecordIdValue::string("london"),
+                    RecordIdValue::float(1.5),
+                    RecordIdValue::object([
+                        ("at", RecordIdValue::null()),
+                        ("ok", RecordIdValue::bool(true)),
+                    ]),
+                ]),
+            )
+        );
+    }
+
+    #[test]
+    fn reports_the_position_of_invalid_record_ids() {
+        let error = |text: &str| text.parse::<RecordId>().unwrap_err();
+
+        assert_eq!(
+            error("person"),
+            ParseRecordIdError {
+                position: 6,
+                message: "expected `:` after the table name".to_string(),
+            }
+        );
+        assert_eq!(error("person:[1, 2").position, 12);
+        assert_eq!(error("person:{a: 'x}").position, 11);
+        assert_eq!(
+            error("person:u'018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4x'").position,
+            44
+        );
+        assert_eq!(error("person:1 2").position, 9);
+        assert_eq!(error(":1").position, 0);
+    }
 
     #[test]
     fn serializes_uuid_keys_as_tagged_maps() {
@@ -629,13 +1415,19 @@ mod tests {
     #[test]
     fn record_id_new_accepts_string_key() {
         let value = serde_json::to_value(RecordId::new("person", "demo")).unwrap();
-        assert_eq!(value, json!({"table": "person", "key": "demo"}));
+        assert_eq!(
+            value,
+            json!({"$surrealdb::record_id": {"table": "person", "key": "demo"}})
+        );
     }
 
     #[test]
     fn record_id_new_accepts_number_key() {
         let value = serde_json::to_value(RecordId::new("person", 42_i64)).unwrap();
-        assert_eq!(value, json!({"table": "person", "key": 42}));
+        assert_eq!(
+            value,
+            json!({"$surrealdb::record_id": {"table": "person", "key": 42}})
+        );
     }
 
     #[test]
@@ -647,10 +1439,10 @@ mod tests {
         .unwrap();
         assert_eq!(
             value,
-            json!({
+            json!({"$surrealdb::record_id": {
                 "table": "person",
                 "key": {"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"}
-            })
+            }})
         );
     }
 
@@ -661,7 +1453,10 @@ mod tests {
             RecordIdKey::array([RecordIdValue::string("tenant-a"), RecordIdValue::number(42)]),
         ))
         .unwrap();
-        assert_eq!(value, json!({"table": "person", "key": ["tenant-a", 42]}));
+        assert_eq!(
+            value,
+            json!({"$surrealdb::record_id": {"table": "person", "key": ["tenant-a", 42]}})
+        );
     }
 
     #[test]
@@ -676,10 +1471,33 @@ mod tests {
         .unwrap();
         assert_eq!(
             value,
-            json!({
+            json!({"$surrealdb::record_id": {
                 "table": "person",
                 "key": {"tenant": "demo", "shard": 1}
-            })
+            }})
+        );
+    }
+
+    #[test]
+    fn deserializes_tagged_and_untagged_record_id_maps() {
+        let tagged = json!({"$surrealdb::record_id": {"table": "person", "key": "demo"}});
+        let untagged = json!({"table": "person", "key": "demo"});
+
+        for value in [tagged, untagged] {
+            assert_eq!(
+                serde_json::from_value::<RecordId>(value).unwrap(),
+                RecordId::new("person", "demo")
+            );
+        }
+        assert_eq!(
+            serde_json::from_value::<RecordIdRange>(json!({"table": "person", "key": {
+                "$surrealdb::range": {"start": null, "end": {"excluded": 5}}
+            }}))
+            .unwrap(),
+            RecordIdRange::new(
+                "person",
+                Range::new(Bound::Unbounded, Bound::Excluded(5_i64))
+            )
         );
     }
 
@@ -5,6 +5,7 @@ use std::ops::Deref;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, WireEncoding};
 
 const REGEX_TAG: &str = "$surrealdb::regex";
 
@@ -54,6 +55,20 @@ impl Serialize for Regex {
     where
         S: Serializer,
     {
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Regex {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // SurrealDB has no regex tag, so native encoding sends the pattern text.
+        if encoding == WireEncoding::Native {
+            return serializer.serialize_str(&self.0);
+        }
+
         serialize_tagged_scalar(serializer, REGEX_TAG, &self.0)
     }
 }
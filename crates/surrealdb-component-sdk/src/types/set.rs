@@ -0,0 +1,147 @@
+use std::ops::Deref;
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::wire::{self, Encode, Native, WireEncoding};
+
+const SET_TAG: &str = "$surrealdb::set";
+
+/// SurrealDB set, serialized as a `$surrealdb::set` tagged map so `set<T>` fields stay sets.
+///
+/// Items keep the order the host sent them in. SurrealDB removes duplicates and sorts items when
+/// it stores the set. Deserialization also accepts a plain array, and CBOR tag 56 is the set
+/// under [`WireEncoding::Native`](crate::WireEncoding::Native).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Set<T>(pub Vec<T>);
+
+impl<T> Set<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Default for Set<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for Set<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> From<Vec<T>> for Set<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> FromIterator<T> for Set<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Set<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T: Serialize> Serialize for Set<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_set(serializer, &self.0)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Set<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<T> {
+            Tagged {
+                #[serde(rename = "$surrealdb::set")]
+                items: Vec<T>,
+            },
+            Array(Vec<T>),
+            Native(Native<Vec<T>>),
+        }
+
+        match Repr::deserialize(deserializer) {
+            Ok(Repr::Tagged { items } | Repr::Array(items) | Repr::Native(Native(items))) => {
+                Ok(Self(items))
+            }
+            Err(_) => Err(serde::de::Error::custom(format!(
+                "invalid set representation, expected a {SET_TAG} tagged map or an array"
+            ))),
+        }
+    }
+}
+
+pub(crate) fn serialize_set<S, T>(serializer: S, items: &[T]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    wire::serialize(serializer, &Items(items))
+}
+
+struct Items<'a, T>(&'a [T]);
+
+impl<T: Serialize> Encode for Items<'_, T> {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return wire::serialize_tagged(serializer, wire::SET_TAG, self.0);
+        }
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(SET_TAG, self.0)?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Set;
+    use crate::types::wire::{self, WireEncoding};
+
+    #[test]
+    fn serializes_as_tagged_map_and_accepts_arrays() {
+        let tags: Set<String> = ["rust", "wasm"].into_iter().map(String::from).collect();
+        let value = serde_json::to_value(&tags).unwrap();
+
+        assert_eq!(value, json!({"$surrealdb::set": ["rust", "wasm"]}));
+        assert_eq!(serde_json::from_value::<Set<String>>(value).unwrap(), tags);
+        assert_eq!(
+            serde_json::from_value::<Set<String>>(json!(["rust", "wasm"])).unwrap(),
+            tags
+        );
+
+        let bytes = wire::to_vec(&tags, WireEncoding::Native).unwrap();
+        assert_eq!(&bytes[..3], &[0xd8, 56, 0x82]);
+        assert_eq!(serde_cbor::from_slice::<Set<String>>(&bytes).unwrap(), tags);
+    }
+}
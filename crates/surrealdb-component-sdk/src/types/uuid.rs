@@ -1,51 +1,199 @@
-use std::collections::HashMap;
 use std::fmt;
-use std::ops::Deref;
+use std::str::FromStr;
 
+use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_cbor::tags::Tagged;
 
 use crate::types::tagged_scalar::serialize_tagged_scalar;
+use crate::types::wire::{self, Encode, WireEncoding};
 
 const UUID_TAG: &str = "$surrealdb::uuid";
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Uuid(pub String);
+/// CBOR tag for a UUID carried as its 16 bytes.
+pub const CBOR_UUID_TAG: u64 = 37;
+
+/// UUID stored as its 16 bytes, validated when it is parsed.
+///
+/// Serializes as a `$surrealdb::uuid` tagged map holding the hyphenated text, or as CBOR tag 37
+/// under [`WireEncoding::Native`](crate::WireEncoding::Native). Wrap it in [`CborUuid`] to send
+/// tag 37 with either encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; 16]);
 
 impl Uuid {
-    pub fn into_inner(self) -> String {
+    pub const NIL: Self = Self([0; 16]);
+
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub const fn into_bytes(self) -> [u8; 16] {
         self.0
     }
-}
 
-impl Deref for Uuid {
-    type Target = str;
+    /// Version number from the UUID's version nibble, such as 4 or 7.
+    pub const fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Random UUID built from `wasi:random`.
+    pub fn new_v4() -> Self {
+        Self::v4_from_random(random_bytes())
+    }
+
+    /// Time ordered UUID built from the `wasi:clocks` system clock and `wasi:random`.
+    ///
+    /// UUIDs created within the same millisecond are ordered by their random bits only.
+    pub fn new_v7() -> Self {
+        let now = wasip3::clocks::system_clock::now();
+        let millis = u64::try_from(now.seconds).unwrap_or_default() * 1_000
+            + u64::from(now.nanoseconds / 1_000_000);
+        Self::v7_from_parts(millis, random_bytes())
+    }
+
+    fn v4_from_random(mut bytes: [u8; 16]) -> Self {
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+
+    fn v7_from_parts(unix_millis: u64, mut bytes: [u8; 16]) -> Self {
+        bytes[..6].copy_from_slice(&unix_millis.to_be_bytes()[2..]);
+        bytes[6] = (bytes[6] & 0x0f) | 0x70;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+
+    /// Wraps the UUID so it serializes as CBOR tag 37.
+    pub fn cbor_tagged(self) -> CborUuid {
+        CborUuid(self)
     }
 }
 
+fn random_bytes() -> [u8; 16] {
+    let mut bytes = [0; 16];
+    let random = wasip3::random::random::get_random_bytes(16);
+    bytes.copy_from_slice(&random[..16]);
+    bytes
+}
+
 impl fmt::Display for Uuid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0)
+        for (index, byte) in self.0.iter().enumerate() {
+            if matches!(index, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Failure to parse UUID text, pointing at the byte where parsing stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUuidError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid uuid at position {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseUuidError {}
+
+/// Parses the hyphenated form, or the simple form of 32 hex digits, in either case.
+impl FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |position: usize, message: &str| ParseUuidError {
+            position,
+            message: message.to_string(),
+        };
+
+        let hyphenated = match value.len() {
+            36 => true,
+            32 => false,
+            _ => {
+                return Err(error(
+                    value.len().min(36),
+                    "expected 32 hex digits, optionally hyphenated as 8-4-4-4-12",
+                ));
+            }
+        };
+
+        let mut bytes = [0; 16];
+        let mut nibbles = 0;
+        for (position, byte) in value.bytes().enumerate() {
+            if hyphenated && matches!(position, 8 | 13 | 18 | 23) {
+                if byte != b'-' {
+                    return Err(error(position, "expected `-`"));
+                }
+                continue;
+            }
+
+            let nibble = (byte as char)
+                .to_digit(16)
+                .ok_or_else(|| error(position, "expected a hex digit"))?;
+            bytes[nibbles / 2] |= (nibble as u8) << if nibbles % 2 == 0 { 4 } else { 0 };
+            nibbles += 1;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<&str> for Uuid {
+    type Error = ParseUuidError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Uuid {
+    type Error = ParseUuidError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<[u8; 16]> for Uuid {
+    fn from(value: [u8; 16]) -> Self {
+        Self(value)
     }
 }
 
 impl From<Uuid> for String {
     fn from(value: Uuid) -> Self {
-        value.0
+        value.to_string()
     }
 }
 
-impl From<String> for Uuid {
-    fn from(value: String) -> Self {
-        Self(value)
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for Uuid {
+    fn from(value: ::uuid::Uuid) -> Self {
+        Self(value.into_bytes())
     }
 }
 
-impl From<&str> for Uuid {
-    fn from(value: &str) -> Self {
-        Self(value.to_string())
+#[cfg(feature = "uuid")]
+impl From<Uuid> for ::uuid::Uuid {
+    fn from(value: Uuid) -> Self {
+        Self::from_bytes(value.0)
     }
 }
 
@@ -54,7 +202,72 @@ impl Serialize for Uuid {
     where
         S: Serializer,
     {
-        serialize_tagged_scalar(serializer, UUID_TAG, &self.0)
+        wire::serialize(serializer, self)
+    }
+}
+
+impl Encode for Uuid {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if encoding == WireEncoding::Native {
+            return CborUuid(*self).serialize(serializer);
+        }
+
+        serialize_tagged_scalar(serializer, UUID_TAG, &self.to_string())
+    }
+}
+
+struct UuidVisitor;
+
+impl<'de> Visitor<'de> for UuidVisitor {
+    type Value = Uuid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("uuid text, 16 uuid bytes, or a $surrealdb::uuid tagged map")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        <[u8; 16]>::try_from(value)
+            .map(Uuid)
+            .map_err(|_| E::invalid_length(value.len(), &"16 uuid bytes"))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Some((key, value)) = map.next_entry::<String, String>()? else {
+            return Err(serde::de::Error::custom("invalid uuid representation"));
+        };
+        if map.next_key::<String>()?.is_some() {
+            return Err(serde::de::Error::custom("invalid uuid representation"));
+        }
+        if key != UUID_TAG && key != "uuid" {
+            return Err(serde::de::Error::custom(format!(
+                "invalid uuid representation, unexpected key `{key}`"
+            )));
+        }
+
+        value.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -63,31 +276,45 @@ impl<'de> Deserialize<'de> for Uuid {
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum Repr {
-            String(String),
-            Tagged(HashMap<String, String>),
-        }
+        deserializer.deserialize_any(UuidVisitor)
+    }
+}
 
-        let repr = Repr::deserialize(deserializer)?;
-        match repr {
-            Repr::String(value) => Ok(Uuid(value)),
-            Repr::Tagged(mut values) => {
-                if let Some(value) = values.remove(UUID_TAG) {
-                    return Ok(Uuid(value));
-                }
-                if let Some(value) = values.remove("uuid") {
-                    return Ok(Uuid(value));
-                }
-                if values.len() == 1 {
-                    let (_, value) = values.into_iter().next().expect("single entry map");
-                    return Ok(Uuid(value));
-                }
+/// [`Uuid`] serialized as CBOR tag 37 around its 16 bytes instead of the `$surrealdb::uuid` map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CborUuid(pub Uuid);
 
-                Err(serde::de::Error::custom("invalid uuid representation"))
-            }
-        }
+impl Serialize for CborUuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Tagged::new(
+            Some(CBOR_UUID_TAG),
+            serde_cbor::Value::Bytes(self.0.0.to_vec()),
+        )
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CborUuid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Uuid::deserialize(deserializer).map(Self)
+    }
+}
+
+impl From<Uuid> for CborUuid {
+    fn from(value: Uuid) -> Self {
+        Self(value)
+    }
+}
+
+impl From<CborUuid> for Uuid {
+    fn from(value: CborUuid) -> Self {
+        value.0
     }
 }
 
@@ -95,15 +322,66 @@ impl<'de> Deserialize<'de> for Uuid {
 mod tests {
     use serde_json::json;
 
-    use super::Uuid;
+    use super::{CborUuid, Uuid};
+    use crate::types::wire::{self, WireEncoding};
+
+    const TEXT: &str = "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d";
 
     #[test]
     fn serializes_as_tagged_map() {
-        let value =
-            serde_json::to_value(Uuid::from("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d")).unwrap();
+        let uuid: Uuid = TEXT.parse().unwrap();
+        let value = serde_json::to_value(uuid).unwrap();
+
+        assert_eq!(value, json!({"$surrealdb::uuid": TEXT}));
+        assert_eq!(serde_json::from_value::<Uuid>(value).unwrap(), uuid);
+        assert_eq!(uuid.version(), 7);
+    }
+
+    #[test]
+    fn validates_text_and_reports_the_position() {
+        assert_eq!(
+            "018F6B5BF4B47F288B349B46EF4F2F4D".parse::<Uuid>().unwrap(),
+            TEXT.parse().unwrap()
+        );
         assert_eq!(
-            value,
-            json!({"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"})
+            "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4g"
+                .parse::<Uuid>()
+                .unwrap_err()
+                .position,
+            35
         );
+        assert_eq!(
+            "018f6b5b_f4b4-7f28-8b34-9b46ef4f2f4d"
+                .parse::<Uuid>()
+                .unwrap_err()
+                .position,
+            8
+        );
+        assert!("not-a-uuid".parse::<Uuid>().is_err());
+        assert!(serde_json::from_value::<Uuid>(json!({"$surrealdb::uuid": "nope"})).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_cbor_tag_37() {
+        let uuid: Uuid = TEXT.parse().unwrap();
+        let bytes = serde_cbor::to_vec(&uuid.cbor_tagged()).unwrap();
+
+        assert_eq!(&bytes[..2], &[0xd8, 37]);
+        assert_eq!(wire::to_vec(&uuid, WireEncoding::Native).unwrap(), bytes);
+        assert_eq!(serde_cbor::from_slice::<Uuid>(&bytes).unwrap(), uuid);
+        assert_eq!(
+            serde_cbor::from_slice::<CborUuid>(&bytes).unwrap(),
+            CborUuid(uuid)
+        );
+    }
+
+    #[test]
+    fn sets_version_and_variant_bits() {
+        let v4 = Uuid::v4_from_random([0xff; 16]);
+        let v7 = Uuid::v7_from_parts(0x0123_4567_89ab, [0; 16]);
+
+        assert_eq!(v4.to_string(), "ffffffff-ffff-4fff-bfff-ffffffffffff");
+        assert_eq!(v7.to_string(), "01234567-89ab-7000-8000-000000000000");
+        assert!(Uuid::v7_from_parts(1, [0xff; 16]) < Uuid::v7_from_parts(2, [0; 16]));
     }
 }
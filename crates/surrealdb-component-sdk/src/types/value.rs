@@ -0,0 +1,901 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Bound, Index};
+
+use chrono::{DateTime, Utc};
+use serde::de::{Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::geometry;
+use crate::types::maybe_none::serialize_none;
+use crate::types::record_id::NativeRecord;
+use crate::types::set::serialize_set;
+use crate::types::wire::{self, WireEncoding};
+use crate::types::{
+    Bytes, CBOR_UUID_TAG, Datetime, Decimal, Duration, FileRef, Geometry, Range, RecordId,
+    RecordIdKey, RecordIdRange, RecordIdValue, Regex, Uuid,
+};
+
+const UUID_TAG: &str = "$surrealdb::uuid";
+const DURATION_TAG: &str = "$surrealdb::duration";
+const DECIMAL_TAG: &str = "$surrealdb::decimal";
+const REGEX_TAG: &str = "$surrealdb::regex";
+const DATETIME_TAG: &str = "$surrealdb::datetime";
+const RANGE_TAG: &str = "$surrealdb::range";
+const FILE_TAG: &str = "$surrealdb::file";
+const GEOMETRY_TAG: &str = "$surrealdb::geometry";
+const NONE_TAG: &str = "$surrealdb::none";
+const SET_TAG: &str = "$surrealdb::set";
+const RECORD_ID_TAG: &str = "$surrealdb::record_id";
+
+static NONE: Value = Value::None;
+
+pub type Object = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+}
+
+impl Number {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            Self::Float(_) | Self::Decimal(_) => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            Self::Decimal(value) => value.to_string().parse().ok(),
+        }
+    }
+}
+
+/// Any SurrealDB value, decoded from the normalized CBOR the host adapter produces.
+///
+/// `NONE` and sets travel as `$surrealdb::none` and `$surrealdb::set` tagged maps, so they stay
+/// apart from [`Value::Null`] and [`Value::Array`]. The host leaves `NONE` object fields out.
+///
+/// SurrealDB's native CBOR tags decode whatever the encoding, so values from
+/// [`WireEncoding::Native`](crate::WireEncoding::Native) results need no extra step. Tables
+/// (tag 7) decode as [`Value::String`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Value {
+    #[default]
+    None,
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Bytes(Bytes),
+    Duration(Duration),
+    Datetime(Datetime),
+    Uuid(Uuid),
+    Regex(Regex),
+    Geometry(Geometry),
+    RecordId(RecordId),
+    RecordIdRange(RecordIdRange),
+    Array(Vec<Value>),
+    Object(Object),
+    Set(Vec<Value>),
+    Range(Box<Range<Value>>),
+    File(FileRef),
+}
+
+impl Value {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, Self::None | Self::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Number(number) => number.as_i64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => number.as_f64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Elements of an array or a set.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) | Self::Set(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Self::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_record_id(&self) -> Option<&RecordId> {
+        match self {
+            Self::RecordId(record_id) => Some(record_id),
+            _ => None,
+        }
+    }
+
+    /// Field of an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self {
+            Self::Object(object) => object.get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Looks up a nested value by JSON pointer, such as `/items/3/price`. Array and set
+    /// segments are indexes, and `~1` and `~0` escape `/` and `~` in object keys.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        pointer_segments(pointer)?
+            .into_iter()
+            .try_fold(self, |value, segment| match value {
+                Self::Object(object) => object.get(&segment),
+                Self::Array(values) | Self::Set(values) => {
+                    values.get(segment.parse::<usize>().ok()?)
+                }
+                _ => None,
+            })
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        pointer_segments(pointer)?
+            .into_iter()
+            .try_fold(self, |value, segment| match value {
+                Self::Object(object) => object.get_mut(&segment),
+                Self::Array(values) | Self::Set(values) => {
+                    values.get_mut(segment.parse::<usize>().ok()?)
+                }
+                _ => None,
+            })
+    }
+}
+
+fn pointer_segments(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+
+    Some(
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Missing fields and out of range indexes yield [`Value::None`].
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).unwrap_or(&NONE)
+    }
+}
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.as_array()
+            .and_then(|values| values.get(index))
+            .unwrap_or(&NONE)
+    }
+}
+
+macro_rules! impl_from {
+    ($($source:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$source> for Value {
+                fn from(value: $source) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    bool => Bool,
+    Number => Number,
+    String => String,
+    Bytes => Bytes,
+    Duration => Duration,
+    Datetime => Datetime,
+    Uuid => Uuid,
+    Regex => Regex,
+    Geometry => Geometry,
+    RecordId => RecordId,
+    RecordIdRange => RecordIdRange,
+    Vec<Value> => Array,
+    Object => Object,
+    FileRef => File,
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Number(Number::Int(value.into()))
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Number(Number::Int(value))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Number(Number::Float(value))
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(value: Decimal) -> Self {
+        Self::Number(Number::Decimal(value))
+    }
+}
+
+impl From<Range<Value>> for Value {
+    fn from(value: Range<Value>) -> Self {
+        Self::Range(Box::new(value))
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::None, Into::into)
+    }
+}
+
+impl Serialize for Number {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Int(value) => serializer.serialize_i64(*value),
+            Self::Float(value) => serializer.serialize_f64(*value),
+            Self::Decimal(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::None => serialize_none(serializer),
+            Self::Null => serializer.serialize_unit(),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Number(value) => value.serialize(serializer),
+            Self::String(value) => serializer.serialize_str(value),
+            Self::Bytes(value) => serializer.serialize_bytes(value),
+            Self::Duration(value) => value.serialize(serializer),
+            Self::Datetime(value) => value.serialize(serializer),
+            Self::Uuid(value) => value.serialize(serializer),
+            Self::Regex(value) => value.serialize(serializer),
+            Self::Geometry(value) => value.serialize(serializer),
+            Self::RecordId(value) => value.serialize(serializer),
+            Self::RecordIdRange(value) => value.serialize(serializer),
+            Self::Array(values) => values.serialize(serializer),
+            Self::Set(values) => serialize_set(serializer, values),
+            Self::Object(object) => object.serialize(serializer),
+            Self::Range(range) => range.serialize(serializer),
+            Self::File(file) => file.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        wire::deserialize(deserializer, |encoding| ValueVisitor { encoding })
+    }
+}
+
+struct ValueVisitor {
+    encoding: WireEncoding,
+}
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a SurrealDB value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Value::Number(Number::Int(value)))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visit_u128(u128::from(value))
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Value::Number(match i64::try_from(value) {
+            Ok(value) => Number::Int(value),
+            Err(_) => big_integer(value.to_string(), value as f64),
+        }))
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Value::Number(match i64::try_from(value) {
+            Ok(value) => Number::Int(value),
+            Err(_) => big_integer(value.to_string(), value as f64),
+        }))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Value::Number(Number::Float(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(Value::Bytes(Bytes(value.to_vec())))
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Value::Bytes(Bytes(value)))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some(tag) = serde_cbor::tags::current_cbor_tag() else {
+            return deserializer.deserialize_any(self);
+        };
+
+        Ok(match tag {
+            wire::NONE_TAG => {
+                IgnoredAny::deserialize(deserializer)?;
+                Value::None
+            }
+            wire::TABLE_TAG => Value::String(String::deserialize(deserializer)?),
+            wire::RECORD_ID_TAG => match NativeRecord::deserialize(deserializer)? {
+                NativeRecord::Id(id) => Value::RecordId(id),
+                NativeRecord::Range(range) => Value::RecordIdRange(range),
+            },
+            CBOR_UUID_TAG | wire::UUID_TEXT_TAG => Value::Uuid(Uuid::deserialize(deserializer)?),
+            wire::DECIMAL_TAG => {
+                let text = String::deserialize(deserializer)?;
+                let decimal = text.parse().map_err(|error| {
+                    D::Error::custom(format!("invalid decimal `{text}`: {error}"))
+                })?;
+                Value::Number(Number::Decimal(decimal))
+            }
+            wire::DATETIME_TAG => Value::Datetime(Datetime::deserialize(deserializer)?),
+            wire::DURATION_TEXT_TAG => Value::Duration(
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(D::Error::custom)?,
+            ),
+            wire::DURATION_TAG => {
+                Value::Duration(Duration::from_compact(&Vec::deserialize(deserializer)?)?)
+            }
+            wire::RANGE_TAG => Value::Range(Box::new(Range::deserialize(deserializer)?)),
+            wire::BOUND_INCLUDED_TAG | wire::BOUND_EXCLUDED_TAG => {
+                let side = if tag == wire::BOUND_INCLUDED_TAG {
+                    "included"
+                } else {
+                    "excluded"
+                };
+                Value::Object(Object::from([(
+                    side.to_string(),
+                    Value::deserialize(deserializer)?,
+                )]))
+            }
+            wire::FILE_TAG => {
+                let (bucket, key) = <(String, String)>::deserialize(deserializer)?;
+                Value::File(FileRef::new(bucket, key))
+            }
+            wire::SET_TAG => Value::Set(Deserialize::deserialize(deserializer)?),
+            wire::GEOMETRY_POINT_TAG..=wire::GEOMETRY_COLLECTION_TAG => {
+                Value::Geometry(geometry::deserialize_native(tag, deserializer)?)
+            }
+            _ => deserializer.deserialize_any(self)?,
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut object = Object::new();
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+        object_to_value(object, self.encoding)
+    }
+}
+
+/// Integers outside the `i64` range become decimals, or floats when a decimal cannot hold them.
+fn big_integer(text: String, approximate: f64) -> Number {
+    text.parse()
+        .map(Number::Decimal)
+        .unwrap_or(Number::Float(approximate))
+}
+
+fn is_tag(key: &str) -> bool {
+    matches!(
+        key,
+        UUID_TAG
+            | DURATION_TAG
+            | DECIMAL_TAG
+            | REGEX_TAG
+            | DATETIME_TAG
+            | RANGE_TAG
+            | FILE_TAG
+            | GEOMETRY_TAG
+            | NONE_TAG
+            | SET_TAG
+            | RECORD_ID_TAG
+    )
+}
+
+/// Recognizes the single entry tag maps the host encodes, including `$surrealdb::record_id`.
+/// Untagged `{ table, key }` maps stay objects. Under [`WireEncoding::Native`] every map is a
+/// plain object.
+fn object_to_value<E: Error>(mut object: Object, encoding: WireEncoding) -> Result<Value, E> {
+    if encoding == WireEncoding::Native {
+        return Ok(Value::Object(object));
+    }
+
+    if object.len() == 1
+        && let Some((tag, _)) = object.first_key_value()
+        && is_tag(tag)
+    {
+        let (tag, value) = object.pop_first().expect("single entry map");
+        return tagged_to_value(&tag, value);
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn tagged_to_value<E: Error>(tag: &str, value: Value) -> Result<Value, E> {
+    if tag == RANGE_TAG {
+        return range(value)
+            .map(|range| Value::Range(Box::new(range)))
+            .ok_or_else(|| E::custom("invalid range representation"));
+    }
+
+    if tag == NONE_TAG {
+        return match value {
+            Value::Null => Ok(Value::None),
+            _ => Err(E::custom("invalid none representation")),
+        };
+    }
+
+    if tag == SET_TAG {
+        return match value {
+            Value::Array(values) => Ok(Value::Set(values)),
+            _ => Err(E::custom("invalid set representation")),
+        };
+    }
+
+    if tag == RECORD_ID_TAG {
+        return record_id(value).ok_or_else(|| E::custom("invalid record id representation"));
+    }
+
+    if tag == GEOMETRY_TAG {
+        let geojson = serde_json::to_value(value).map_err(E::custom)?;
+        return serde_json::from_value(geojson)
+            .map(Value::Geometry)
+            .map_err(E::custom);
+    }
+
+    let Value::String(text) = value else {
+        return Err(E::custom(format!("invalid tagged scalar value for {tag}")));
+    };
+
+    match tag {
+        UUID_TAG => text.parse().map(Value::Uuid).map_err(E::custom),
+        DURATION_TAG => text
+            .parse::<Duration>()
+            .map(Value::Duration)
+            .map_err(E::custom),
+        DECIMAL_TAG => text
+            .parse()
+            .map(|decimal| Value::Number(Number::Decimal(decimal)))
+            .map_err(|error| E::custom(format!("invalid decimal `{text}`: {error}"))),
+        REGEX_TAG => Ok(Value::Regex(Regex::from(text))),
+        DATETIME_TAG => DateTime::parse_from_rfc3339(&text)
+            .map(|datetime| Value::Datetime(Datetime(datetime.with_timezone(&Utc))))
+            .map_err(E::custom),
+        FILE_TAG => text.parse().map(Value::File).map_err(E::custom),
+        _ => Err(E::custom(format!("unknown tag {tag}"))),
+    }
+}
+
+fn range(value: Value) -> Option<Range<Value>> {
+    let Value::Object(mut object) = value else {
+        return None;
+    };
+
+    let start = bound(object.remove("start").unwrap_or_default())?;
+    let end = bound(object.remove("end").unwrap_or_default())?;
+    Some(Range::new(start, end))
+}
+
+fn bound(value: Value) -> Option<Bound<Value>> {
+    match value {
+        Value::None | Value::Null => Some(Bound::Unbounded),
+        Value::Object(mut object) if object.len() == 1 => {
+            if let Some(value) = object.remove("included") {
+                Some(Bound::Included(value))
+            } else {
+                object.remove("excluded").map(Bound::Excluded)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn record_id(value: Value) -> Option<Value> {
+    let Value::Object(object) = value else {
+        return None;
+    };
+    let (Some(Value::String(table)), Some(key)) = (object.get("table"), object.get("key")) else {
+        return None;
+    };
+    if object.len() != 2 {
+        return None;
+    }
+
+    if let Value::Range(range) = key {
+        return Some(Value::RecordIdRange(RecordIdRange {
+            table: table.clone(),
+            range: record_id_key_range(range)?,
+        }));
+    }
+    Some(Value::RecordId(RecordId::new(
+        table.clone(),
+        record_id_key(key)?,
+    )))
+}
+
+fn record_id_key(value: &Value) -> Option<RecordIdKey> {
+    Some(match value {
+        Value::Number(Number::Int(value)) => RecordIdKey::Number(*value),
+        Value::String(value) => RecordIdKey::String(value.clone()),
+        Value::Uuid(value) => RecordIdKey::Uuid(value.to_string()),
+        Value::Array(values) => {
+            RecordIdKey::Array(values.iter().map(record_id_value).collect::<Option<_>>()?)
+        }
+        Value::Object(object) => RecordIdKey::Object(
+            object
+                .iter()
+                .map(|(key, value)| Some((key.clone(), record_id_value(value)?)))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+fn record_id_key_range(range: &Range<Value>) -> Option<Range<RecordIdKey>> {
+    let bound = |bound: &Bound<Value>| match bound {
+        Bound::Included(value) => record_id_key(value).map(Bound::Included),
+        Bound::Excluded(value) => record_id_key(value).map(Bound::Excluded),
+        Bound::Unbounded => Some(Bound::Unbounded),
+    };
+    Some(Range::new(bound(&range.start)?, bound(&range.end)?))
+}
+
+fn record_id_value(value: &Value) -> Option<RecordIdValue> {
+    Some(match value {
+        Value::None | Value::Null => RecordIdValue::Null,
+        Value::Bool(value) => RecordIdValue::Bool(*value),
+        Value::Number(Number::Int(value)) => RecordIdValue::Number(*value),
+        Value::Number(Number::Float(value)) => RecordIdValue::Float(*value),
+        Value::String(value) => RecordIdValue::String(value.clone()),
+        Value::Array(values) => {
+            RecordIdValue::Array(values.iter().map(record_id_value).collect::<Option<_>>()?)
+        }
+        Value::Object(object) => RecordIdValue::Object(
+            object
+                .iter()
+                .map(|(key, value)| Some((key.clone(), record_id_value(value)?)))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use super::{Number, Value};
+    use crate::types::wire::{self, WireEncoding};
+    use crate::types::{
+        Bytes, Datetime, Decimal, Duration, FileRef, Geometry, Point, Range, RecordId,
+        RecordIdRange, Uuid,
+    };
+
+    fn decode(value: serde_json::Value) -> Value {
+        let bytes = wire::to_vec(&value, WireEncoding::Legacy).unwrap();
+        wire::from_slice(&bytes, WireEncoding::Legacy).unwrap()
+    }
+
+    #[test]
+    fn decodes_tagged_scalars_record_ids_and_geometries() {
+        let value = decode(json!({
+            "id": {"$surrealdb::record_id": {"table": "person", "key": "demo"}},
+            "token": {"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"},
+            "balance": {"$surrealdb::decimal": "12.50"},
+            "home": {"$surrealdb::geometry": {"type": "Point", "coordinates": [1.5, 2.5]}},
+            "span": {"$surrealdb::range": {"start": {"included": 1}, "end": {"excluded": 5}}},
+            "avatar": {"$surrealdb::file": "avatars:/demo.png"},
+            "room": {"table": "kitchen", "key": "abc", "legs": 4},
+            "seat": {"table": "kitchen", "key": "chair"},
+            "people": {"$surrealdb::record_id": {"table": "person", "key": {"$surrealdb::range": {
+                "start": {"included": 1},
+                "end": null,
+            }}}},
+        }));
+
+        assert_eq!(
+            value["id"],
+            Value::RecordId(RecordId::new("person", "demo"))
+        );
+        assert_eq!(
+            value["token"],
+            Value::Uuid(
+                "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"
+                    .parse::<Uuid>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            value["balance"],
+            Value::Number(Number::Decimal("12.50".parse::<Decimal>().unwrap()))
+        );
+        assert_eq!(
+            value["home"],
+            Value::Geometry(Geometry::Point(Point::new(1.5, 2.5)))
+        );
+        assert_eq!(
+            value["span"],
+            Value::from(Range::new(
+                Bound::Included(Value::from(1)),
+                Bound::Excluded(Value::from(5))
+            ))
+        );
+        assert_eq!(
+            value["avatar"],
+            Value::File(FileRef::new("avatars", "/demo.png"))
+        );
+        assert_eq!(value["room"]["legs"], Value::from(4));
+        assert_eq!(value["seat"]["key"], Value::from("chair"));
+        assert_eq!(
+            value["people"],
+            Value::RecordIdRange(RecordIdRange::new(
+                "person",
+                Range::new(Bound::Included(1_i64), Bound::Unbounded)
+            ))
+        );
+        assert!(value["missing"].is_none());
+    }
+
+    #[test]
+    fn serializes_back_to_the_same_shape() {
+        let source = json!({
+            "created": {"$surrealdb::datetime": "2024-03-15T12:34:56Z"},
+            "owner": {"$surrealdb::record_id": {"table": "person", "key": 42}},
+            "tags": ["a", "b"],
+            "ratio": 0.5,
+        });
+        let value = decode(source.clone());
+        let bytes = wire::to_vec(&value, WireEncoding::Legacy).unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            source
+        );
+    }
+
+    #[test]
+    fn keeps_none_null_and_sets_apart() {
+        let source = json!({
+            "nickname": {"$surrealdb::none": null},
+            "note": null,
+            "tags": {"$surrealdb::set": ["a", "b"]},
+            "list": ["a", "b"],
+        });
+        let value = decode(source.clone());
+
+        assert_eq!(value["nickname"], Value::None);
+        assert_eq!(value["note"], Value::Null);
+        assert_eq!(
+            value["tags"],
+            Value::Set(vec![Value::from("a"), Value::from("b")])
+        );
+        assert!(matches!(value["list"], Value::Array(_)));
+        assert_eq!(serde_json::to_value(&value).unwrap(), source);
+    }
+
+    #[test]
+    fn serializes_bytes_as_cbor_byte_strings() {
+        let value = Value::Bytes(Bytes(vec![1, 2, 3]));
+        let decoded: serde_cbor::Value =
+            serde_cbor::from_slice(&serde_cbor::to_vec(&value).unwrap()).unwrap();
+
+        assert_eq!(decoded, serde_cbor::Value::Bytes(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn decodes_cbor_tag_37_as_uuid() {
+        let uuid: Uuid = "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d".parse().unwrap();
+        let bytes = serde_cbor::to_vec(&vec![uuid.cbor_tagged()]).unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<Value>(&bytes).unwrap(),
+            Value::Array(vec![Value::Uuid(uuid)])
+        );
+    }
+
+    #[test]
+    fn pointer_walks_objects_and_arrays() {
+        let mut value = decode(json!({"items": [{"price": 3}, {"a/b": true}]}));
+
+        assert_eq!(value.pointer("/items/0/price"), Some(&Value::from(3)));
+        assert_eq!(value.pointer("/items/1/a~1b"), Some(&Value::from(true)));
+        assert_eq!(value.pointer("/items/2"), None);
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("items"), None);
+
+        *value.pointer_mut("/items/0/price").unwrap() = Value::from(4);
+        assert_eq!(value["items"][0]["price"], Value::from(4));
+    }
+
+    #[test]
+    fn round_trips_native_tags() {
+        let value = Value::Object(
+            [
+                ("id", Value::RecordId(RecordId::new("person", 42))),
+                (
+                    "people",
+                    Value::RecordIdRange(RecordIdRange::new("person", 1_i64..5)),
+                ),
+                (
+                    "created",
+                    Value::Datetime(Datetime(Utc.timestamp_opt(1_710_506_096, 5).unwrap())),
+                ),
+                ("ttl", Value::Duration(Duration::new(90, 5))),
+                ("balance", Value::from("12.50".parse::<Decimal>().unwrap())),
+                ("avatar", Value::File(FileRef::new("avatars", "/demo.png"))),
+                ("tags", Value::Set(vec![Value::from("a")])),
+                (
+                    "span",
+                    Value::from(Range::new(
+                        Bound::Unbounded,
+                        Bound::Excluded(Value::from(5)),
+                    )),
+                ),
+                (
+                    "home",
+                    Value::Geometry(Geometry::Point(Point::new(1.5, 2.5))),
+                ),
+                ("nickname", Value::None),
+                (
+                    "room",
+                    Value::Object(
+                        [
+                            ("table".to_string(), Value::from("kitchen")),
+                            ("key".to_string(), Value::from("abc")),
+                        ]
+                        .into(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+        );
+        let bytes = wire::to_vec(&value, WireEncoding::Native).unwrap();
+        let decoded: Value = wire::from_slice(&bytes, WireEncoding::Native).unwrap();
+
+        assert_eq!(decoded, value);
+        assert!(matches!(decoded["room"], Value::Object(_)));
+    }
+}
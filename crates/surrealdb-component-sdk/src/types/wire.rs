@@ -0,0 +1,1038 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{
+    self, Deserialize, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+};
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Serialize, Serializer};
+use serde_cbor::tags::Tagged;
+
+/// CBOR form of the SurrealDB values plain CBOR cannot express, such as record ids and
+/// datetimes. Choose it per query with [`Query::encoding`](crate::Query::encoding).
+///
+/// The SDK carries the encoding through its own serializer and deserializer, so it never affects
+/// `serde` calls outside the SDK.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireEncoding {
+    /// Single entry `$surrealdb::*` maps, with record ids as `$surrealdb::record_id` maps.
+    #[default]
+    Legacy,
+    /// SurrealDB's registered CBOR tags, as its RPC protocol uses them. Maps always decode as
+    /// plain objects.
+    Native,
+}
+
+pub(crate) const NONE_TAG: u64 = 6;
+pub(crate) const TABLE_TAG: u64 = 7;
+pub(crate) const RECORD_ID_TAG: u64 = 8;
+pub(crate) const UUID_TEXT_TAG: u64 = 9;
+pub(crate) const DECIMAL_TAG: u64 = 10;
+pub(crate) const DATETIME_TAG: u64 = 12;
+pub(crate) const DURATION_TEXT_TAG: u64 = 13;
+pub(crate) const DURATION_TAG: u64 = 14;
+pub(crate) const RANGE_TAG: u64 = 49;
+pub(crate) const BOUND_INCLUDED_TAG: u64 = 50;
+pub(crate) const BOUND_EXCLUDED_TAG: u64 = 51;
+pub(crate) const FILE_TAG: u64 = 55;
+pub(crate) const SET_TAG: u64 = 56;
+pub(crate) const GEOMETRY_POINT_TAG: u64 = 88;
+pub(crate) const GEOMETRY_LINE_TAG: u64 = 89;
+pub(crate) const GEOMETRY_POLYGON_TAG: u64 = 90;
+pub(crate) const GEOMETRY_MULTIPOINT_TAG: u64 = 91;
+pub(crate) const GEOMETRY_MULTILINE_TAG: u64 = 92;
+pub(crate) const GEOMETRY_MULTIPOLYGON_TAG: u64 = 93;
+pub(crate) const GEOMETRY_COLLECTION_TAG: u64 = 94;
+
+/// Newtype the SDK types serialize and deserialize through. Inside it the [`Encoder`] and
+/// [`Decoder`] report the encoding they carry as `is_human_readable`, legacy being the human
+/// readable one, so no other type sees the answer change.
+const FORMS: &str = "$surrealdb::forms";
+
+/// Newtype an SDK type goes through when [`FORMS`] reported the native encoding. Only the
+/// [`Encoder`] and [`Decoder`] answer `is_human_readable` with true inside it, which tells them
+/// apart from the ones outside the SDK that are not human readable, such as `serde_cbor`'s.
+const NATIVE_FORMS: &str = "$surrealdb::native_forms";
+
+/// SDK type whose serialized form depends on the wire encoding.
+pub(crate) trait Encode {
+    fn encode<S>(&self, serializer: S, encoding: WireEncoding) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// Serializes `value` in the form of the encoding `serializer` carries. Serializers outside the
+/// SDK, such as `serde_json` and `serde_cbor`, always get the legacy form.
+pub(crate) fn serialize<S, T>(serializer: S, value: &T) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Encode + ?Sized,
+{
+    serializer.serialize_newtype_struct(FORMS, &Forms(value))
+}
+
+/// Deserializes with the visitor `visitor` builds for the encoding `deserializer` carries,
+/// following the same rules as [`serialize`], so deserializers outside the SDK get the legacy
+/// visitor.
+pub(crate) fn deserialize<'de, D, V>(
+    deserializer: D,
+    visitor: impl FnOnce(WireEncoding) -> V,
+) -> Result<V::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    deserializer.deserialize_newtype_struct(FORMS, FormsVisitor(visitor, PhantomData))
+}
+
+pub(crate) fn to_vec<T>(value: &T, encoding: WireEncoding) -> serde_cbor::Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    serde_cbor::to_vec(&Encoded { value, encoding })
+}
+
+pub(crate) fn from_slice<T>(bytes: &[u8], encoding: WireEncoding) -> serde_cbor::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let mut deserializer = serde_cbor::Deserializer::from_slice(bytes);
+    let value = Seed {
+        inner: PhantomData::<T>,
+        encoding,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+struct Forms<'a, T: ?Sized>(&'a T);
+
+impl<T: Encode + ?Sized> Serialize for Forms<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            return self.0.encode(serializer, WireEncoding::Legacy);
+        }
+
+        serializer.serialize_newtype_struct(NATIVE_FORMS, &NativeForms(self.0))
+    }
+}
+
+struct NativeForms<'a, T: ?Sized>(&'a T);
+
+impl<T: Encode + ?Sized> Serialize for NativeForms<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encoding = if serializer.is_human_readable() {
+            WireEncoding::Native
+        } else {
+            WireEncoding::Legacy
+        };
+        self.0.encode(serializer, encoding)
+    }
+}
+
+struct FormsVisitor<F, V>(F, PhantomData<V>);
+
+impl<'de, F, V> Visitor<'de> for FormsVisitor<F, V>
+where
+    F: FnOnce(WireEncoding) -> V,
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a SurrealDB value")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_any((self.0)(WireEncoding::Legacy));
+        }
+
+        let visitor = NativeFormsVisitor(self.0, PhantomData);
+        deserializer.deserialize_newtype_struct(NATIVE_FORMS, visitor)
+    }
+}
+
+struct NativeFormsVisitor<F, V>(F, PhantomData<V>);
+
+impl<'de, F, V> Visitor<'de> for NativeFormsVisitor<F, V>
+where
+    F: FnOnce(WireEncoding) -> V,
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a SurrealDB value")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoding = if deserializer.is_human_readable() {
+            WireEncoding::Native
+        } else {
+            WireEncoding::Legacy
+        };
+        deserializer.deserialize_any((self.0)(encoding))
+    }
+}
+
+/// Value serialized through an [`Encoder`].
+struct Encoded<'a, T: ?Sized> {
+    value: &'a T,
+    encoding: WireEncoding,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Encoded<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(Encoder {
+            inner: serializer,
+            encoding: self.encoding,
+            stage: Stage::Value,
+        })
+    }
+}
+
+/// Serializer that carries the wire encoding down to every nested value.
+struct Encoder<S> {
+    inner: S,
+    encoding: WireEncoding,
+    stage: Stage,
+}
+
+/// Newtype of the SDK's protocol an [`Encoder`] or [`Decoder`] is inside, which decides its
+/// `is_human_readable`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Value,
+    Forms,
+    NativeForms,
+}
+
+impl Stage {
+    /// Stage inside the newtype `name`, when it belongs to the protocol.
+    fn enter(self, name: &str) -> Option<Self> {
+        match (name, self) {
+            (FORMS, _) => Some(Self::Forms),
+            (NATIVE_FORMS, Self::Forms) => Some(Self::NativeForms),
+            _ => None,
+        }
+    }
+
+    /// `is_human_readable` answer of the stage, or `None` to forward the inner one.
+    fn is_human_readable(self, encoding: WireEncoding) -> Option<bool> {
+        match self {
+            Self::Value => None,
+            Self::Forms => Some(encoding == WireEncoding::Legacy),
+            Self::NativeForms => Some(true),
+        }
+    }
+}
+
+impl<S> Encoder<S> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Encoded<'a, T> {
+        Encoded {
+            value,
+            encoding: self.encoding,
+        }
+    }
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for Encoder<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward_serialize!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if let Some(stage) = self.stage.enter(name) {
+            return value.serialize(Encoder { stage, ..self });
+        }
+
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner
+            .serialize_newtype_variant(name, index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_seq(len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_tuple(len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_tuple_struct(name, len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_tuple_variant(name, index, variant, len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_map(len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_struct(name, len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let encoding = self.encoding;
+        self.inner
+            .serialize_struct_variant(name, index, variant, len)
+            .map(|inner| Compound { inner, encoding })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.stage
+            .is_human_readable(self.encoding)
+            .unwrap_or_else(|| self.inner.is_human_readable())
+    }
+}
+
+/// Compound serializer of an [`Encoder`].
+struct Compound<C> {
+    inner: C,
+    encoding: WireEncoding,
+}
+
+impl<C> Compound<C> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> Encoded<'a, T> {
+        Encoded {
+            value,
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl<C: SerializeSeq> SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeTupleVariant> SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeMap> SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.wrap(key);
+        self.inner.serialize_key(&key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+/// Seed that deserializes through a [`Decoder`].
+struct Seed<T> {
+    inner: T,
+    encoding: WireEncoding,
+}
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for Seed<T> {
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize(Decoder {
+            inner: deserializer,
+            encoding: self.encoding,
+            stage: Stage::Value,
+        })
+    }
+}
+
+/// Deserializer that carries the wire encoding down to every nested value.
+struct Decoder<D> {
+    inner: D,
+    encoding: WireEncoding,
+    stage: Stage,
+}
+
+impl<D> Decoder<D> {
+    fn wrap<T>(&self, inner: T) -> Wrapped<T> {
+        Wrapped {
+            inner,
+            encoding: self.encoding,
+        }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let visitor = self.wrap(visitor);
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Decoder<D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Some(stage) = self.stage.enter(name) {
+            return visitor.visit_newtype_struct(Decoder { stage, ..self });
+        }
+
+        let visitor = self.wrap(visitor);
+        self.inner.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.stage
+            .is_human_readable(self.encoding)
+            .unwrap_or_else(|| self.inner.is_human_readable())
+    }
+}
+
+/// Visitor or access of a [`Decoder`], handing the encoding to the deserializers it yields.
+struct Wrapped<T> {
+    inner: T,
+    encoding: WireEncoding,
+}
+
+impl<T> Wrapped<T> {
+    fn decoder<D>(&self, inner: D) -> Decoder<D> {
+        Decoder {
+            inner,
+            encoding: self.encoding,
+            stage: Stage::Value,
+        }
+    }
+
+    fn seed<S>(&self, inner: S) -> Seed<S> {
+        Seed {
+            inner,
+            encoding: self.encoding,
+        }
+    }
+
+    fn wrap<U>(&self, inner: U) -> Wrapped<U> {
+        Wrapped {
+            inner,
+            encoding: self.encoding,
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E>(self, value: $ty) -> Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Wrapped<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    );
+
+    fn visit_none<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.decoder(deserializer);
+        self.inner.visit_some(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.decoder(deserializer);
+        self.inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let seq = self.wrap(seq);
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let map = self.wrap(map);
+        self.inner.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let data = self.wrap(data);
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Wrapped<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.inner.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Wrapped<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.inner.next_key_seed(seed)
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for Wrapped<A> {
+    type Error = A::Error;
+    type Variant = Wrapped<A::Variant>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        let encoding = self.encoding;
+        self.inner
+            .variant_seed(seed)
+            .map(|(value, inner)| (value, Wrapped { inner, encoding }))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Wrapped<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.wrap(visitor);
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = self.wrap(visitor);
+        self.inner.struct_variant(fields, visitor)
+    }
+}
+
+pub(crate) fn serialize_tagged<S, T>(serializer: S, tag: u64, value: &T) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize + ?Sized,
+{
+    Tagged::new(Some(tag), value).serialize(serializer)
+}
+
+/// Value inside a native CBOR tag, whatever the tag. Lets `#[serde(untagged)]` representations
+/// accept native forms, whose tag only reaches them as a newtype.
+pub(crate) struct Native<T>(pub T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Native<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NativeVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for NativeVisitor<T> {
+            type Value = Native<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a CBOR tagged value")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                T::deserialize(deserializer).map(Native)
+            }
+        }
+
+        deserializer.deserialize_any(NativeVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Serialize, Serializer};
+
+    use super::{WireEncoding, from_slice, to_vec};
+    use crate::types::{Datetime, Decimal, Duration, RecordId, Value};
+
+    /// Serializes whether its serializer reports being human readable.
+    struct HumanReadable;
+
+    impl Serialize for HumanReadable {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let human_readable = serializer.is_human_readable();
+            serializer.serialize_bool(human_readable)
+        }
+    }
+
+    #[test]
+    fn carries_the_encoding_to_nested_sdk_types_only() {
+        let id = RecordId::new("person", "tobie");
+        let value = (vec![id.clone()], HumanReadable);
+
+        let legacy: (Value, bool) = from_slice(
+            &to_vec(&value, WireEncoding::Legacy).unwrap(),
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+        let native: (Value, bool) = from_slice(
+            &to_vec(&value, WireEncoding::Native).unwrap(),
+            WireEncoding::Native,
+        )
+        .unwrap();
+
+        assert_eq!(
+            legacy,
+            (Value::Array(vec![Value::RecordId(id.clone())]), false)
+        );
+        assert_eq!(native, (Value::Array(vec![Value::RecordId(id)]), false));
+        assert_ne!(
+            to_vec(&value, WireEncoding::Legacy).unwrap(),
+            to_vec(&value, WireEncoding::Native).unwrap()
+        );
+    }
+
+    #[test]
+    fn native_decoding_keeps_tag_maps_as_objects() {
+        let bytes = to_vec(
+            &serde_json::json!({ "$surrealdb::uuid": "0189d6e3-8eac-703a-9a48-d9faa78b44b9" }),
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            from_slice::<Value>(&bytes, WireEncoding::Legacy).unwrap(),
+            Value::Uuid(_)
+        ));
+        assert!(matches!(
+            from_slice::<Value>(&bytes, WireEncoding::Native).unwrap(),
+            Value::Object(_)
+        ));
+    }
+
+    #[test]
+    fn plain_serde_gets_the_legacy_forms() {
+        let value = (
+            Datetime::from(std::time::SystemTime::UNIX_EPOCH),
+            Duration::from(std::time::Duration::from_secs(90)),
+            Decimal::from("12.50"),
+            RecordId::new("person", "tobie"),
+        );
+        let bytes = serde_cbor::to_vec(&value).unwrap();
+
+        assert_eq!(bytes, to_vec(&value, WireEncoding::Legacy).unwrap());
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            serde_json::json!([
+                { "$surrealdb::datetime": "1970-01-01T00:00:00Z" },
+                { "$surrealdb::duration": "1m30s" },
+                { "$surrealdb::decimal": "12.50" },
+                { "$surrealdb::record_id": { "table": "person", "key": "tobie" } },
+            ])
+        );
+        assert_ne!(bytes, to_vec(&value, WireEncoding::Native).unwrap());
+        assert!(matches!(
+            serde_cbor::from_slice::<Value>(&bytes).unwrap(),
+            Value::Array(values) if matches!(values[3], Value::RecordId(_))
+        ));
+    }
+}
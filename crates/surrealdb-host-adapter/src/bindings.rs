@@ -0,0 +1,8 @@
+wasmtime::component::bindgen!({
+    path: "wit",
+    world: "host",
+    imports: { default: async | store | trappable },
+    with: {
+        "seamlezz:surrealdb/call.transaction": crate::TransactionHandle,
+    },
+});
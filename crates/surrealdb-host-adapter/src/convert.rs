@@ -1,42 +1,165 @@
 use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Bound;
 use std::str::FromStr;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use geo_types::{Coord, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon};
 use rust_decimal::Decimal as RustDecimal;
 use serde_cbor::Value as CborValue;
 use surrealdb_types::{
-    Array as SurrealArray, Bytes as SurrealBytes, Duration as SurrealDuration,
-    Number as SurrealNumber, Object as SurrealObject, RecordId as SurrealRecordId,
-    RecordIdKey as SurrealRecordIdKey, Regex as SurrealRegex, SurrealValue as _,
-    Uuid as SurrealUuid, Value as SurrealValue,
+    Array as SurrealArray, Bytes as SurrealBytes, Datetime as SurrealDatetime,
+    Duration as SurrealDuration, File as SurrealFile, Geometry as SurrealGeometry,
+    Number as SurrealNumber, Object as SurrealObject, Range as SurrealRange,
+    RecordId as SurrealRecordId, RecordIdKey as SurrealRecordIdKey,
+    RecordIdKeyRange as SurrealRecordIdKeyRange, Regex as SurrealRegex, Set as SurrealSet,
+    SurrealValue as _, Uuid as SurrealUuid, Value as SurrealValue,
 };
+use thiserror::Error;
+
+mod native;
 
 const UUID_TAG: &str = "$surrealdb::uuid";
 const DURATION_TAG: &str = "$surrealdb::duration";
 const DECIMAL_TAG: &str = "$surrealdb::decimal";
 const REGEX_TAG: &str = "$surrealdb::regex";
+const DATETIME_TAG: &str = "$surrealdb::datetime";
+const GEOMETRY_TAG: &str = "$surrealdb::geometry";
+const RANGE_TAG: &str = "$surrealdb::range";
+const FILE_TAG: &str = "$surrealdb::file";
+const NONE_TAG: &str = "$surrealdb::none";
+const SET_TAG: &str = "$surrealdb::set";
+const RECORD_ID_TAG: &str = "$surrealdb::record_id";
+
+const CBOR_DATETIME_TEXT_TAG: u64 = 0;
+const CBOR_DATETIME_EPOCH_TAG: u64 = 1;
+const CBOR_UUID_TAG: u64 = 37;
+const CBOR_POSITIVE_BIGNUM_TAG: u64 = 2;
+const CBOR_NEGATIVE_BIGNUM_TAG: u64 = 3;
+
+/// How param integers outside the `i64` range decode, such as `u64` hashes or CBOR bignums.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntegerPolicy {
+    /// Reject the param, naming where the integer sits in it.
+    #[default]
+    Strict,
+    /// Bind the integer as an exact decimal. Integers beyond the 96 bit decimal range are still
+    /// rejected.
+    LosslessDecimal,
+}
+
+/// CBOR form of the SurrealDB values plain CBOR cannot express, such as record ids and
+/// datetimes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireEncoding {
+    /// Single entry `$surrealdb::*` maps, with record ids as `$surrealdb::record_id` maps.
+    #[default]
+    Legacy,
+    /// SurrealDB's registered CBOR tags, as its RPC protocol uses them. Maps always decode as
+    /// plain objects.
+    Native,
+}
 
-pub fn cbor_slice_to_surreal(bytes: &[u8]) -> Result<SurrealValue> {
-    let value: CborValue = serde_cbor::from_slice(bytes)?;
-    cbor_to_surreal(value)
+/// Location of a value inside a param, displayed as a JSON pointer.
+#[derive(Clone, Copy)]
+enum Path<'a> {
+    Root,
+    Key(&'a Path<'a>, &'a str),
+    Index(&'a Path<'a>, usize),
+}
+
+impl<'a> Path<'a> {
+    fn key(&'a self, key: &'a str) -> Self {
+        Self::Key(self, key)
+    }
+
+    fn index(&'a self, index: usize) -> Self {
+        Self::Index(self, index)
+    }
 }
 
-pub fn surreal_to_cbor_bytes(value: SurrealValue) -> Result<Vec<u8>> {
-    let normalized = surreal_to_cbor(value)?;
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root => Ok(()),
+            Self::Key(parent, key) => {
+                write!(f, "{parent}/{}", key.replace('~', "~0").replace('/', "~1"))
+            }
+            Self::Index(parent, index) => write!(f, "{parent}/{index}"),
+        }
+    }
+}
+
+/// Param value that could not be decoded, located by a JSON pointer that starts at the param
+/// name, such as `/document/items/3/price`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{pointer}: expected {expected}, found {actual}{}", reason.as_ref().map(|reason| format!(" ({reason})")).unwrap_or_default())]
+pub struct ParamDecodeError {
+    pub pointer: String,
+    pub expected: &'static str,
+    pub actual: &'static str,
+    pub reason: Option<String>,
+}
+
+impl ParamDecodeError {
+    fn new(path: &Path<'_>, expected: &'static str, actual: &'static str) -> Self {
+        Self {
+            pointer: path.to_string(),
+            expected,
+            actual,
+            reason: None,
+        }
+    }
+
+    fn because(mut self, reason: impl fmt::Display) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+}
+
+pub fn cbor_param_to_surreal(
+    key: &str,
+    bytes: &[u8],
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+) -> Result<SurrealValue, ParamDecodeError> {
+    let path = Path::Root.key(key);
+    let value: CborValue = serde_cbor::from_slice(bytes).map_err(|error| {
+        ParamDecodeError::new(&path, "a CBOR value", "malformed CBOR").because(error)
+    })?;
+    let converted = match encoding {
+        WireEncoding::Legacy => cbor_to_surreal(value, integers, encoding, &path),
+        WireEncoding::Native => native::cbor_to_surreal(value, integers, &path),
+    };
+    converted.map_err(|error| {
+        error.downcast().unwrap_or_else(|error| {
+            ParamDecodeError::new(&path, "a SurrealDB value", "an invalid value").because(error)
+        })
+    })
+}
+
+pub fn surreal_to_cbor_bytes(value: SurrealValue, encoding: WireEncoding) -> Result<Vec<u8>> {
+    let normalized = match encoding {
+        WireEncoding::Legacy => surreal_to_cbor(value, encoding)?,
+        WireEncoding::Native => native::surreal_to_cbor(value)?,
+    };
     Ok(serde_cbor::to_vec(&normalized)?)
 }
 
-fn surreal_to_cbor(value: SurrealValue) -> Result<CborValue> {
+fn surreal_to_cbor(value: SurrealValue, encoding: WireEncoding) -> Result<CborValue> {
+    let convert = |value| surreal_to_cbor(value, encoding);
     Ok(match value {
-        SurrealValue::None | SurrealValue::Null => CborValue::Null,
+        SurrealValue::None => tagged(NONE_TAG, CborValue::Null),
+        SurrealValue::Null => CborValue::Null,
         SurrealValue::Bool(v) => CborValue::Bool(v),
         SurrealValue::Number(v) => number_to_cbor(v),
         SurrealValue::String(v) => CborValue::Text(v),
         SurrealValue::Bytes(v) => CborValue::Bytes(v.into_inner().to_vec()),
         SurrealValue::Duration(v) => tagged_text(DURATION_TAG, v.to_string()),
-        SurrealValue::Datetime(v) => CborValue::Text(v.to_string()),
+        SurrealValue::Datetime(v) => tagged_text(DATETIME_TAG, datetime_to_text(v)),
         SurrealValue::Uuid(v) => tagged_text(UUID_TAG, v.to_string()),
-        SurrealValue::Geometry(v) => serde_cbor::value::to_value(v)?,
+        SurrealValue::Geometry(v) => tagged(GEOMETRY_TAG, geometry_to_geojson(v)),
         SurrealValue::Table(v) => CborValue::Text(v.to_string()),
         SurrealValue::RecordId(v) => {
             let mut map = BTreeMap::new();
@@ -46,114 +169,327 @@ fn surreal_to_cbor(value: SurrealValue) -> Result<CborValue> {
             );
             map.insert(
                 CborValue::Text("key".to_string()),
-                record_id_key_to_cbor(v.key)?,
+                record_id_key_to_cbor(v.key, encoding)?,
             );
-            CborValue::Map(map)
+            tagged(RECORD_ID_TAG, CborValue::Map(map))
         }
-        SurrealValue::File(v) => serde_cbor::value::to_value(v)?,
-        SurrealValue::Range(v) => serde_cbor::value::to_value(v)?,
+        SurrealValue::File(v) => tagged_text(FILE_TAG, file_to_text(&v)),
+        SurrealValue::Range(v) => tagged(RANGE_TAG, range_to_cbor(v.start, v.end, convert)?),
         SurrealValue::Regex(v) => tagged_text(REGEX_TAG, v.regex().as_str().to_string()),
-        SurrealValue::Array(v) => CborValue::Array(
-            v.into_iter()
-                .map(surreal_to_cbor)
-                .collect::<Result<Vec<_>>>()?,
-        ),
-        SurrealValue::Object(v) => {
-            let mut entries: BTreeMap<String, SurrealValue> = BTreeMap::new();
-            for (key, value) in v {
-                entries.insert(key, value);
-            }
-
-            let mut object = BTreeMap::new();
-            for (key, value) in entries {
-                object.insert(CborValue::Text(key), surreal_to_cbor(value)?);
-            }
-            CborValue::Map(object)
+        SurrealValue::Array(v) => {
+            CborValue::Array(v.into_iter().map(convert).collect::<Result<Vec<_>>>()?)
         }
-        SurrealValue::Set(v) => CborValue::Array(
-            v.into_iter()
-                .map(surreal_to_cbor)
-                .collect::<Result<Vec<_>>>()?,
+        SurrealValue::Object(v) => object_to_cbor(v, convert)?,
+        SurrealValue::Set(v) => tagged(
+            SET_TAG,
+            CborValue::Array(v.into_iter().map(convert).collect::<Result<Vec<_>>>()?),
         ),
     })
 }
 
-fn cbor_to_surreal(value: CborValue) -> Result<SurrealValue> {
+/// Converts one value, attaching its location to failures that do not carry one yet.
+fn cbor_to_surreal(
+    value: CborValue,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    let actual = kind(&value);
+    convert_cbor(value, integers, encoding, path).map_err(|error| locate(error, path, actual))
+}
+
+fn locate(error: anyhow::Error, path: &Path<'_>, actual: &'static str) -> anyhow::Error {
+    if error.is::<ParamDecodeError>() {
+        error
+    } else {
+        mismatch(path, "a SurrealDB value", actual)
+            .because(error)
+            .into()
+    }
+}
+
+fn convert_cbor(
+    value: CborValue,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
     Ok(match value {
         CborValue::Null => SurrealValue::Null,
         CborValue::Bool(value) => SurrealValue::Bool(value),
-        CborValue::Integer(value) => {
-            if value < i64::MIN as i128 || value > i64::MAX as i128 {
-                bail!("unsupported integer representation")
-            }
-            SurrealValue::Number(SurrealNumber::Int(value as i64))
-        }
+        CborValue::Integer(value) => integer_to_surreal(value, integers, path)?,
         CborValue::Float(value) => SurrealValue::Number(SurrealNumber::Float(value)),
         CborValue::Bytes(value) => SurrealValue::Bytes(SurrealBytes::from(value)),
         CborValue::Text(value) => SurrealValue::String(value),
         CborValue::Array(values) => SurrealValue::Array(
             values
                 .into_iter()
-                .map(cbor_to_surreal)
+                .enumerate()
+                .map(|(index, value)| {
+                    cbor_to_surreal(value, integers, encoding, &path.index(index))
+                })
                 .collect::<Result<SurrealArray>>()?,
         ),
-        CborValue::Map(values) => map_to_surreal(values)?,
-        CborValue::Tag(_, value) => cbor_to_surreal(*value)?,
-        _ => bail!("unsupported cbor value"),
+        CborValue::Map(values) => map_to_surreal(values, integers, encoding, path)?,
+        CborValue::Tag(CBOR_DATETIME_TEXT_TAG, value) => match *value {
+            CborValue::Text(value) => SurrealValue::Datetime(
+                text_to_datetime(&value)
+                    .map_err(|error| mismatch(path, DATETIME_TEXT, "text").because(error))?,
+            ),
+            value => return Err(mismatch(path, DATETIME_TEXT, kind(&value)).into()),
+        },
+        CborValue::Tag(CBOR_DATETIME_EPOCH_TAG, value) => {
+            let actual = kind(&value);
+            SurrealValue::Datetime(epoch_to_datetime(*value).map_err(|error| {
+                mismatch(path, "epoch seconds within the datetime range", actual).because(error)
+            })?)
+        }
+        CborValue::Tag(CBOR_UUID_TAG, value) => match *value {
+            CborValue::Bytes(value) => SurrealValue::Uuid(SurrealUuid::try_from(
+                uuid::Uuid::from_slice(&value)
+                    .map_err(|error| mismatch(path, UUID_BYTES, "bytes").because(error))?
+                    .to_string(),
+            )?),
+            value => return Err(mismatch(path, UUID_BYTES, kind(&value)).into()),
+        },
+        CborValue::Tag(tag @ (CBOR_POSITIVE_BIGNUM_TAG | CBOR_NEGATIVE_BIGNUM_TAG), value) => {
+            bignum_to_surreal(tag, *value, integers, path)?
+        }
+        CborValue::Tag(_, value) => cbor_to_surreal(*value, integers, encoding, path)?,
+        value => return Err(mismatch(path, "a SurrealDB value", kind(&value)).into()),
     })
 }
 
+const DATETIME_TEXT: &str = "RFC 3339 datetime text";
+const UUID_BYTES: &str = "16 uuid bytes";
+const RANGE_BOUND: &str = "null or an included or excluded bound";
+const RECORD_ID_MAP: &str = "a { table, key } record id";
+
+fn mismatch(path: &Path<'_>, expected: &'static str, actual: &'static str) -> ParamDecodeError {
+    ParamDecodeError::new(path, expected, actual)
+}
+
+/// CBOR kind of `value`, as reported in [`ParamDecodeError::actual`].
+fn kind(value: &CborValue) -> &'static str {
+    match value {
+        CborValue::Null => "null",
+        CborValue::Bool(_) => "bool",
+        CborValue::Integer(_) => "integer",
+        CborValue::Float(_) => "float",
+        CborValue::Bytes(_) => "bytes",
+        CborValue::Text(_) => "text",
+        CborValue::Array(_) => "array",
+        CborValue::Map(_) => "map",
+        CborValue::Tag(..) => "tagged value",
+        _ => "unknown value",
+    }
+}
+
+fn integer_to_surreal(
+    value: i128,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    if let Ok(value) = i64::try_from(value) {
+        return Ok(SurrealValue::Number(SurrealNumber::Int(value)));
+    }
+
+    match integers {
+        IntegerPolicy::Strict => Err(mismatch(path, "an integer within the i64 range", "integer")
+            .because(format_args!(
+                "{value} needs IntegerPolicy::LosslessDecimal to bind as a decimal"
+            ))
+            .into()),
+        IntegerPolicy::LosslessDecimal => RustDecimal::try_from_i128_with_scale(value, 0)
+            .map(|value| SurrealValue::Number(SurrealNumber::Decimal(value)))
+            .map_err(|_| out_of_decimal_range(path, &value).into()),
+    }
+}
+
+/// Decodes CBOR tag 2 and 3 bignums, whose big endian bytes hold `n` and `-1 - n`.
+fn bignum_to_surreal(
+    tag: u64,
+    value: CborValue,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    let CborValue::Bytes(bytes) = value else {
+        return Err(mismatch(path, "bignum bytes", kind(&value)).into());
+    };
+
+    let digits: Vec<u8> = bytes.into_iter().skip_while(|byte| *byte == 0).collect();
+    let magnitude = (digits.len() <= 16)
+        .then(|| {
+            digits.iter().fold(0_u128, |magnitude, byte| {
+                (magnitude << 8) | u128::from(*byte)
+            })
+        })
+        .and_then(|magnitude| i128::try_from(magnitude).ok());
+
+    match magnitude {
+        Some(magnitude) if tag == CBOR_NEGATIVE_BIGNUM_TAG => {
+            integer_to_surreal(-1 - magnitude, integers, path)
+        }
+        Some(magnitude) => integer_to_surreal(magnitude, integers, path),
+        None => {
+            Err(out_of_decimal_range(path, &format_args!("a {} byte bignum", digits.len())).into())
+        }
+    }
+}
+
+fn out_of_decimal_range(path: &Path<'_>, integer: &dyn fmt::Display) -> ParamDecodeError {
+    mismatch(path, "an integer within the decimal range", "integer")
+        .because(format_args!("{integer} does not fit in 96 bits"))
+}
+
 fn is_tagged_scalar_key(tag: &str) -> bool {
-    matches!(tag, UUID_TAG | DURATION_TAG | DECIMAL_TAG | REGEX_TAG)
+    matches!(
+        tag,
+        UUID_TAG | DURATION_TAG | DECIMAL_TAG | REGEX_TAG | DATETIME_TAG | FILE_TAG
+    )
 }
 
-fn map_to_surreal(values: BTreeMap<CborValue, CborValue>) -> Result<SurrealValue> {
+fn map_to_surreal(
+    values: BTreeMap<CborValue, CborValue>,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
     if values.len() == 1 {
         let mut iter = values.into_iter();
         let (key, value) = iter.next().expect("single entry map");
 
-        if let CborValue::Text(tag) = &key
-            && is_tagged_scalar_key(tag)
-        {
-            return tagged_scalar_to_surreal(tag.clone(), value);
+        if let CborValue::Text(tag) = &key {
+            if tag == GEOMETRY_TAG {
+                let actual = kind(&value);
+                return geojson_to_geometry(value)
+                    .map(SurrealValue::Geometry)
+                    .map_err(|error| {
+                        mismatch(&path.key(tag), "a GeoJSON geometry", actual)
+                            .because(error)
+                            .into()
+                    });
+            }
+            if tag == RANGE_TAG {
+                return Ok(SurrealValue::Range(Box::new(cbor_to_range(
+                    value,
+                    integers,
+                    encoding,
+                    &path.key(tag),
+                )?)));
+            }
+            if tag == NONE_TAG {
+                let CborValue::Null = value else {
+                    return Err(mismatch(&path.key(tag), "null", kind(&value)).into());
+                };
+                return Ok(SurrealValue::None);
+            }
+            if tag == SET_TAG {
+                let CborValue::Array(values) = value else {
+                    return Err(mismatch(&path.key(tag), "an array", kind(&value)).into());
+                };
+                let path = path.key(tag);
+                return Ok(SurrealValue::Set(SurrealSet::from(
+                    values
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, value)| {
+                            cbor_to_surreal(value, integers, encoding, &path.index(index))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                )));
+            }
+            if tag == RECORD_ID_TAG {
+                let path = path.key(tag);
+                let actual = kind(&value);
+                let CborValue::Map(values) = value else {
+                    return Err(mismatch(&path, RECORD_ID_MAP, actual).into());
+                };
+                let object = map_to_object(values, integers, encoding, &path)?;
+                return record_id_object(&object)
+                    .ok_or_else(|| mismatch(&path, RECORD_ID_MAP, actual).into());
+            }
+            if is_tagged_scalar_key(tag) {
+                return tagged_scalar_to_surreal(tag.clone(), value, &path.key(tag));
+            }
         }
 
+        let key = map_key_to_string(key, path)?;
+        let value = cbor_to_surreal(value, integers, encoding, &path.key(&key))?;
         let mut object = SurrealObject::new();
-        object.insert(map_key_to_string(key)?, cbor_to_surreal(value)?);
-        return maybe_record_id_object(object);
+        object.insert(key, value);
+        return Ok(SurrealValue::Object(object));
     }
 
+    let object = map_to_object(values, integers, encoding, path)?;
+    Ok(SurrealValue::Object(object))
+}
+
+fn map_to_object(
+    values: BTreeMap<CborValue, CborValue>,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+    path: &Path<'_>,
+) -> Result<SurrealObject> {
     let mut object = SurrealObject::new();
     for (key, value) in values {
-        object.insert(map_key_to_string(key)?, cbor_to_surreal(value)?);
+        let key = map_key_to_string(key, path)?;
+        let value = cbor_to_surreal(value, integers, encoding, &path.key(&key))?;
+        object.insert(key, value);
     }
-
-    maybe_record_id_object(object)
+    Ok(object)
 }
 
-fn maybe_record_id_object(object: SurrealObject) -> Result<SurrealValue> {
-    if let (Some(SurrealValue::String(table)), Some(key)) = (object.get("table"), object.get("key"))
-        && let Ok(key) = SurrealRecordIdKey::from_value(key.clone())
+fn record_id_object(object: &SurrealObject) -> Option<SurrealValue> {
+    let Some(SurrealValue::String(table)) = object.get("table") else {
+        return None;
+    };
+    let key = object.get("key")?;
+
+    if let SurrealValue::Range(range) = key
+        && let Some(range) = record_id_key_range(range)
     {
-        return Ok(SurrealValue::RecordId(SurrealRecordId::new(
+        return Some(SurrealValue::RecordId(SurrealRecordId::new(
             table.clone(),
-            key,
+            SurrealRecordIdKey::Range(Box::new(range)),
         )));
     }
 
-    Ok(SurrealValue::Object(object))
+    SurrealRecordIdKey::from_value(key.clone())
+        .ok()
+        .map(|key| SurrealValue::RecordId(SurrealRecordId::new(table.clone(), key)))
 }
 
-fn tagged_scalar_to_surreal(tag: String, value: CborValue) -> Result<SurrealValue> {
+fn tagged_scalar_to_surreal(
+    tag: String,
+    value: CborValue,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    let expected = match tag.as_str() {
+        UUID_TAG => "uuid text",
+        DURATION_TAG => "duration text",
+        DECIMAL_TAG => "decimal text",
+        REGEX_TAG => "regex text",
+        DATETIME_TAG => DATETIME_TEXT,
+        FILE_TAG => "file reference text",
+        _ => "text",
+    };
     let CborValue::Text(value) = value else {
-        bail!("invalid tagged scalar value")
+        return Err(mismatch(path, expected, kind(&value)).into());
     };
 
+    parse_tagged_scalar(tag, value)
+        .map_err(|error| mismatch(path, expected, "text").because(error).into())
+}
+
+fn parse_tagged_scalar(tag: String, value: String) -> Result<SurrealValue> {
     Ok(match tag.as_str() {
         UUID_TAG => SurrealValue::Uuid(SurrealUuid::try_from(value)?),
         DURATION_TAG => SurrealValue::Duration(SurrealDuration::from_str(&value)?),
         DECIMAL_TAG => SurrealValue::Number(SurrealNumber::Decimal(RustDecimal::from_str(&value)?)),
         REGEX_TAG => SurrealValue::Regex(SurrealRegex::from_str(&value)?),
+        DATETIME_TAG => SurrealValue::Datetime(text_to_datetime(&value)?),
+        FILE_TAG => SurrealValue::File(text_to_file(&value)?),
         _ => {
             let mut object = SurrealObject::new();
             object.insert(tag, value);
@@ -162,11 +498,36 @@ fn tagged_scalar_to_surreal(tag: String, value: CborValue) -> Result<SurrealValu
     })
 }
 
-fn map_key_to_string(key: CborValue) -> Result<String> {
+fn text_to_datetime(value: &str) -> Result<SurrealDatetime> {
+    let datetime = DateTime::parse_from_rfc3339(value)?;
+    Ok(SurrealDatetime::from(datetime.with_timezone(&Utc)))
+}
+
+fn epoch_to_datetime(value: CborValue) -> Result<SurrealDatetime> {
+    let datetime = match value {
+        CborValue::Integer(seconds) => Utc.timestamp_opt(i64::try_from(seconds)?, 0).single(),
+        CborValue::Float(seconds) => {
+            let whole = seconds.floor();
+            let nanos = ((seconds - whole) * 1_000_000_000.0) as u32;
+            Utc.timestamp_opt(whole as i64, nanos).single()
+        }
+        _ => bail!("invalid epoch datetime value"),
+    };
+
+    Ok(SurrealDatetime::from(
+        datetime.context("epoch datetime out of range")?,
+    ))
+}
+
+fn datetime_to_text(value: SurrealDatetime) -> String {
+    DateTime::<Utc>::from(value).to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn map_key_to_string(key: CborValue, path: &Path<'_>) -> Result<String> {
     match key {
         CborValue::Text(text) => Ok(text),
         CborValue::Integer(integer) => Ok(integer.to_string()),
-        _ => bail!("unsupported map key type"),
+        key => Err(mismatch(path, "text or integer map keys", kind(&key)).into()),
     }
 }
 
@@ -178,48 +539,309 @@ fn number_to_cbor(value: SurrealNumber) -> CborValue {
     }
 }
 
-fn record_id_key_to_cbor(value: SurrealRecordIdKey) -> Result<CborValue> {
+fn record_id_key_to_cbor(value: SurrealRecordIdKey, encoding: WireEncoding) -> Result<CborValue> {
+    let convert = |value| surreal_to_cbor(value, encoding);
     Ok(match value {
         SurrealRecordIdKey::Number(v) => CborValue::Integer(v as i128),
         SurrealRecordIdKey::String(v) => CborValue::Text(v),
         SurrealRecordIdKey::Uuid(v) => tagged_text(UUID_TAG, v.to_string()),
-        SurrealRecordIdKey::Array(v) => CborValue::Array(
-            v.into_iter()
-                .map(surreal_to_cbor)
-                .collect::<Result<Vec<_>>>()?,
+        SurrealRecordIdKey::Array(v) => {
+            CborValue::Array(v.into_iter().map(convert).collect::<Result<Vec<_>>>()?)
+        }
+        SurrealRecordIdKey::Object(v) => object_to_cbor(v, convert)?,
+        SurrealRecordIdKey::Range(v) => tagged(
+            RANGE_TAG,
+            range_to_cbor(v.start, v.end, |key| record_id_key_to_cbor(key, encoding))?,
         ),
-        SurrealRecordIdKey::Object(v) => {
-            let mut entries: BTreeMap<String, SurrealValue> = BTreeMap::new();
-            for (key, value) in v {
-                entries.insert(key, value);
-            }
+    })
+}
 
-            let mut object = BTreeMap::new();
-            for (key, value) in entries {
-                object.insert(CborValue::Text(key), surreal_to_cbor(value)?);
-            }
-            CborValue::Map(object)
+/// Encodes an object's fields, leaving `NONE` fields out. A missing field reads as `NONE`, which
+/// keeps plain `Option<T>` fields decoding.
+fn object_to_cbor(
+    object: SurrealObject,
+    convert: impl Fn(SurrealValue) -> Result<CborValue>,
+) -> Result<CborValue> {
+    let mut entries = BTreeMap::new();
+    for (key, value) in object {
+        if !matches!(value, SurrealValue::None) {
+            entries.insert(CborValue::Text(key), convert(value)?);
         }
-        SurrealRecordIdKey::Range(v) => serde_cbor::value::to_value(v)?,
+    }
+    Ok(CborValue::Map(entries))
+}
+
+/// Encodes range bounds as `{"start": bound, "end": bound}`, where a bound is
+/// `{"included": value}`, `{"excluded": value}`, or null when unbounded.
+fn range_to_cbor<T>(
+    start: Bound<T>,
+    end: Bound<T>,
+    convert: impl Fn(T) -> Result<CborValue>,
+) -> Result<CborValue> {
+    let bound = |bound: Bound<T>| -> Result<CborValue> {
+        Ok(match bound {
+            Bound::Included(value) => tagged("included", convert(value)?),
+            Bound::Excluded(value) => tagged("excluded", convert(value)?),
+            Bound::Unbounded => CborValue::Null,
+        })
+    };
+
+    let mut map = BTreeMap::new();
+    map.insert(CborValue::Text("start".to_string()), bound(start)?);
+    map.insert(CborValue::Text("end".to_string()), bound(end)?);
+    Ok(CborValue::Map(map))
+}
+
+fn cbor_to_range(
+    value: CborValue,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+    path: &Path<'_>,
+) -> Result<SurrealRange> {
+    let CborValue::Map(mut values) = value else {
+        return Err(mismatch(path, "a map with start and end bounds", kind(&value)).into());
+    };
+    let mut bound = |name: &str| -> Result<Bound<SurrealValue>> {
+        Ok(match values.remove(&CborValue::Text(name.to_string())) {
+            None | Some(CborValue::Null) => Bound::Unbounded,
+            Some(CborValue::Map(entry)) if entry.len() == 1 => {
+                let (bound, value) = entry.into_iter().next().expect("single entry map");
+                match bound {
+                    CborValue::Text(bound) if bound == "included" => Bound::Included(
+                        cbor_to_surreal(value, integers, encoding, &path.key(name).key(&bound))?,
+                    ),
+                    CborValue::Text(bound) if bound == "excluded" => Bound::Excluded(
+                        cbor_to_surreal(value, integers, encoding, &path.key(name).key(&bound))?,
+                    ),
+                    _ => return Err(mismatch(&path.key(name), RANGE_BOUND, "map").into()),
+                }
+            }
+            Some(value) => return Err(mismatch(&path.key(name), RANGE_BOUND, kind(&value)).into()),
+        })
+    };
+
+    Ok(SurrealRange {
+        start: bound("start")?,
+        end: bound("end")?,
+    })
+}
+
+fn record_id_key_range(range: &SurrealRange) -> Option<SurrealRecordIdKeyRange> {
+    let bound = |bound: &Bound<SurrealValue>| match bound {
+        Bound::Included(value) => SurrealRecordIdKey::from_value(value.clone())
+            .ok()
+            .map(Bound::Included),
+        Bound::Excluded(value) => SurrealRecordIdKey::from_value(value.clone())
+            .ok()
+            .map(Bound::Excluded),
+        Bound::Unbounded => Some(Bound::Unbounded),
+    };
+
+    Some(SurrealRecordIdKeyRange {
+        start: bound(&range.start)?,
+        end: bound(&range.end)?,
     })
 }
 
+/// Formats a file reference as `bucket:/key`, the text the guest SDK parses.
+fn file_to_text(file: &SurrealFile) -> String {
+    let key = file.key();
+    if key.starts_with('/') {
+        format!("{}:{key}", file.bucket())
+    } else {
+        format!("{}:/{key}", file.bucket())
+    }
+}
+
+fn text_to_file(value: &str) -> Result<SurrealFile> {
+    match value.split_once(':') {
+        Some((bucket, key)) if !bucket.is_empty() && key.starts_with('/') => {
+            Ok(SurrealFile::new(bucket, key))
+        }
+        _ => bail!("invalid file reference `{value}`, expected `bucket:/key`"),
+    }
+}
+
 fn tagged_text(tag: &str, value: String) -> CborValue {
+    tagged(tag, CborValue::Text(value))
+}
+
+fn tagged(tag: &str, value: CborValue) -> CborValue {
+    let mut map = BTreeMap::new();
+    map.insert(CborValue::Text(tag.to_string()), value);
+    CborValue::Map(map)
+}
+
+fn geometry_to_geojson(value: SurrealGeometry) -> CborValue {
+    let (kind, member, body) = match value {
+        SurrealGeometry::Point(point) => ("Point", "coordinates", position_to_cbor(point.0)),
+        SurrealGeometry::Line(line) => ("LineString", "coordinates", line_to_cbor(&line)),
+        SurrealGeometry::Polygon(polygon) => ("Polygon", "coordinates", polygon_to_cbor(&polygon)),
+        SurrealGeometry::MultiPoint(points) => (
+            "MultiPoint",
+            "coordinates",
+            CborValue::Array(
+                points
+                    .0
+                    .iter()
+                    .map(|point| position_to_cbor(point.0))
+                    .collect(),
+            ),
+        ),
+        SurrealGeometry::MultiLine(lines) => (
+            "MultiLineString",
+            "coordinates",
+            CborValue::Array(lines.0.iter().map(line_to_cbor).collect()),
+        ),
+        SurrealGeometry::MultiPolygon(polygons) => (
+            "MultiPolygon",
+            "coordinates",
+            CborValue::Array(polygons.0.iter().map(polygon_to_cbor).collect()),
+        ),
+        SurrealGeometry::Collection(geometries) => (
+            "GeometryCollection",
+            "geometries",
+            CborValue::Array(geometries.into_iter().map(geometry_to_geojson).collect()),
+        ),
+    };
+
     let mut map = BTreeMap::new();
-    map.insert(CborValue::Text(tag.to_string()), CborValue::Text(value));
+    map.insert(
+        CborValue::Text("type".to_string()),
+        CborValue::Text(kind.to_string()),
+    );
+    map.insert(CborValue::Text(member.to_string()), body);
     CborValue::Map(map)
 }
 
+fn position_to_cbor(coord: Coord<f64>) -> CborValue {
+    CborValue::Array(vec![CborValue::Float(coord.x), CborValue::Float(coord.y)])
+}
+
+fn line_to_cbor(line: &LineString<f64>) -> CborValue {
+    CborValue::Array(line.0.iter().copied().map(position_to_cbor).collect())
+}
+
+fn polygon_to_cbor(polygon: &Polygon<f64>) -> CborValue {
+    CborValue::Array(
+        std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .map(line_to_cbor)
+            .collect(),
+    )
+}
+
+fn geojson_to_geometry(value: CborValue) -> Result<SurrealGeometry> {
+    let CborValue::Map(mut map) = value else {
+        bail!("invalid geometry value, expected a GeoJSON map")
+    };
+    let Some(CborValue::Text(kind)) = map.remove(&CborValue::Text("type".to_string())) else {
+        bail!("invalid geometry value, missing GeoJSON type")
+    };
+
+    if kind == "GeometryCollection" {
+        let Some(CborValue::Array(geometries)) =
+            map.remove(&CborValue::Text("geometries".to_string()))
+        else {
+            bail!("invalid geometry collection, missing geometries")
+        };
+        return Ok(SurrealGeometry::Collection(
+            geometries
+                .into_iter()
+                .map(geojson_to_geometry)
+                .collect::<Result<Vec<_>>>()?,
+        ));
+    }
+
+    let coordinates = map
+        .remove(&CborValue::Text("coordinates".to_string()))
+        .with_context(|| format!("invalid {kind} geometry, missing coordinates"))?;
+
+    Ok(match kind.as_str() {
+        "Point" => SurrealGeometry::Point(Point(cbor_to_position(coordinates)?)),
+        "LineString" => SurrealGeometry::Line(cbor_to_line(coordinates)?),
+        "Polygon" => SurrealGeometry::Polygon(cbor_to_polygon(coordinates)?),
+        "MultiPoint" => SurrealGeometry::MultiPoint(MultiPoint(
+            cbor_array(coordinates)?
+                .into_iter()
+                .map(|position| cbor_to_position(position).map(Point))
+                .collect::<Result<_>>()?,
+        )),
+        "MultiLineString" => SurrealGeometry::MultiLine(MultiLineString(
+            cbor_array(coordinates)?
+                .into_iter()
+                .map(cbor_to_line)
+                .collect::<Result<_>>()?,
+        )),
+        "MultiPolygon" => SurrealGeometry::MultiPolygon(MultiPolygon(
+            cbor_array(coordinates)?
+                .into_iter()
+                .map(cbor_to_polygon)
+                .collect::<Result<_>>()?,
+        )),
+        _ => bail!("unsupported geometry type {kind}"),
+    })
+}
+
+fn cbor_array(value: CborValue) -> Result<Vec<CborValue>> {
+    match value {
+        CborValue::Array(values) => Ok(values),
+        _ => bail!("invalid geometry coordinates, expected an array"),
+    }
+}
+
+fn cbor_to_position(value: CborValue) -> Result<Coord<f64>> {
+    let position = cbor_array(value)?;
+    let [x, y] = position.as_slice() else {
+        bail!("invalid geometry position, expected [x, y]")
+    };
+
+    Ok(Coord {
+        x: cbor_to_f64(x)?,
+        y: cbor_to_f64(y)?,
+    })
+}
+
+fn cbor_to_f64(value: &CborValue) -> Result<f64> {
+    match value {
+        CborValue::Float(value) => Ok(*value),
+        CborValue::Integer(value) => Ok(*value as f64),
+        _ => bail!("invalid geometry coordinate, expected a number"),
+    }
+}
+
+fn cbor_to_line(value: CborValue) -> Result<LineString<f64>> {
+    Ok(LineString(
+        cbor_array(value)?
+            .into_iter()
+            .map(cbor_to_position)
+            .collect::<Result<_>>()?,
+    ))
+}
+
+fn cbor_to_polygon(value: CborValue) -> Result<Polygon<f64>> {
+    let mut rings = cbor_array(value)?.into_iter().map(cbor_to_line);
+    let exterior = rings
+        .next()
+        .transpose()?
+        .unwrap_or_else(|| LineString(Vec::new()));
+    Ok(Polygon::new(exterior, rings.collect::<Result<_>>()?))
+}
+
 pub fn ordered_params(params: Vec<(String, SurrealValue)>) -> BTreeMap<String, SurrealValue> {
     params.into_iter().collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_cbor::Value as CborValue;
     use serde_json::json;
     use surrealdb_types::{RecordId, RecordIdKey, Value};
 
-    use super::cbor_slice_to_surreal;
+    use super::{
+        IntegerPolicy, ParamDecodeError, RECORD_ID_MAP, WireEncoding, cbor_param_to_surreal,
+        surreal_to_cbor_bytes,
+    };
 
     #[test]
     fn decodes_tagged_scalars_into_native_values() {
@@ -227,7 +849,9 @@ mod tests {
             &json!({"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"}),
         )
         .unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::Uuid(uuid) => {
@@ -237,14 +861,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn decodes_tagged_datetime_into_native_value() {
+        let bytes =
+            serde_cbor::to_vec(&json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
+
+        assert!(
+            matches!(value, Value::Datetime(_)),
+            "expected datetime, got {value:?}"
+        );
+    }
+
+    #[test]
+    fn decodes_cbor_datetime_tags_into_native_values() {
+        let text = CborValue::Tag(0, Box::new(CborValue::Text("2024-03-15T12:34:56Z".into())));
+        let epoch = CborValue::Tag(1, Box::new(CborValue::Integer(1_710_506_096)));
+
+        for tagged in [text, epoch] {
+            let value = cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&tagged).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy,
+            )
+            .unwrap();
+            assert!(
+                matches!(value, Value::Datetime(_)),
+                "expected datetime, got {value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_cbor_uuid_tag_into_native_value() {
+        let uuid = uuid::Uuid::parse_str("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d").unwrap();
+        let tagged = CborValue::Tag(37, Box::new(CborValue::Bytes(uuid.as_bytes().to_vec())));
+
+        match cbor_param_to_surreal(
+            "param",
+            &serde_cbor::to_vec(&tagged).unwrap(),
+            IntegerPolicy::Strict,
+            WireEncoding::Legacy,
+        )
+        .unwrap()
+        {
+            Value::Uuid(value) => assert_eq!(value.to_string(), uuid.to_string()),
+            other => panic!("expected uuid, got {other:?}"),
+        }
+
+        let short = CborValue::Tag(37, Box::new(CborValue::Bytes(vec![1, 2, 3])));
+        assert!(
+            cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&short).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn encodes_native_datetime_as_tagged_scalar() {
+        let bytes =
+            serde_cbor::to_vec(&json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
+        let encoded = surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(&encoded).unwrap(),
+            json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})
+        );
+    }
+
     #[test]
     fn decodes_tagged_record_id_keys_into_native_values() {
-        let bytes = serde_cbor::to_vec(&json!({
+        let bytes = serde_cbor::to_vec(&json!({"$surrealdb::record_id": {
             "table": "person",
             "key": {"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"}
-        }))
+        }}))
         .unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::RecordId(RecordId { table, key }) => {
@@ -257,8 +961,13 @@ mod tests {
 
     #[test]
     fn decodes_record_id_with_string_key() {
-        let bytes = serde_cbor::to_vec(&json!({"table": "person", "key": "demo"})).unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let bytes = serde_cbor::to_vec(
+            &json!({"$surrealdb::record_id": {"table": "person", "key": "demo"}}),
+        )
+        .unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::RecordId(RecordId { table, key }) => {
@@ -271,8 +980,12 @@ mod tests {
 
     #[test]
     fn decodes_record_id_with_number_key() {
-        let bytes = serde_cbor::to_vec(&json!({"table": "person", "key": 42})).unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let bytes =
+            serde_cbor::to_vec(&json!({"$surrealdb::record_id": {"table": "person", "key": 42}}))
+                .unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::RecordId(RecordId { table, key }) => {
@@ -285,9 +998,14 @@ mod tests {
 
     #[test]
     fn decodes_record_id_with_array_key() {
-        let bytes =
-            serde_cbor::to_vec(&json!({"table": "person", "key": ["tenant-a", 42]})).unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let bytes = serde_cbor::to_vec(&json!({"$surrealdb::record_id": {
+            "table": "person",
+            "key": ["tenant-a", 42]
+        }}))
+        .unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::RecordId(RecordId { table, key }) => {
@@ -300,12 +1018,14 @@ mod tests {
 
     #[test]
     fn decodes_record_id_with_object_key() {
-        let bytes = serde_cbor::to_vec(&json!({
+        let bytes = serde_cbor::to_vec(&json!({"$surrealdb::record_id": {
             "table": "person",
             "key": {"tenant": "demo", "shard": 1}
-        }))
+        }}))
         .unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         match value {
             Value::RecordId(RecordId { table, key }) => {
@@ -316,10 +1036,321 @@ mod tests {
         }
     }
 
+    #[test]
+    fn round_trips_tagged_geometry() {
+        let geometry = json!({"$surrealdb::geometry": {
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]],
+        }});
+        let value = cbor_param_to_surreal(
+            "param",
+            &serde_cbor::to_vec(&geometry).unwrap(),
+            IntegerPolicy::Strict,
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+
+        assert!(
+            matches!(value, Value::Geometry(_)),
+            "expected geometry, got {value:?}"
+        );
+        let encoded = surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(&encoded).unwrap(),
+            geometry
+        );
+    }
+
+    #[test]
+    fn rejects_or_widens_integers_beyond_i64_by_policy() {
+        let bytes = serde_cbor::to_vec(&json!({"ids": [1, u64::MAX]})).unwrap();
+
+        let error =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap_err();
+        assert_eq!(error.pointer, "/param/ids/1");
+        assert_eq!(error.expected, "an integer within the i64 range");
+        assert_eq!(
+            error.reason.as_deref(),
+            Some("18446744073709551615 needs IntegerPolicy::LosslessDecimal to bind as a decimal")
+        );
+
+        let value = cbor_param_to_surreal(
+            "param",
+            &bytes,
+            IntegerPolicy::LosslessDecimal,
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(
+                &surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap()
+            )
+            .unwrap(),
+            json!({"ids": [1, {"$surrealdb::decimal": "18446744073709551615"}]})
+        );
+    }
+
+    #[test]
+    fn decodes_cbor_bignums_within_the_decimal_range() {
+        let bignum =
+            |tag: u64, bytes: Vec<u8>| CborValue::Tag(tag, Box::new(CborValue::Bytes(bytes)));
+        let bytes = serde_cbor::to_vec(&CborValue::Array(vec![
+            bignum(2, vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            bignum(3, vec![0, 5]),
+        ]))
+        .unwrap();
+
+        let value = cbor_param_to_surreal(
+            "param",
+            &bytes,
+            IntegerPolicy::LosslessDecimal,
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(
+                &surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap()
+            )
+            .unwrap(),
+            json!([{"$surrealdb::decimal": "18446744073709551616"}, -6])
+        );
+
+        for (wire, message) in [
+            (
+                bignum(2, vec![0xff; 13]),
+                "/param/0: expected an integer within the decimal range, found integer \
+                 (20282409603651670423947251286015 does not fit in 96 bits)",
+            ),
+            (
+                bignum(3, vec![0xff; 17]),
+                "/param/0: expected an integer within the decimal range, found integer \
+                 (a 17 byte bignum does not fit in 96 bits)",
+            ),
+        ] {
+            let bytes = serde_cbor::to_vec(&CborValue::Array(vec![wire])).unwrap();
+            let error = cbor_param_to_surreal(
+                "param",
+                &bytes,
+                IntegerPolicy::LosslessDecimal,
+                WireEncoding::Legacy,
+            )
+            .unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn round_trips_tagged_ranges_files_and_record_id_ranges() {
+        let range =
+            json!({"$surrealdb::range": {"start": {"included": 1}, "end": {"excluded": 5}}});
+        let file = json!({"$surrealdb::file": "avatars:/users/demo.png"});
+        let ids = json!({"$surrealdb::record_id": {"table": "person", "key": {"$surrealdb::range": {
+            "start": {"included": 1},
+            "end": null,
+        }}}});
+
+        for (wire, check) in [
+            (
+                range,
+                (|value| matches!(value, Value::Range(_))) as fn(&Value) -> bool,
+            ),
+            (file, |value| matches!(value, Value::File(_))),
+            (
+                ids,
+                |value| matches!(value, Value::RecordId(id) if matches!(id.key, RecordIdKey::Range(_))),
+            ),
+        ] {
+            let value = cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy,
+            )
+            .unwrap();
+            assert!(check(&value), "unexpected value {value:?} for {wire}");
+
+            let encoded = surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap();
+            assert_eq!(
+                serde_cbor::from_slice::<serde_json::Value>(&encoded).unwrap(),
+                wire
+            );
+        }
+    }
+
+    #[test]
+    fn reads_untagged_record_id_maps_as_objects() {
+        let decode = |wire: &serde_json::Value| {
+            cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy,
+            )
+        };
+
+        for wire in [
+            json!({"table": "person", "key": "demo"}),
+            json!({"table": "kitchen", "key": "abc", "legs": 4}),
+        ] {
+            let value = decode(&wire).unwrap();
+            assert!(matches!(value, Value::Object(_)), "got {value:?}");
+        }
+
+        let error =
+            decode(&json!({"$surrealdb::record_id": {"table": 1, "key": "demo"}})).unwrap_err();
+        assert_eq!(error.pointer, "/param/$surrealdb::record_id");
+        assert_eq!(error.expected, RECORD_ID_MAP);
+    }
+
+    #[test]
+    fn rejects_invalid_range_bounds_and_file_references() {
+        for wire in [
+            json!({"$surrealdb::range": {"start": {"above": 1}, "end": null}}),
+            json!({"$surrealdb::range": 5}),
+            json!({"$surrealdb::file": "no-key"}),
+        ] {
+            assert!(
+                cbor_param_to_surreal(
+                    "param",
+                    &serde_cbor::to_vec(&wire).unwrap(),
+                    IntegerPolicy::Strict,
+                    WireEncoding::Legacy
+                )
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn reports_the_path_and_kinds_of_invalid_params() {
+        let decode = |wire: serde_json::Value| {
+            cbor_param_to_surreal(
+                "document",
+                &serde_cbor::to_vec(&wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy,
+            )
+            .unwrap_err()
+        };
+
+        let error =
+            decode(json!({"items": [{}, {}, {}, {"price": {"$surrealdb::decimal": "12,50"}}]}));
+        assert_eq!(error.pointer, "/document/items/3/price/$surrealdb::decimal");
+        assert_eq!((error.expected, error.actual), ("decimal text", "text"));
+        assert!(error.reason.is_some());
+
+        assert_eq!(
+            decode(json!({"a/b": {"$surrealdb::uuid": 7}})),
+            ParamDecodeError {
+                pointer: "/document/a~1b/$surrealdb::uuid".to_string(),
+                expected: "uuid text",
+                actual: "integer",
+                reason: None,
+            }
+        );
+        assert_eq!(
+            decode(json!({"$surrealdb::range": {"start": {"above": 1}}})).to_string(),
+            "/document/$surrealdb::range/start: expected null or an included or excluded bound, \
+             found map"
+        );
+
+        let error = cbor_param_to_surreal(
+            "document",
+            &[0xff],
+            IntegerPolicy::Strict,
+            WireEncoding::Legacy,
+        )
+        .unwrap_err();
+        assert_eq!(
+            (error.pointer.as_str(), error.expected, error.actual),
+            ("/document", "a CBOR value", "malformed CBOR")
+        );
+    }
+
+    #[test]
+    fn keeps_none_null_and_sets_apart() {
+        for (wire, check) in [
+            (
+                json!({"$surrealdb::none": null}),
+                (|value| matches!(value, Value::None)) as fn(&Value) -> bool,
+            ),
+            (json!(null), |value| matches!(value, Value::Null)),
+            (json!({"$surrealdb::set": ["a", "b"]}), |value| {
+                matches!(value, Value::Set(_))
+            }),
+            (json!([{"$surrealdb::none": null}, null]), |value| {
+                matches!(value, Value::Array(_))
+            }),
+        ] {
+            let value = cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy,
+            )
+            .unwrap();
+            assert!(check(&value), "unexpected value {value:?} for {wire}");
+
+            let encoded = surreal_to_cbor_bytes(value, WireEncoding::Legacy).unwrap();
+            assert_eq!(
+                serde_cbor::from_slice::<serde_json::Value>(&encoded).unwrap(),
+                wire
+            );
+        }
+
+        assert!(
+            cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&json!({"$surrealdb::set": 1})).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Legacy
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn leaves_none_object_fields_out() {
+        let bytes = serde_cbor::to_vec(&json!({
+            "name": "demo",
+            "nickname": {"$surrealdb::none": null},
+            "note": null,
+        }))
+        .unwrap();
+        let encoded = surreal_to_cbor_bytes(
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap(),
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<serde_json::Value>(&encoded).unwrap(),
+            json!({"name": "demo", "note": null})
+        );
+    }
+
+    #[test]
+    fn decodes_untagged_geojson_as_plain_object() {
+        let bytes =
+            serde_cbor::to_vec(&json!({"type": "Point", "coordinates": [1.0, 2.0]})).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
+
+        assert!(
+            matches!(value, Value::Object(_)),
+            "expected plain object, got {value:?}"
+        );
+    }
+
     #[test]
     fn decodes_single_entry_object_as_plain_object() {
         let bytes = serde_cbor::to_vec(&json!({"shard": 1})).unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         assert!(
             matches!(value, Value::Object(_)),
@@ -330,11 +1361,172 @@ mod tests {
     #[test]
     fn decodes_single_entry_object_with_non_reserved_tag_as_plain_object() {
         let bytes = serde_cbor::to_vec(&json!({"custom_tag": "some_value"})).unwrap();
-        let value = cbor_slice_to_surreal(&bytes).unwrap();
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Legacy)
+                .unwrap();
 
         assert!(
             matches!(value, Value::Object(_)),
             "expected plain object for non-reserved tag, got {value:?}"
         );
     }
+
+    #[test]
+    fn round_trips_native_tags() {
+        let tag = |tag, value| CborValue::Tag(tag, Box::new(value));
+        let uuid = uuid::Uuid::parse_str("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d").unwrap();
+        let point = |x, y| {
+            tag(
+                88,
+                CborValue::Array(vec![CborValue::Float(x), CborValue::Float(y)]),
+            )
+        };
+        let wire = CborValue::Array(vec![
+            tag(6, CborValue::Null),
+            tag(7, CborValue::Text("user".into())),
+            tag(
+                8,
+                CborValue::Array(vec![CborValue::Text("user".into()), CborValue::Integer(1)]),
+            ),
+            tag(
+                12,
+                CborValue::Array(vec![
+                    CborValue::Integer(1_710_506_096),
+                    CborValue::Integer(500),
+                ]),
+            ),
+            tag(37, CborValue::Bytes(uuid.as_bytes().to_vec())),
+            tag(10, CborValue::Text("1.50".into())),
+            tag(
+                49,
+                CborValue::Array(vec![
+                    tag(50, CborValue::Integer(1)),
+                    tag(51, CborValue::Integer(9)),
+                ]),
+            ),
+            tag(
+                55,
+                CborValue::Array(vec![
+                    CborValue::Text("avatars".into()),
+                    CborValue::Text("/a.png".into()),
+                ]),
+            ),
+            tag(56, CborValue::Array(vec![CborValue::Text("a".into())])),
+            tag(89, CborValue::Array(vec![point(1.0, 2.0), point(3.0, 4.0)])),
+        ]);
+        let bytes = serde_cbor::to_vec(&wire).unwrap();
+
+        let value =
+            cbor_param_to_surreal("param", &bytes, IntegerPolicy::Strict, WireEncoding::Native)
+                .unwrap();
+        let Value::Array(values) = &value else {
+            panic!("expected array, got {value:?}");
+        };
+        assert!(matches!(values[0], Value::None));
+        assert!(matches!(values[1], Value::Table(_)));
+        assert!(matches!(values[2], Value::RecordId(_)));
+        assert!(matches!(values[3], Value::Datetime(_)));
+        assert!(matches!(values[4], Value::Uuid(_)));
+        assert!(matches!(values[5], Value::Number(_)));
+        assert!(matches!(values[6], Value::Range(_)));
+        assert!(matches!(values[7], Value::File(_)));
+        assert!(matches!(values[8], Value::Set(_)));
+        assert!(matches!(values[9], Value::Geometry(_)));
+
+        assert_eq!(
+            surreal_to_cbor_bytes(value, WireEncoding::Native).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn decodes_native_durations_and_record_id_ranges() {
+        let tag = |tag, value| CborValue::Tag(tag, Box::new(value));
+        let compact = tag(
+            14,
+            CborValue::Array(vec![CborValue::Integer(90), CborValue::Integer(5)]),
+        );
+        let text = tag(13, CborValue::Text("1m30s5ns".into()));
+
+        for wire in [compact, text] {
+            let value = cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Native,
+            )
+            .unwrap();
+            let Value::Duration(duration) = value else {
+                panic!("expected duration, got {value:?}");
+            };
+            assert_eq!(duration.to_string(), "1m30s5ns");
+        }
+
+        let range = tag(
+            8,
+            CborValue::Array(vec![
+                CborValue::Text("user".into()),
+                tag(
+                    49,
+                    CborValue::Array(vec![tag(50, CborValue::Integer(1)), CborValue::Null]),
+                ),
+            ]),
+        );
+        let value = cbor_param_to_surreal(
+            "param",
+            &serde_cbor::to_vec(&range).unwrap(),
+            IntegerPolicy::Strict,
+            WireEncoding::Native,
+        )
+        .unwrap();
+        assert!(
+            matches!(
+                &value,
+                Value::RecordId(RecordId {
+                    key: RecordIdKey::Range(_),
+                    ..
+                })
+            ),
+            "expected record id range, got {value:?}"
+        );
+    }
+
+    #[test]
+    fn decodes_maps_as_plain_objects_in_native_encoding() {
+        for wire in [
+            json!({"table": "user", "key": 1}),
+            json!({"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"}),
+        ] {
+            let value = cbor_param_to_surreal(
+                "param",
+                &serde_cbor::to_vec(&wire).unwrap(),
+                IntegerPolicy::Strict,
+                WireEncoding::Native,
+            )
+            .unwrap();
+            assert!(
+                matches!(value, Value::Object(_)),
+                "expected plain object for {wire}, got {value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reports_the_path_of_invalid_native_tags() {
+        let wire = CborValue::Array(vec![CborValue::Tag(
+            8,
+            Box::new(CborValue::Text("user:1".into())),
+        )]);
+        let error = cbor_param_to_surreal(
+            "ids",
+            &serde_cbor::to_vec(&wire).unwrap(),
+            IntegerPolicy::Strict,
+            WireEncoding::Native,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.pointer, "/ids/0");
+        assert_eq!(error.expected, "[table, key]");
+        assert_eq!(error.actual, "text");
+    }
 }
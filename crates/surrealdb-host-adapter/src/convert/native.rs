@@ -0,0 +1,526 @@
+//! SurrealDB's registered CBOR tags, the encoding its RPC protocol speaks.
+
+use std::ops::Bound;
+use std::str::FromStr;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, TimeZone, Utc};
+use geo_types::{Coord, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon};
+use rust_decimal::Decimal as RustDecimal;
+use serde_cbor::Value as CborValue;
+use surrealdb_types::{
+    Bytes as SurrealBytes, Datetime as SurrealDatetime, Duration as SurrealDuration,
+    File as SurrealFile, Geometry as SurrealGeometry, Number as SurrealNumber,
+    Object as SurrealObject, Range as SurrealRange, RecordId as SurrealRecordId,
+    RecordIdKey as SurrealRecordIdKey, Set as SurrealSet, SurrealValue as _, Uuid as SurrealUuid,
+    Value as SurrealValue,
+};
+
+use super::{
+    CBOR_DATETIME_EPOCH_TAG, CBOR_DATETIME_TEXT_TAG, CBOR_NEGATIVE_BIGNUM_TAG,
+    CBOR_POSITIVE_BIGNUM_TAG, DATETIME_TEXT, IntegerPolicy, Path, RANGE_BOUND, UUID_BYTES,
+    bignum_to_surreal, cbor_to_f64, epoch_to_datetime, integer_to_surreal, kind, locate,
+    map_key_to_string, mismatch, object_to_cbor, record_id_key_range, text_to_datetime,
+};
+
+const NONE: u64 = 6;
+const TABLE: u64 = 7;
+const RECORD_ID: u64 = 8;
+const UUID_TEXT: u64 = 9;
+const DECIMAL_TEXT: u64 = 10;
+const DATETIME_COMPACT: u64 = 12;
+const DURATION_TEXT: u64 = 13;
+const DURATION_COMPACT: u64 = 14;
+const UUID: u64 = 37;
+const RANGE: u64 = 49;
+const BOUND_INCLUDED: u64 = 50;
+const BOUND_EXCLUDED: u64 = 51;
+const FILE: u64 = 55;
+const SET: u64 = 56;
+const GEOMETRY_POINT: u64 = 88;
+const GEOMETRY_LINE: u64 = 89;
+const GEOMETRY_POLYGON: u64 = 90;
+const GEOMETRY_MULTIPOINT: u64 = 91;
+const GEOMETRY_MULTILINE: u64 = 92;
+const GEOMETRY_MULTIPOLYGON: u64 = 93;
+const GEOMETRY_COLLECTION: u64 = 94;
+
+pub(super) fn surreal_to_cbor(value: SurrealValue) -> Result<CborValue> {
+    Ok(match value {
+        SurrealValue::None => tagged(NONE, CborValue::Null),
+        SurrealValue::Null => CborValue::Null,
+        SurrealValue::Bool(v) => CborValue::Bool(v),
+        SurrealValue::Number(SurrealNumber::Int(v)) => CborValue::Integer(v as i128),
+        SurrealValue::Number(SurrealNumber::Float(v)) => CborValue::Float(v),
+        SurrealValue::Number(SurrealNumber::Decimal(v)) => {
+            tagged(DECIMAL_TEXT, CborValue::Text(v.to_string()))
+        }
+        SurrealValue::String(v) => CborValue::Text(v),
+        SurrealValue::Bytes(v) => CborValue::Bytes(v.into_inner().to_vec()),
+        SurrealValue::Duration(v) => tagged(DURATION_TEXT, CborValue::Text(v.to_string())),
+        SurrealValue::Datetime(v) => {
+            let datetime = DateTime::<Utc>::from(v);
+            tagged(
+                DATETIME_COMPACT,
+                CborValue::Array(vec![
+                    CborValue::Integer(i128::from(datetime.timestamp())),
+                    CborValue::Integer(i128::from(datetime.timestamp_subsec_nanos())),
+                ]),
+            )
+        }
+        SurrealValue::Uuid(v) => uuid_to_cbor(&v)?,
+        SurrealValue::Geometry(v) => geometry_to_cbor(v),
+        SurrealValue::Table(v) => tagged(TABLE, CborValue::Text(v.to_string())),
+        SurrealValue::RecordId(v) => tagged(
+            RECORD_ID,
+            CborValue::Array(vec![
+                CborValue::Text(v.table.to_string()),
+                record_id_key_to_cbor(v.key)?,
+            ]),
+        ),
+        SurrealValue::File(v) => tagged(
+            FILE,
+            CborValue::Array(vec![
+                CborValue::Text(v.bucket().to_string()),
+                CborValue::Text(v.key().to_string()),
+            ]),
+        ),
+        SurrealValue::Range(v) => range_to_cbor(v.start, v.end, surreal_to_cbor)?,
+        // SurrealDB has no regex tag, so regexes travel as their source text.
+        SurrealValue::Regex(v) => CborValue::Text(v.regex().as_str().to_string()),
+        SurrealValue::Array(v) => CborValue::Array(
+            v.into_iter()
+                .map(surreal_to_cbor)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        SurrealValue::Object(v) => object_to_cbor(v, surreal_to_cbor)?,
+        SurrealValue::Set(v) => tagged(
+            SET,
+            CborValue::Array(
+                v.into_iter()
+                    .map(surreal_to_cbor)
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+        ),
+    })
+}
+
+fn record_id_key_to_cbor(value: SurrealRecordIdKey) -> Result<CborValue> {
+    Ok(match value {
+        SurrealRecordIdKey::Number(v) => CborValue::Integer(v as i128),
+        SurrealRecordIdKey::String(v) => CborValue::Text(v),
+        SurrealRecordIdKey::Uuid(v) => uuid_to_cbor(&v)?,
+        SurrealRecordIdKey::Array(v) => CborValue::Array(
+            v.into_iter()
+                .map(surreal_to_cbor)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        SurrealRecordIdKey::Object(v) => object_to_cbor(v, surreal_to_cbor)?,
+        SurrealRecordIdKey::Range(v) => range_to_cbor(v.start, v.end, record_id_key_to_cbor)?,
+    })
+}
+
+fn uuid_to_cbor(value: &SurrealUuid) -> Result<CborValue> {
+    let uuid = uuid::Uuid::parse_str(&value.to_string())?;
+    Ok(tagged(UUID, CborValue::Bytes(uuid.as_bytes().to_vec())))
+}
+
+fn range_to_cbor<T>(
+    start: Bound<T>,
+    end: Bound<T>,
+    convert: fn(T) -> Result<CborValue>,
+) -> Result<CborValue> {
+    let bound = |bound: Bound<T>| -> Result<CborValue> {
+        Ok(match bound {
+            Bound::Included(value) => tagged(BOUND_INCLUDED, convert(value)?),
+            Bound::Excluded(value) => tagged(BOUND_EXCLUDED, convert(value)?),
+            Bound::Unbounded => CborValue::Null,
+        })
+    };
+
+    Ok(tagged(
+        RANGE,
+        CborValue::Array(vec![bound(start)?, bound(end)?]),
+    ))
+}
+
+fn geometry_to_cbor(value: SurrealGeometry) -> CborValue {
+    match value {
+        SurrealGeometry::Point(point) => point_to_cbor(point.0),
+        SurrealGeometry::Line(line) => line_to_cbor(&line),
+        SurrealGeometry::Polygon(polygon) => polygon_to_cbor(&polygon),
+        SurrealGeometry::MultiPoint(points) => tagged(
+            GEOMETRY_MULTIPOINT,
+            CborValue::Array(
+                points
+                    .0
+                    .iter()
+                    .map(|point| point_to_cbor(point.0))
+                    .collect(),
+            ),
+        ),
+        SurrealGeometry::MultiLine(lines) => tagged(
+            GEOMETRY_MULTILINE,
+            CborValue::Array(lines.0.iter().map(line_to_cbor).collect()),
+        ),
+        SurrealGeometry::MultiPolygon(polygons) => tagged(
+            GEOMETRY_MULTIPOLYGON,
+            CborValue::Array(polygons.0.iter().map(polygon_to_cbor).collect()),
+        ),
+        SurrealGeometry::Collection(geometries) => tagged(
+            GEOMETRY_COLLECTION,
+            CborValue::Array(geometries.into_iter().map(geometry_to_cbor).collect()),
+        ),
+    }
+}
+
+fn point_to_cbor(coord: Coord<f64>) -> CborValue {
+    tagged(
+        GEOMETRY_POINT,
+        CborValue::Array(vec![CborValue::Float(coord.x), CborValue::Float(coord.y)]),
+    )
+}
+
+fn line_to_cbor(line: &LineString<f64>) -> CborValue {
+    tagged(
+        GEOMETRY_LINE,
+        CborValue::Array(line.0.iter().copied().map(point_to_cbor).collect()),
+    )
+}
+
+fn polygon_to_cbor(polygon: &Polygon<f64>) -> CborValue {
+    tagged(
+        GEOMETRY_POLYGON,
+        CborValue::Array(
+            std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .map(line_to_cbor)
+                .collect(),
+        ),
+    )
+}
+
+fn tagged(tag: u64, value: CborValue) -> CborValue {
+    CborValue::Tag(tag, Box::new(value))
+}
+
+/// Converts one value, attaching its location to failures that do not carry one yet.
+pub(super) fn cbor_to_surreal(
+    value: CborValue,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    let actual = kind(&value);
+    convert_cbor(value, integers, path).map_err(|error| locate(error, path, actual))
+}
+
+fn convert_cbor(
+    value: CborValue,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    Ok(match value {
+        CborValue::Null => SurrealValue::Null,
+        CborValue::Bool(value) => SurrealValue::Bool(value),
+        CborValue::Integer(value) => integer_to_surreal(value, integers, path)?,
+        CborValue::Float(value) => SurrealValue::Number(SurrealNumber::Float(value)),
+        CborValue::Bytes(value) => SurrealValue::Bytes(SurrealBytes::from(value)),
+        CborValue::Text(value) => SurrealValue::String(value),
+        CborValue::Array(values) => {
+            SurrealValue::Array(items(values, integers, path)?.into_iter().collect())
+        }
+        CborValue::Map(values) => {
+            let mut object = SurrealObject::new();
+            for (key, value) in values {
+                let key = map_key_to_string(key, path)?;
+                let value = cbor_to_surreal(value, integers, &path.key(&key))?;
+                object.insert(key, value);
+            }
+            SurrealValue::Object(object)
+        }
+        CborValue::Tag(tag, value) => tag_to_surreal(tag, *value, integers, path)?,
+        value => return Err(mismatch(path, "a SurrealDB value", kind(&value)).into()),
+    })
+}
+
+fn tag_to_surreal(
+    tag: u64,
+    value: CborValue,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealValue> {
+    Ok(match (tag, value) {
+        (NONE, CborValue::Null) => SurrealValue::None,
+        (NONE, value) => return Err(mismatch(path, "null", kind(&value)).into()),
+        (TABLE, CborValue::Text(table)) => SurrealValue::Table(table.into()),
+        (TABLE, value) => return Err(mismatch(path, "table name text", kind(&value)).into()),
+        (RECORD_ID, value) => SurrealValue::RecordId(record_id(value, integers, path)?),
+        (CBOR_DATETIME_TEXT_TAG, CborValue::Text(value)) => SurrealValue::Datetime(
+            text_to_datetime(&value)
+                .map_err(|error| mismatch(path, DATETIME_TEXT, "text").because(error))?,
+        ),
+        (CBOR_DATETIME_TEXT_TAG, value) => {
+            return Err(mismatch(path, DATETIME_TEXT, kind(&value)).into());
+        }
+        (CBOR_DATETIME_EPOCH_TAG, value) => {
+            let actual = kind(&value);
+            SurrealValue::Datetime(epoch_to_datetime(value).map_err(|error| {
+                mismatch(path, "epoch seconds within the datetime range", actual).because(error)
+            })?)
+        }
+        (DATETIME_COMPACT, value) => {
+            let actual = kind(&value);
+            SurrealValue::Datetime(compact_datetime(value).ok_or_else(|| {
+                mismatch(
+                    path,
+                    "[seconds, nanoseconds] within the datetime range",
+                    actual,
+                )
+            })?)
+        }
+        (UUID_TEXT, CborValue::Text(value)) => SurrealValue::Uuid(
+            SurrealUuid::try_from(value)
+                .map_err(|error| mismatch(path, "uuid text", "text").because(error))?,
+        ),
+        (UUID_TEXT, value) => return Err(mismatch(path, "uuid text", kind(&value)).into()),
+        (UUID, CborValue::Bytes(value)) => SurrealValue::Uuid(SurrealUuid::try_from(
+            uuid::Uuid::from_slice(&value)
+                .map_err(|error| mismatch(path, UUID_BYTES, "bytes").because(error))?
+                .to_string(),
+        )?),
+        (UUID, value) => return Err(mismatch(path, UUID_BYTES, kind(&value)).into()),
+        (DECIMAL_TEXT, CborValue::Text(value)) => SurrealValue::Number(SurrealNumber::Decimal(
+            RustDecimal::from_str(&value)
+                .map_err(|error| mismatch(path, "decimal text", "text").because(error))?,
+        )),
+        (DECIMAL_TEXT, value) => return Err(mismatch(path, "decimal text", kind(&value)).into()),
+        (DURATION_TEXT, CborValue::Text(value)) => SurrealValue::Duration(
+            SurrealDuration::from_str(&value)
+                .map_err(|error| mismatch(path, "duration text", "text").because(error))?,
+        ),
+        (DURATION_TEXT, value) => {
+            return Err(mismatch(path, "duration text", kind(&value)).into());
+        }
+        (DURATION_COMPACT, value) => {
+            let actual = kind(&value);
+            SurrealValue::Duration(
+                compact_duration(value)
+                    .ok_or_else(|| mismatch(path, "[seconds, nanoseconds]", actual))?,
+            )
+        }
+        (tag @ (CBOR_POSITIVE_BIGNUM_TAG | CBOR_NEGATIVE_BIGNUM_TAG), value) => {
+            bignum_to_surreal(tag, value, integers, path)?
+        }
+        (RANGE, value) => SurrealValue::Range(Box::new(range(value, integers, path)?)),
+        (FILE, value) => {
+            let actual = kind(&value);
+            match value {
+                CborValue::Array(parts) => match <[CborValue; 2]>::try_from(parts) {
+                    Ok([CborValue::Text(bucket), CborValue::Text(key)]) => {
+                        SurrealValue::File(SurrealFile::new(&bucket, &key))
+                    }
+                    _ => return Err(mismatch(path, "[bucket, key] text", actual).into()),
+                },
+                _ => return Err(mismatch(path, "[bucket, key] text", actual).into()),
+            }
+        }
+        (SET, CborValue::Array(values)) => {
+            SurrealValue::Set(SurrealSet::from(items(values, integers, path)?))
+        }
+        (SET, value) => return Err(mismatch(path, "an array", kind(&value)).into()),
+        (GEOMETRY_POINT..=GEOMETRY_COLLECTION, value) => {
+            let actual = kind(&value);
+            SurrealValue::Geometry(geometry(tag, value).map_err(|error| {
+                mismatch(path, "a tagged SurrealDB geometry", actual).because(error)
+            })?)
+        }
+        (_, value) => cbor_to_surreal(value, integers, path)?,
+    })
+}
+
+fn items(
+    values: Vec<CborValue>,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<Vec<SurrealValue>> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| cbor_to_surreal(value, integers, &path.index(index)))
+        .collect()
+}
+
+/// Decodes a `[table, key]` record id. A range key reads as a record id range.
+fn record_id(
+    value: CborValue,
+    integers: IntegerPolicy,
+    path: &Path<'_>,
+) -> Result<SurrealRecordId> {
+    const EXPECTED: &str = "[table, key]";
+    let CborValue::Array(parts) = value else {
+        return Err(mismatch(path, EXPECTED, kind(&value)).into());
+    };
+    let Ok([table, key]) = <[CborValue; 2]>::try_from(parts) else {
+        return Err(mismatch(path, EXPECTED, "array").into());
+    };
+    let table = match table {
+        CborValue::Tag(TABLE, table) => *table,
+        table => table,
+    };
+    let CborValue::Text(table) = table else {
+        return Err(mismatch(&path.index(0), "table name text", kind(&table)).into());
+    };
+
+    let key_path = path.index(1);
+    let key = match cbor_to_surreal(key, integers, &key_path)? {
+        SurrealValue::Range(range) => record_id_key_range(&range)
+            .map(|range| SurrealRecordIdKey::Range(Box::new(range)))
+            .ok_or_else(|| mismatch(&key_path, "record id key bounds", "range"))?,
+        key => SurrealRecordIdKey::from_value(key)
+            .map_err(|error| mismatch(&key_path, "a record id key", "value").because(error))?,
+    };
+    Ok(SurrealRecordId::new(table, key))
+}
+
+fn range(value: CborValue, integers: IntegerPolicy, path: &Path<'_>) -> Result<SurrealRange> {
+    const EXPECTED: &str = "[start, end] bounds";
+    let CborValue::Array(bounds) = value else {
+        return Err(mismatch(path, EXPECTED, kind(&value)).into());
+    };
+    let Ok([start, end]) = <[CborValue; 2]>::try_from(bounds) else {
+        return Err(mismatch(path, EXPECTED, "array").into());
+    };
+    let bound = |value: CborValue, index: usize| -> Result<Bound<SurrealValue>> {
+        let path = path.index(index);
+        Ok(match value {
+            CborValue::Null => Bound::Unbounded,
+            CborValue::Tag(BOUND_INCLUDED, value) => {
+                Bound::Included(cbor_to_surreal(*value, integers, &path)?)
+            }
+            CborValue::Tag(BOUND_EXCLUDED, value) => {
+                Bound::Excluded(cbor_to_surreal(*value, integers, &path)?)
+            }
+            value => return Err(mismatch(&path, RANGE_BOUND, kind(&value)).into()),
+        })
+    };
+
+    Ok(SurrealRange {
+        start: bound(start, 0)?,
+        end: bound(end, 1)?,
+    })
+}
+
+fn seconds_and_nanos(value: CborValue) -> Option<(i64, u32)> {
+    let CborValue::Array(parts) = value else {
+        return None;
+    };
+    match parts.as_slice() {
+        [] => Some((0, 0)),
+        [CborValue::Integer(secs)] => Some((i64::try_from(*secs).ok()?, 0)),
+        [CborValue::Integer(secs), CborValue::Integer(nanos)] => Some((
+            i64::try_from(*secs).ok()?,
+            u32::try_from(*nanos)
+                .ok()
+                .filter(|nanos| *nanos < 1_000_000_000)?,
+        )),
+        _ => None,
+    }
+}
+
+fn compact_datetime(value: CborValue) -> Option<SurrealDatetime> {
+    let (secs, nanos) = seconds_and_nanos(value)?;
+    Utc.timestamp_opt(secs, nanos)
+        .single()
+        .map(SurrealDatetime::from)
+}
+
+fn compact_duration(value: CborValue) -> Option<SurrealDuration> {
+    let (secs, nanos) = seconds_and_nanos(value)?;
+    let secs = u64::try_from(secs).ok()?;
+    SurrealDuration::from_str(&format!("{secs}s{nanos}ns")).ok()
+}
+
+fn geometry(tag: u64, value: CborValue) -> Result<SurrealGeometry> {
+    Ok(match tag {
+        GEOMETRY_POINT => SurrealGeometry::Point(Point(position(value)?)),
+        GEOMETRY_LINE => SurrealGeometry::Line(line(value)?),
+        GEOMETRY_POLYGON => SurrealGeometry::Polygon(polygon(value)?),
+        GEOMETRY_MULTIPOINT => SurrealGeometry::MultiPoint(MultiPoint(
+            members(value, GEOMETRY_POINT)?
+                .map(|point| position(point).map(Point))
+                .collect::<Result<_>>()?,
+        )),
+        GEOMETRY_MULTILINE => SurrealGeometry::MultiLine(MultiLineString(
+            members(value, GEOMETRY_LINE)?
+                .map(line)
+                .collect::<Result<_>>()?,
+        )),
+        GEOMETRY_MULTIPOLYGON => SurrealGeometry::MultiPolygon(MultiPolygon(
+            members(value, GEOMETRY_POLYGON)?
+                .map(polygon)
+                .collect::<Result<_>>()?,
+        )),
+        _ => {
+            let CborValue::Array(geometries) = value else {
+                bail!("invalid geometry collection, expected an array")
+            };
+            SurrealGeometry::Collection(
+                geometries
+                    .into_iter()
+                    .map(|geometry_value| match geometry_value {
+                        CborValue::Tag(tag @ GEOMETRY_POINT..=GEOMETRY_COLLECTION, value) => {
+                            geometry(tag, *value)
+                        }
+                        _ => bail!("invalid geometry collection member, expected a geometry tag"),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        }
+    })
+}
+
+/// Unwraps the members of a geometry, each of which must carry the `tag` of its own geometry.
+fn members(value: CborValue, tag: u64) -> Result<impl Iterator<Item = CborValue>> {
+    let CborValue::Array(values) = value else {
+        bail!("invalid geometry, expected an array of tag {tag} members")
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            CborValue::Tag(member, value) if member == tag => Ok(*value),
+            _ => bail!("invalid geometry member, expected tag {tag}"),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Vec::into_iter)
+}
+
+fn position(value: CborValue) -> Result<Coord<f64>> {
+    let CborValue::Array(position) = value else {
+        bail!("invalid geometry point, expected [x, y]")
+    };
+    let [x, y] = position.as_slice() else {
+        bail!("invalid geometry point, expected [x, y]")
+    };
+
+    Ok(Coord {
+        x: cbor_to_f64(x)?,
+        y: cbor_to_f64(y)?,
+    })
+}
+
+fn line(value: CborValue) -> Result<LineString<f64>> {
+    Ok(LineString(
+        members(value, GEOMETRY_POINT)?
+            .map(position)
+            .collect::<Result<_>>()?,
+    ))
+}
+
+fn polygon(value: CborValue) -> Result<Polygon<f64>> {
+    let mut rings = members(value, GEOMETRY_LINE)?.map(line);
+    let exterior = rings
+        .next()
+        .transpose()?
+        .unwrap_or_else(|| LineString(Vec::new()));
+    Ok(Polygon::new(exterior, rings.collect::<Result<_>>()?))
+}
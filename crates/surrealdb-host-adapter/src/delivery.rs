@@ -0,0 +1,356 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use surrealdb_types::RecordId;
+use tokio::sync::Notify;
+
+/// What a subscription does when the guest falls `capacity` events behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop reading the SurrealDB live stream until the guest catches up.
+    #[default]
+    Block,
+    /// Discard the oldest buffered event.
+    DropOldest,
+    /// Replace a buffered update of the same record, and block when there is none.
+    CoalesceUpdates,
+    /// Deliver a `lagged` event after the buffered events and end the subscription.
+    Kill,
+}
+
+/// Buffering of live events between the SurrealDB stream and the guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveDeliveryConfig {
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl LiveDeliveryConfig {
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow,
+        }
+    }
+}
+
+impl Default for LiveDeliveryConfig {
+    fn default() -> Self {
+        Self::new(256, OverflowPolicy::Block)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SendError {
+    /// The guest dropped its end of the stream.
+    Closed,
+    /// The buffer is full and the policy is [`OverflowPolicy::Kill`].
+    Lagged,
+}
+
+struct Queued<T> {
+    /// Record the event is about, used to coalesce updates.
+    record: Option<RecordId>,
+    is_update: bool,
+    event: T,
+}
+
+struct State<T> {
+    events: VecDeque<Queued<T>>,
+    sender_closed: bool,
+    receiver_closed: bool,
+    receiver_waker: Option<Waker>,
+}
+
+struct Shared<T> {
+    config: LiveDeliveryConfig,
+    state: Mutex<State<T>>,
+    space: Notify,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub(crate) struct LiveSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub(crate) struct LiveReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub(crate) fn channel<T>(config: LiveDeliveryConfig) -> (LiveSender<T>, LiveReceiver<T>) {
+    let shared = Arc::new(Shared {
+        config,
+        state: Mutex::new(State {
+            events: VecDeque::with_capacity(config.capacity),
+            sender_closed: false,
+            receiver_closed: false,
+            receiver_waker: None,
+        }),
+        space: Notify::new(),
+    });
+
+    (
+        LiveSender {
+            shared: Arc::clone(&shared),
+        },
+        LiveReceiver { shared },
+    )
+}
+
+impl<T> LiveSender<T> {
+    /// Buffers `event`, applying the overflow policy when the buffer is full. `record` is the
+    /// record the event is about, and only updates are coalesced.
+    pub(crate) async fn send(
+        &self,
+        record: Option<RecordId>,
+        is_update: bool,
+        event: T,
+    ) -> Result<(), SendError> {
+        let mut queued = Some(Queued {
+            record,
+            is_update,
+            event,
+        });
+        loop {
+            let space = self.shared.space.notified();
+            {
+                let mut state = self.shared.lock();
+                if state.receiver_closed {
+                    return Err(SendError::Closed);
+                }
+
+                let event = queued.take().expect("event is sent once");
+                if state.events.len() < self.shared.config.capacity {
+                    push(&mut state, event);
+                    return Ok(());
+                }
+
+                match self.shared.config.overflow {
+                    OverflowPolicy::Block => queued = Some(event),
+                    OverflowPolicy::DropOldest => {
+                        state.events.pop_front();
+                        push(&mut state, event);
+                        return Ok(());
+                    }
+                    OverflowPolicy::CoalesceUpdates => match coalesce(&mut state, event) {
+                        Ok(()) => return Ok(()),
+                        Err(event) => queued = Some(event),
+                    },
+                    OverflowPolicy::Kill => return Err(SendError::Lagged),
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Buffers `event` past the capacity and ends the stream after it.
+    pub(crate) fn close_with(self, event: T) {
+        let mut state = self.shared.lock();
+        push(
+            &mut state,
+            Queued {
+                record: None,
+                is_update: false,
+                event,
+            },
+        );
+    }
+}
+
+fn push<T>(state: &mut State<T>, queued: Queued<T>) {
+    state.events.push_back(queued);
+    if let Some(waker) = state.receiver_waker.take() {
+        waker.wake();
+    }
+}
+
+/// Replaces the last buffered update of the same record with `update`. When a create or delete of
+/// the record was buffered after it, the old update is removed and `update` goes to the back
+/// instead, so events of one record stay in order. Returns `update` when there is nothing to
+/// coalesce it with.
+fn coalesce<T>(state: &mut State<T>, update: Queued<T>) -> Result<(), Queued<T>> {
+    let Some(record) = update.record.as_ref().filter(|_| update.is_update) else {
+        return Err(update);
+    };
+    let Some(position) = state
+        .events
+        .iter()
+        .rposition(|queued| queued.is_update && queued.record.as_ref() == Some(record))
+    else {
+        return Err(update);
+    };
+
+    let followed = state
+        .events
+        .iter()
+        .skip(position + 1)
+        .any(|queued| queued.record.as_ref() == Some(record));
+    if followed {
+        state.events.remove(position);
+        push(state, update);
+    } else {
+        state.events[position] = update;
+    }
+    Ok(())
+}
+
+impl<T> Drop for LiveSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.sender_closed = true;
+        if let Some(waker) = state.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> LiveReceiver<T> {
+    /// Returns the next buffered event, or `None` once the sender is gone and the buffer is empty.
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.shared.lock();
+        if let Some(Queued { event, .. }) = state.events.pop_front() {
+            drop(state);
+            self.shared.space.notify_one();
+            return Poll::Ready(Some(event));
+        }
+
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.receiver_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for LiveReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_closed = true;
+        self.shared.space.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+
+    use surrealdb_types::{RecordId, RecordIdKey};
+
+    use super::{LiveDeliveryConfig, OverflowPolicy, SendError, channel};
+
+    fn record(key: impl Into<RecordIdKey>) -> Option<RecordId> {
+        Some(RecordId::new("person", key))
+    }
+
+    async fn drain(receiver: &mut super::LiveReceiver<&'static str>) -> Vec<&'static str> {
+        let mut events = Vec::new();
+        while let Some(event) = poll_fn(|cx| receiver.poll_recv(cx)).await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_newest_events() {
+        let (sender, mut receiver) =
+            channel(LiveDeliveryConfig::new(2, OverflowPolicy::DropOldest));
+
+        for event in ["a", "b", "c"] {
+            sender.send(None, false, event).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(drain(&mut receiver).await, vec!["b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn coalesce_replaces_buffered_updates_of_the_same_record() {
+        let (sender, mut receiver) =
+            channel(LiveDeliveryConfig::new(2, OverflowPolicy::CoalesceUpdates));
+
+        sender.send(record("a"), false, "create a").await.unwrap();
+        sender.send(record("a"), true, "update a 1").await.unwrap();
+        sender.send(record("a"), true, "update a 2").await.unwrap();
+        drop(sender);
+
+        assert_eq!(drain(&mut receiver).await, vec!["create a", "update a 2"]);
+    }
+
+    #[tokio::test]
+    async fn coalesce_moves_updates_behind_later_creates_and_deletes_of_the_record() {
+        let (sender, mut receiver) =
+            channel(LiveDeliveryConfig::new(3, OverflowPolicy::CoalesceUpdates));
+
+        sender.send(record("a"), true, "update a v1").await.unwrap();
+        sender.send(record("a"), false, "delete a").await.unwrap();
+        sender.send(record("a"), false, "create a").await.unwrap();
+        sender.send(record("a"), true, "update a v2").await.unwrap();
+        drop(sender);
+
+        assert_eq!(
+            drain(&mut receiver).await,
+            vec!["delete a", "create a", "update a v2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn coalesce_keeps_updates_of_records_with_distinct_keys() {
+        let (sender, mut receiver) =
+            channel(LiveDeliveryConfig::new(1, OverflowPolicy::CoalesceUpdates));
+
+        sender.send(record(1), true, "update 1").await.unwrap();
+        sender
+            .send(record(1), true, "update 1 again")
+            .await
+            .unwrap();
+        let blocked = tokio::spawn(async move {
+            sender.send(record("1"), true, "update '1'").await.unwrap();
+        });
+
+        assert_eq!(
+            poll_fn(|cx| receiver.poll_recv(cx)).await,
+            Some("update 1 again")
+        );
+        blocked.await.unwrap();
+        assert_eq!(drain(&mut receiver).await, vec!["update '1'"]);
+    }
+
+    #[tokio::test]
+    async fn kill_reports_lag_and_delivers_the_closing_event_last() {
+        let (sender, mut receiver) = channel(LiveDeliveryConfig::new(1, OverflowPolicy::Kill));
+
+        sender.send(None, false, "a").await.unwrap();
+        assert_eq!(sender.send(None, false, "b").await, Err(SendError::Lagged));
+        sender.close_with("lagged");
+
+        assert_eq!(drain(&mut receiver).await, vec!["a", "lagged"]);
+    }
+
+    #[tokio::test]
+    async fn block_waits_for_the_receiver_and_stops_when_it_is_dropped() {
+        let (sender, mut receiver) = channel(LiveDeliveryConfig::new(1, OverflowPolicy::Block));
+
+        sender.send(None, false, "a").await.unwrap();
+        let blocked = tokio::spawn(async move {
+            let second = sender.send(None, false, "b").await;
+            let third = sender.send(None, false, "c").await;
+            (second, third)
+        });
+        tokio::task::yield_now().await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(poll_fn(|cx| receiver.poll_recv(cx)).await, Some("a"));
+        tokio::task::yield_now().await;
+        drop(receiver);
+
+        assert_eq!(blocked.await.unwrap(), (Ok(()), Err(SendError::Closed)));
+    }
+}
@@ -0,0 +1,921 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use opentelemetry::KeyValue;
+use opentelemetry::trace::{Span, Status, TraceContextExt};
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb::method::Transaction;
+use tokio::sync::{Mutex, oneshot};
+use wasmtime::component::{
+    Accessor, Destination, HasSelf, Linker, Resource, ResourceTable, StreamProducer, StreamReader,
+    StreamResult,
+};
+use wasmtime::{AsContextMut, StoreContextMut};
+
+use crate::bindings::seamlezz::surrealdb::call;
+use crate::bindings::wasmcloud::observability::propagation;
+use crate::delivery::{self, LiveReceiver, LiveSender, SendError};
+use crate::{
+    LiveAction, LiveDeliveryConfig, LiveEvent, QueryError, ROW_CHUNK_SIZE, RowChunk, RowChunks,
+    Session, StatementError, StatementKind, SubscribeError, SubscriptionManager, SubscriptionTask,
+    WireEncoding,
+};
+use crate::{error_chain, telemetry};
+
+pub struct SurrealCtx {
+    db: Surreal<Any>,
+    subscriptions: Arc<SubscriptionManager>,
+    session: Session,
+    live_delivery: LiveDeliveryConfig,
+    default_timeout: Option<Duration>,
+    call_stats: Arc<CallStats>,
+    table: ResourceTable,
+}
+
+impl SurrealCtx {
+    pub fn new(db: Surreal<Any>, subscriptions: Arc<SubscriptionManager>) -> Self {
+        Self {
+            db,
+            subscriptions,
+            session: Session::default(),
+            live_delivery: LiveDeliveryConfig::default(),
+            default_timeout: None,
+            call_stats: Arc::new(CallStats::default()),
+            table: ResourceTable::new(),
+        }
+    }
+
+    /// Runs every statement from this component in `session`.
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    /// Buffers live events for each subscription as described by `config`.
+    pub fn with_live_delivery(mut self, config: LiveDeliveryConfig) -> Self {
+        self.live_delivery = config;
+        self
+    }
+
+    /// Bounds every `query` and `subscribe` call. Guests may ask for a shorter timeout but never
+    /// a longer one.
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    pub fn db(&self) -> &Surreal<Any> {
+        &self.db
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn live_delivery(&self) -> LiveDeliveryConfig {
+        self.live_delivery
+    }
+
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    fn timeout(&self, timeout_ms: Option<u64>) -> Option<Duration> {
+        let requested = timeout_ms.map(Duration::from_millis);
+        match (requested, self.default_timeout) {
+            (Some(requested), Some(default)) => Some(requested.min(default)),
+            (requested, default) => requested.or(default),
+        }
+    }
+
+    fn call_session(&self, encoding: call::WireEncoding) -> Session {
+        self.session
+            .clone()
+            .with_wire_encoding(map_wire_encoding(encoding))
+    }
+
+    pub fn subscriptions(&self) -> &Arc<SubscriptionManager> {
+        &self.subscriptions
+    }
+
+    pub fn call_stats(&self) -> CallStatsSnapshot {
+        self.call_stats.snapshot()
+    }
+
+    pub async fn shutdown(&self) {
+        self.subscriptions.shutdown().await;
+    }
+}
+
+const TRANSACTION_FINISHED: &str = "transaction already finished";
+const TRANSACTION_TIMED_OUT: &str = "transaction was cancelled after a query timed out";
+
+pub struct TransactionHandle {
+    transaction: Arc<Mutex<TransactionState>>,
+}
+
+enum TransactionState {
+    Open(Transaction<Any>),
+    /// Cancelled by the host because a query left it in an unknown state.
+    Failed(&'static str),
+    Finished,
+}
+
+impl TransactionHandle {
+    fn new(transaction: Transaction<Any>) -> Self {
+        Self {
+            transaction: Arc::new(Mutex::new(TransactionState::Open(transaction))),
+        }
+    }
+
+    async fn take(self) -> TransactionState {
+        std::mem::replace(
+            &mut *self.transaction.lock().await,
+            TransactionState::Finished,
+        )
+    }
+}
+
+pub trait SurrealView: Send {
+    fn surreal(&mut self) -> &mut SurrealCtx;
+}
+
+pub fn add_to_linker<T: SurrealView + 'static>(linker: &mut Linker<T>) -> wasmtime::Result<()> {
+    propagation::add_to_linker::<T, HasSelf<SurrealCtx>>(linker, T::surreal)?;
+    call::add_to_linker::<T, HasSelf<SurrealCtx>>(linker, T::surreal)?;
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct CallStats {
+    query_calls: AtomicU64,
+    subscribe_calls: AtomicU64,
+    cancel_calls: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallStatsSnapshot {
+    pub query_calls: u64,
+    pub subscribe_calls: u64,
+    pub cancel_calls: u64,
+}
+
+impl CallStats {
+    fn increment_query(&self) {
+        self.query_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn increment_subscribe(&self) {
+        self.subscribe_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn increment_cancel(&self) {
+        self.cancel_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> CallStatsSnapshot {
+        CallStatsSnapshot {
+            query_calls: self.query_calls.load(Ordering::Relaxed),
+            subscribe_calls: self.subscribe_calls.load(Ordering::Relaxed),
+            cancel_calls: self.cancel_calls.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn map_wire_encoding(encoding: call::WireEncoding) -> WireEncoding {
+    match encoding {
+        call::WireEncoding::Legacy => WireEncoding::Legacy,
+        call::WireEncoding::Native => WireEncoding::Native,
+    }
+}
+
+fn map_live_action(action: LiveAction) -> call::LiveAction {
+    match action {
+        LiveAction::Create => call::LiveAction::Create,
+        LiveAction::Update => call::LiveAction::Update,
+        LiveAction::Delete => call::LiveAction::Delete,
+        LiveAction::Killed => call::LiveAction::Killed,
+        LiveAction::Lagged => call::LiveAction::Lagged,
+    }
+}
+
+fn to_binding_live_event(event: LiveEvent) -> call::LiveEvent {
+    call::LiveEvent {
+        subscription_id: event.subscription_id,
+        query_id: event.query_id,
+        action: map_live_action(event.action),
+        data: event.data,
+    }
+}
+
+/// Ends a subscription whose live stream failed with a `killed` event carrying the error, so the
+/// guest can tell the failure from a clean end of the stream.
+fn close_killed(
+    sender: LiveSender<call::LiveEvent>,
+    subscription_id: u64,
+    query_id: String,
+    error: &(dyn std::error::Error + 'static),
+    encoding: WireEncoding,
+) {
+    match crate::killed_live_event(subscription_id, query_id, error_chain(error), encoding) {
+        Ok(event) => sender.close_with(to_binding_live_event(event)),
+        Err(_error) => {
+            debug_log!(
+                "wasi host subscribe task killed event failed. subscription_id={}, error={}",
+                subscription_id,
+                error_chain(&_error)
+            );
+        }
+    }
+}
+
+fn map_statement_error(error: StatementError) -> call::QueryError {
+    match error {
+        StatementError::ParseError(message) => call::QueryError::ParseError(message),
+        StatementError::NotAllowed(message) => call::QueryError::NotAllowed(message),
+        StatementError::TransactionCascade(message) => {
+            call::QueryError::TransactionCascade(message)
+        }
+        StatementError::Thrown(message) => call::QueryError::Thrown(message),
+        StatementError::Timeout(message) => call::QueryError::Timeout(message),
+        StatementError::SchemaViolation(message) => call::QueryError::SchemaViolation(message),
+        StatementError::NotFound(message) => call::QueryError::NotFound(message),
+        StatementError::InvalidParam(message) => call::QueryError::InvalidParam(message),
+        StatementError::Internal(message) => call::QueryError::Internal(message),
+    }
+}
+
+/// Statement set failures such as parse errors and undecodable params surface as a single
+/// classified entry.
+fn statement_set_error(error: QueryError) -> StatementError {
+    match error {
+        QueryError::QueryExecution(error) => StatementError::from(&error),
+        error @ QueryError::Timeout(_) => StatementError::Timeout(error.to_string()),
+        QueryError::ParamDecode { source, .. } => StatementError::InvalidParam(source.to_string()),
+    }
+}
+
+/// Failures to start a live query surface as a query error instead of trapping the guest.
+fn subscribe_error(error: SubscribeError) -> StatementError {
+    match error {
+        SubscribeError::QueryExecution(error) | SubscribeError::StreamOpen(error) => {
+            StatementError::from(&error)
+        }
+        SubscribeError::NotAllowed(message) => StatementError::NotAllowed(message),
+        error @ SubscribeError::Timeout(_) => StatementError::Timeout(error.to_string()),
+        SubscribeError::ParamDecode { source, .. } => {
+            StatementError::InvalidParam(source.to_string())
+        }
+        error @ SubscribeError::Serialize(_) => StatementError::Internal(error.to_string()),
+    }
+}
+
+fn to_binding_results(
+    result: Result<Vec<Result<Vec<u8>, StatementError>>, QueryError>,
+) -> Vec<Result<Vec<u8>, call::QueryError>> {
+    let results = match result {
+        Ok(results) => results,
+        Err(error) => vec![Err(statement_set_error(error))],
+    };
+
+    results
+        .into_iter()
+        .map(|result| result.map_err(map_statement_error))
+        .collect()
+}
+
+fn to_binding_row_chunk(chunk: RowChunk) -> call::RowChunk {
+    call::RowChunk {
+        statement: chunk.statement,
+        rows: chunk.rows.map_err(map_statement_error),
+    }
+}
+
+struct RowChunkProducer {
+    chunks: RowChunks,
+}
+
+impl<T> StreamProducer<T> for RowChunkProducer {
+    type Item = call::RowChunk;
+    type Buffer = Option<Self::Item>;
+
+    fn poll_produce<'a>(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _store: StoreContextMut<'a, T>,
+        mut destination: Destination<'a, Self::Item, Self::Buffer>,
+        finish: bool,
+    ) -> Poll<wasmtime::Result<StreamResult>> {
+        if finish {
+            return Poll::Ready(Ok(StreamResult::Cancelled));
+        }
+
+        match self.get_mut().chunks.next() {
+            Some(chunk) => {
+                destination.set_buffer(Some(to_binding_row_chunk(chunk)));
+                Poll::Ready(Ok(StreamResult::Completed))
+            }
+            None => Poll::Ready(Ok(StreamResult::Dropped)),
+        }
+    }
+}
+
+struct LiveEventProducer {
+    receiver: LiveReceiver<call::LiveEvent>,
+}
+
+impl LiveEventProducer {
+    fn new(receiver: LiveReceiver<call::LiveEvent>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl<T> StreamProducer<T> for LiveEventProducer {
+    type Item = call::LiveEvent;
+    type Buffer = Option<Self::Item>;
+
+    fn poll_produce<'a>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        _store: StoreContextMut<'a, T>,
+        mut destination: Destination<'a, Self::Item, Self::Buffer>,
+        finish: bool,
+    ) -> Poll<wasmtime::Result<StreamResult>> {
+        if finish {
+            return Poll::Ready(Ok(StreamResult::Cancelled));
+        }
+
+        let this = self.get_mut();
+        match this.receiver.poll_recv(cx) {
+            Poll::Ready(Some(event)) => {
+                destination.set_buffer(Some(event));
+                Poll::Ready(Ok(StreamResult::Completed))
+            }
+            Poll::Ready(None) => Poll::Ready(Ok(StreamResult::Dropped)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl propagation::Host for SurrealCtx {}
+
+impl call::Host for SurrealCtx {}
+
+impl call::HostTransaction for SurrealCtx {}
+
+impl<T: Send> call::HostWithStore<T> for HasSelf<SurrealCtx> {
+    async fn query(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        query: String,
+        params: Vec<(String, Vec<u8>)>,
+        timeout_ms: Option<u64>,
+        encoding: call::WireEncoding,
+    ) -> wasmtime::Result<Vec<Result<Vec<u8>, call::QueryError>>> {
+        debug_log!(
+            "wasi host query begin. query={}, params_len={}",
+            query,
+            params.len()
+        );
+        let (db, session, timeout, call_stats) = accessor.with(|mut access| {
+            let state = access.get();
+            (
+                state.db.clone(),
+                state.call_session(encoding),
+                state.timeout(timeout_ms),
+                Arc::clone(&state.call_stats),
+            )
+        });
+        call_stats.increment_query();
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::QUERY_SPAN,
+            &parent,
+            telemetry::call_attributes("query", &query, params.len()),
+        );
+
+        let result = crate::query(&db, &session, query, params, timeout).await;
+        match &result {
+            Ok(results) => telemetry::record_statement_results(&mut span, results),
+            Err(error) => telemetry::record_error(&mut span, error),
+        }
+        span.end();
+
+        debug_log!("wasi host query adapter returned. ok={}", result.is_ok());
+        Ok(to_binding_results(result))
+    }
+
+    async fn query_stream(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        query: String,
+        params: Vec<(String, Vec<u8>)>,
+        timeout_ms: Option<u64>,
+        encoding: call::WireEncoding,
+    ) -> wasmtime::Result<StreamReader<call::RowChunk>> {
+        debug_log!(
+            "wasi host query stream begin. query={}, params_len={}",
+            query,
+            params.len()
+        );
+        let (db, session, timeout, call_stats) = accessor.with(|mut access| {
+            let state = access.get();
+            (
+                state.db.clone(),
+                state.call_session(encoding),
+                state.timeout(timeout_ms),
+                Arc::clone(&state.call_stats),
+            )
+        });
+        call_stats.increment_query();
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::QUERY_STREAM_SPAN,
+            &parent,
+            telemetry::call_attributes("query_stream", &query, params.len()),
+        );
+
+        let chunks = match crate::query_stream(&db, &session, query, params, timeout).await {
+            Ok(chunks) => chunks,
+            Err(error) => {
+                telemetry::record_error(&mut span, &error);
+                RowChunks::new(
+                    vec![(StatementKind::Other, Err(statement_set_error(error)))],
+                    ROW_CHUNK_SIZE,
+                )
+                .with_encoding(session.wire_encoding())
+            }
+        };
+        span.end();
+
+        debug_log!("wasi host query stream ready");
+        accessor.with(|mut access| {
+            StreamReader::new(access.as_context_mut(), RowChunkProducer { chunks })
+        })
+    }
+
+    async fn subscribe(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        query: String,
+        params: Vec<(String, Vec<u8>)>,
+        timeout_ms: Option<u64>,
+        encoding: call::WireEncoding,
+    ) -> wasmtime::Result<Result<(u64, StreamReader<call::LiveEvent>), call::QueryError>> {
+        debug_log!(
+            "wasi host subscribe begin. query={}, params_len={}",
+            query,
+            params.len()
+        );
+        let (db, session, live_delivery, timeout, subscriptions, call_stats) =
+            accessor.with(|mut access| {
+                let state = access.get();
+                (
+                    state.db.clone(),
+                    state.call_session(encoding),
+                    state.live_delivery,
+                    state.timeout(timeout_ms),
+                    Arc::clone(&state.subscriptions),
+                    Arc::clone(&state.call_stats),
+                )
+            });
+        call_stats.increment_subscribe();
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::SUBSCRIBE_SPAN,
+            &parent,
+            telemetry::call_attributes("subscribe", &query, params.len()),
+        );
+
+        let subscription_id = subscriptions.allocate_id();
+        span.set_attribute(KeyValue::new(
+            "surrealdb.subscription.id",
+            subscription_id as i64,
+        ));
+
+        let stream = match crate::subscribe(&db, &session, query, params, timeout).await {
+            Ok(stream) => stream,
+            Err(error) => {
+                telemetry::record_error(&mut span, &error);
+                span.end();
+                return Ok(Err(map_statement_error(subscribe_error(error))));
+            }
+        };
+        let live_context = parent.with_span(span);
+        let task_context = live_context.clone();
+        debug_log!(
+            "wasi host subscribe adapter stream ready. subscription_id={}",
+            subscription_id
+        );
+
+        let (sender, receiver) = delivery::channel(live_delivery);
+        let (stop_tx, mut stop_rx) = oneshot::channel();
+        let mut stream = Box::pin(stream);
+        let task_subscriptions = Arc::clone(&subscriptions);
+        let encoding = session.wire_encoding();
+
+        let handle = tokio::spawn(async move {
+            let mut query_id = String::new();
+            debug_log!(
+                "wasi host subscribe task started. subscription_id={}",
+                subscription_id
+            );
+            loop {
+                tokio::select! {
+                    _ = &mut stop_rx => {
+                        debug_log!(
+                            "wasi host subscribe task stop signal. subscription_id={}",
+                            subscription_id
+                        );
+                        break;
+                    }
+                    notification = stream.next() => {
+                        let notification = match notification {
+                            Some(Ok(notification)) => notification,
+                            Some(Err(error)) => {
+                                debug_log!(
+                                    "wasi host subscribe task stream errored. subscription_id={}",
+                                    subscription_id
+                                );
+                                close_killed(sender, subscription_id, query_id, &error, encoding);
+                                break;
+                            }
+                            None => {
+                                debug_log!(
+                                    "wasi host subscribe task stream ended. subscription_id={}",
+                                    subscription_id
+                                );
+                                break;
+                            }
+                        };
+                        query_id = notification.query_id.to_string();
+
+                        let mut notification_span = telemetry::start_span(
+                            telemetry::NOTIFICATION_SPAN,
+                            &task_context,
+                            vec![KeyValue::new(
+                                "surrealdb.subscription.id",
+                                subscription_id as i64,
+                            )],
+                        );
+
+                        let event = match crate::notification_to_live_event(subscription_id, notification, encoding) {
+                            Ok(event) => event,
+                            Err(error) => {
+                                telemetry::record_error(&mut notification_span, &error);
+                                notification_span.end();
+                                debug_log!(
+                                    "wasi host subscribe task notification conversion failed. subscription_id={}",
+                                    subscription_id
+                                );
+                                close_killed(sender, subscription_id, query_id, &error, encoding);
+                                break;
+                            }
+                        };
+                        notification_span.set_attributes(telemetry::notification_attributes(
+                            &event.query_id,
+                            event.action,
+                            event.data.len(),
+                        ));
+                        debug_log!(
+                            "wasi host subscribe task notification converted. subscription_id={}, action={:?}, data_len={}",
+                            subscription_id,
+                            event.action,
+                            event.data.len()
+                        );
+
+                        let record = event.record.clone();
+                        let is_update = matches!(event.action, LiveAction::Update);
+                        let sent = sender
+                            .send(record, is_update, to_binding_live_event(event))
+                            .await;
+                        notification_span.end();
+                        match sent {
+                            Ok(()) => {}
+                            Err(SendError::Closed) => {
+                                debug_log!(
+                                    "wasi host subscribe task send failed. subscription_id={}",
+                                    subscription_id
+                                );
+                                break;
+                            }
+                            Err(SendError::Lagged) => {
+                                debug_log!(
+                                    "wasi host subscribe task lagged. subscription_id={}",
+                                    subscription_id
+                                );
+                                match crate::lagged_live_event(subscription_id, query_id.clone(), encoding) {
+                                    Ok(event) => sender.close_with(to_binding_live_event(event)),
+                                    Err(_error) => {
+                                        debug_log!(
+                                            "wasi host subscribe task lagged event failed. subscription_id={}, error={}",
+                                            subscription_id,
+                                            error_chain(&_error)
+                                        );
+                                    }
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            task_subscriptions.complete(subscription_id).await;
+            debug_log!(
+                "wasi host subscribe task completed. subscription_id={}",
+                subscription_id
+            );
+        });
+
+        subscriptions
+            .register(subscription_id, SubscriptionTask::new(stop_tx, handle))
+            .await;
+
+        let reader = accessor.with(|mut access| {
+            StreamReader::new(access.as_context_mut(), LiveEventProducer::new(receiver))
+        });
+        if let Err(error) = &reader {
+            live_context
+                .span()
+                .set_status(Status::error(error.to_string()));
+        }
+        live_context.span().end();
+        let reader = reader?;
+        debug_log!(
+            "wasi host subscribe reader ready. subscription_id={}",
+            subscription_id
+        );
+
+        Ok(Ok((subscription_id, reader)))
+    }
+
+    async fn cancel(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        subscription_id: u64,
+    ) -> wasmtime::Result<Result<(), String>> {
+        debug_log!(
+            "wasi host cancel begin. subscription_id={}",
+            subscription_id
+        );
+        let (subscriptions, call_stats) = accessor.with(|mut access| {
+            let state = access.get();
+            (
+                Arc::clone(&state.subscriptions),
+                Arc::clone(&state.call_stats),
+            )
+        });
+        call_stats.increment_cancel();
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::CANCEL_SPAN,
+            &parent,
+            vec![
+                KeyValue::new("db.system.name", "surrealdb"),
+                KeyValue::new("db.operation.name", "cancel"),
+                KeyValue::new("surrealdb.subscription.id", subscription_id as i64),
+            ],
+        );
+
+        if subscriptions.cancel(subscription_id).await {
+            span.end();
+            debug_log!(
+                "wasi host cancel success. subscription_id={}",
+                subscription_id
+            );
+            return Ok(Ok(()));
+        }
+
+        let message = format!("subscription {} not found", subscription_id);
+        span.set_status(Status::error(message.clone()));
+        span.end();
+
+        debug_log!(
+            "wasi host cancel not found. subscription_id={}",
+            subscription_id
+        );
+
+        Ok(Err(message))
+    }
+}
+
+impl<T: Send> call::HostTransactionWithStore<T> for HasSelf<SurrealCtx> {
+    async fn begin(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+    ) -> wasmtime::Result<Result<Resource<TransactionHandle>, String>> {
+        debug_log!("wasi host transaction begin");
+        let (db, session) = accessor.with(|mut access| {
+            let state = access.get();
+            (state.db.clone(), state.session.clone())
+        });
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::TRANSACTION_BEGIN_SPAN,
+            &parent,
+            telemetry::operation_attributes("begin"),
+        );
+
+        let transaction = match crate::begin(&db, &session).await {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                telemetry::record_error(&mut span, &error);
+                span.end();
+                return Ok(Err(error_chain(&error)));
+            }
+        };
+        span.end();
+
+        let handle = accessor
+            .with(|mut access| access.get().table.push(TransactionHandle::new(transaction)))?;
+        debug_log!("wasi host transaction started. rep={}", handle.rep());
+
+        Ok(Ok(handle))
+    }
+
+    async fn query(
+        accessor: &Accessor<T, Self>,
+        self_: Resource<TransactionHandle>,
+        parent_context: Option<propagation::TraceContext>,
+        query: String,
+        params: Vec<(String, Vec<u8>)>,
+        timeout_ms: Option<u64>,
+        encoding: call::WireEncoding,
+    ) -> wasmtime::Result<Vec<Result<Vec<u8>, call::QueryError>>> {
+        debug_log!(
+            "wasi host transaction query begin. rep={}, query={}, params_len={}",
+            self_.rep(),
+            query,
+            params.len()
+        );
+        let (transaction, session, timeout, call_stats) =
+            accessor.with(|mut access| -> wasmtime::Result<_> {
+                let state = access.get();
+                let handle = state.table.get(&self_)?;
+                Ok((
+                    Arc::clone(&handle.transaction),
+                    state.call_session(encoding),
+                    state.timeout(timeout_ms),
+                    Arc::clone(&state.call_stats),
+                ))
+            })?;
+        call_stats.increment_query();
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::TRANSACTION_QUERY_SPAN,
+            &parent,
+            telemetry::call_attributes("query", &query, params.len()),
+        );
+
+        let mut guard = transaction.lock().await;
+        let transaction = match &*guard {
+            TransactionState::Open(transaction) => transaction,
+            TransactionState::Failed(message) => {
+                span.set_status(Status::error(*message));
+                span.end();
+                return Ok(vec![Err(call::QueryError::TransactionCascade(
+                    message.to_string(),
+                ))]);
+            }
+            TransactionState::Finished => {
+                span.set_status(Status::error(TRANSACTION_FINISHED));
+                span.end();
+                return Err(wasmtime::Error::msg(TRANSACTION_FINISHED));
+            }
+        };
+
+        let result = crate::transaction_query(transaction, &session, query, params, timeout).await;
+        match &result {
+            Ok(results) => telemetry::record_statement_results(&mut span, results),
+            Err(error) => telemetry::record_error(&mut span, error),
+        }
+        span.end();
+
+        // The statement set was abandoned part way, so nothing after it may commit.
+        if matches!(result, Err(QueryError::Timeout(_)))
+            && let TransactionState::Open(transaction) =
+                std::mem::replace(&mut *guard, TransactionState::Failed(TRANSACTION_TIMED_OUT))
+            && let Err(_error) = crate::cancel_transaction(transaction).await
+        {
+            debug_log!(
+                "wasi host transaction timeout cancel failed. error={}",
+                error_chain(&_error)
+            );
+        }
+
+        debug_log!(
+            "wasi host transaction query returned. ok={}",
+            result.is_ok()
+        );
+        Ok(to_binding_results(result))
+    }
+
+    async fn commit(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        this: Resource<TransactionHandle>,
+    ) -> wasmtime::Result<Result<(), String>> {
+        debug_log!("wasi host transaction commit begin. rep={}", this.rep());
+        let handle = accessor.with(|mut access| access.get().table.delete(this))?;
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::TRANSACTION_COMMIT_SPAN,
+            &parent,
+            telemetry::operation_attributes("commit"),
+        );
+
+        let transaction = match handle.take().await {
+            TransactionState::Open(transaction) => transaction,
+            TransactionState::Failed(message) => {
+                span.set_status(Status::error(message));
+                span.end();
+                return Ok(Err(message.to_string()));
+            }
+            TransactionState::Finished => {
+                span.set_status(Status::error(TRANSACTION_FINISHED));
+                span.end();
+                return Ok(Err(TRANSACTION_FINISHED.to_string()));
+            }
+        };
+
+        let result = crate::commit(transaction).await;
+        if let Err(error) = &result {
+            telemetry::record_error(&mut span, error);
+        }
+        span.end();
+
+        debug_log!("wasi host transaction commit done. ok={}", result.is_ok());
+        Ok(result.map_err(|error| error_chain(&error)))
+    }
+
+    async fn cancel(
+        accessor: &Accessor<T, Self>,
+        parent_context: Option<propagation::TraceContext>,
+        this: Resource<TransactionHandle>,
+    ) -> wasmtime::Result<Result<(), String>> {
+        debug_log!("wasi host transaction cancel begin. rep={}", this.rep());
+        let handle = accessor.with(|mut access| access.get().table.delete(this))?;
+
+        let parent = telemetry::parent_context(parent_context.as_ref());
+        let mut span = telemetry::start_span(
+            telemetry::TRANSACTION_CANCEL_SPAN,
+            &parent,
+            telemetry::operation_attributes("cancel"),
+        );
+
+        let transaction = match handle.take().await {
+            TransactionState::Open(transaction) => transaction,
+            // Already cancelled by the host, which is what the guest asked for.
+            TransactionState::Failed(_) => {
+                span.end();
+                return Ok(Ok(()));
+            }
+            TransactionState::Finished => {
+                span.set_status(Status::error(TRANSACTION_FINISHED));
+                span.end();
+                return Ok(Err(TRANSACTION_FINISHED.to_string()));
+            }
+        };
+
+        let result = crate::cancel_transaction(transaction).await;
+        if let Err(error) = &result {
+            telemetry::record_error(&mut span, error);
+        }
+        span.end();
+
+        debug_log!("wasi host transaction cancel done. ok={}", result.is_ok());
+        Ok(result.map_err(|error| error_chain(&error)))
+    }
+
+    async fn drop(
+        accessor: &Accessor<T, Self>,
+        rep: Resource<TransactionHandle>,
+    ) -> wasmtime::Result<()> {
+        debug_log!("wasi host transaction drop. rep={}", rep.rep());
+        let handle = accessor.with(|mut access| access.get().table.delete(rep))?;
+
+        if let TransactionState::Open(transaction) = handle.take().await
+            && let Err(_error) = crate::cancel_transaction(transaction).await
+        {
+            debug_log!(
+                "wasi host transaction drop cancel failed. error={}",
+                error_chain(&_error)
+            );
+        }
+
+        Ok(())
+    }
+}
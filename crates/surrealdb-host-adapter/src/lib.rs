@@ -1,12 +1,49 @@
+#[cfg(feature = "debug-logs")]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        println!($($arg)*);
+    };
+}
+
+#[cfg(not(feature = "debug-logs"))]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {{};};
+}
+
+pub mod bindings;
 mod convert;
+mod delivery;
+mod host;
 mod manager;
+mod policy;
+mod rows;
+mod session;
+mod statement;
+mod telemetry;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::{Future, IntoFuture};
+use std::ops::Bound;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use convert::{cbor_slice_to_surreal, ordered_params, surreal_to_cbor_bytes};
-use surrealdb::{Notification, Surreal, engine::any::Any, method::QueryStream};
-use surrealdb_types::{Action, Value};
+use convert::{cbor_param_to_surreal, ordered_params, surreal_to_cbor_bytes};
+use session::Rejection;
+use statement::ParamTables;
+use surrealdb::method::{QueryStream, Transaction};
+use surrealdb::{Notification, Surreal, engine::any::Any};
+use surrealdb_types::{
+    Action, ErrorDetails, Number, QueryError as QueryDetail, RecordId, ValidationError, Value,
+};
 use thiserror::Error;
 
+pub use convert::{IntegerPolicy, ParamDecodeError, WireEncoding};
+pub use delivery::{LiveDeliveryConfig, OverflowPolicy};
+pub use host::{CallStatsSnapshot, SurrealCtx, SurrealView, TransactionHandle, add_to_linker};
 pub use manager::{SubscriptionManager, SubscriptionTask};
+pub use policy::{AllowAll, Operation, QueryPolicy, StatementPolicy};
+pub use rows::{ROW_CHUNK_SIZE, RowChunk, RowChunks};
+pub use session::Session;
+pub use statement::{Statement, StatementKind};
 
 #[derive(Debug, Error)]
 pub enum QueryError {
@@ -14,10 +51,12 @@ pub enum QueryError {
     ParamDecode {
         key: String,
         #[source]
-        source: anyhow::Error,
+        source: ParamDecodeError,
     },
     #[error("surreal query failed")]
     QueryExecution(#[source] surrealdb::Error),
+    #[error("query exceeded the {0:?} timeout")]
+    Timeout(Duration),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -26,6 +65,8 @@ pub enum LiveAction {
     Update,
     Delete,
     Killed,
+    /// The guest fell too far behind and the subscription was ended.
+    Lagged,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +74,8 @@ pub struct LiveEvent {
     pub subscription_id: u64,
     pub query_id: String,
     pub action: LiveAction,
+    /// Id of the changed record, when the payload carries one.
+    pub record: Option<RecordId>,
     pub data: Vec<u8>,
 }
 
@@ -42,76 +85,397 @@ pub enum SubscribeError {
     ParamDecode {
         key: String,
         #[source]
-        source: anyhow::Error,
+        source: ParamDecodeError,
     },
     #[error("surreal query failed")]
     QueryExecution(#[source] surrealdb::Error),
+    #[error("{0}")]
+    NotAllowed(String),
+    #[error("live query exceeded the {0:?} timeout")]
+    Timeout(Duration),
     #[error("failed to open live stream")]
     StreamOpen(#[source] surrealdb::Error),
     #[error("failed to serialize live notification")]
     Serialize(#[source] anyhow::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    #[error("failed to begin transaction")]
+    Begin(#[source] surrealdb::Error),
+    #[error("failed to commit transaction")]
+    Commit(#[source] surrealdb::Error),
+    #[error("failed to cancel transaction")]
+    Cancel(#[source] surrealdb::Error),
+}
+
+/// Per statement failure, classified from the typed SurrealDB error details.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum StatementError {
+    #[error("{0}")]
+    ParseError(String),
+    #[error("{0}")]
+    NotAllowed(String),
+    #[error("{0}")]
+    TransactionCascade(String),
+    #[error("{0}")]
+    Thrown(String),
+    #[error("{0}")]
+    Timeout(String),
+    #[error("{0}")]
+    SchemaViolation(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidParam(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl From<&surrealdb::Error> for StatementError {
+    fn from(error: &surrealdb::Error) -> Self {
+        let message = error.to_string();
+        match error.details() {
+            ErrorDetails::Validation(Some(ValidationError::Parse)) => Self::ParseError(message),
+            ErrorDetails::Validation(Some(
+                ValidationError::InvalidParams | ValidationError::InvalidParameter { .. },
+            )) => Self::InvalidParam(message),
+            ErrorDetails::Validation(_) | ErrorDetails::AlreadyExists(_) => {
+                Self::SchemaViolation(message)
+            }
+            ErrorDetails::NotAllowed(_) => Self::NotAllowed(message),
+            ErrorDetails::Thrown => Self::Thrown(message),
+            ErrorDetails::NotFound(_) => Self::NotFound(message),
+            ErrorDetails::Query(Some(QueryDetail::NotExecuted | QueryDetail::Cancelled)) => {
+                Self::TransactionCascade(message)
+            }
+            ErrorDetails::Query(Some(QueryDetail::TimedOut { .. })) => Self::Timeout(message),
+            _ => Self::Internal(message),
+        }
+    }
+}
+
+pub(crate) fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+
+    message
+}
+
 fn decode_params<E>(
     params: Vec<(String, Vec<u8>)>,
-    map_error: impl Fn(String, anyhow::Error) -> E,
+    session: &Session,
+    map_error: impl Fn(String, ParamDecodeError) -> E,
 ) -> Result<Vec<(String, Value)>, E> {
     let mut decoded = Vec::with_capacity(params.len());
     for (key, value) in params {
-        let decoded_value =
-            cbor_slice_to_surreal(&value).map_err(|source| map_error(key.clone(), source))?;
+        let decoded_value = cbor_param_to_surreal(
+            &key,
+            &value,
+            session.integer_policy(),
+            session.wire_encoding(),
+        )
+        .map_err(|source| map_error(key.clone(), source))?;
         decoded.push((key, decoded_value));
     }
 
     Ok(decoded)
 }
 
+/// Parameter that carries the deadline of a timed statement set into the engine.
+const DEADLINE_PARAM: &str = "__host_deadline";
+
+/// Runs `future` to completion or drops it once `timeout` elapses. Dropping the future only stops
+/// the host from waiting, so statement sets are bounded in the engine by [`bound_by_timeout`].
+async fn with_timeout<F: Future>(
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<F::Output, Duration> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| timeout),
+        None => Ok(future.await),
+    }
+}
+
+/// Bounds the statements of `query` that take a `TIMEOUT` clause by the deadline of `timeout`,
+/// binding it in `params`, so they stop in the engine once the host stops waiting for them.
+fn bound_by_timeout(
+    query: String,
+    timeout: Option<Duration>,
+    params: &mut BTreeMap<String, Value>,
+) -> Result<String, surrealdb::Error> {
+    let Some(timeout) = timeout else {
+        return Ok(query);
+    };
+    let deadline = (SystemTime::now() + timeout)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    params.insert(
+        DEADLINE_PARAM.to_string(),
+        Value::Number(Number::Int(i64::try_from(deadline).unwrap_or(i64::MAX))),
+    );
+    statement::bound_by_deadline(&query, DEADLINE_PARAM)
+}
+
+fn param_tables(params: &[(String, Value)]) -> ParamTables {
+    params
+        .iter()
+        .map(|(key, value)| {
+            let mut tables = BTreeSet::new();
+            collect_tables(value, &mut tables);
+            (key.clone(), tables)
+        })
+        .collect()
+}
+
+/// Tables of the records and tables in `value`, including those nested in arrays, objects, sets,
+/// and ranges.
+fn collect_tables(value: &Value, tables: &mut BTreeSet<String>) {
+    match value {
+        Value::RecordId(record) => {
+            tables.insert(record.table.to_string());
+        }
+        Value::Table(table) => {
+            tables.insert(table.to_string());
+        }
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_tables(value, tables)),
+        Value::Set(values) => values
+            .iter()
+            .for_each(|value| collect_tables(value, tables)),
+        Value::Object(object) => object
+            .values()
+            .for_each(|value| collect_tables(value, tables)),
+        Value::Range(range) => {
+            for bound in [&range.start, &range.end] {
+                if let Bound::Included(value) | Bound::Excluded(value) = bound {
+                    collect_tables(value, tables);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rejected statements report their reason and the remaining statements report that they were
+/// skipped, mirroring how SurrealDB reports a failed transaction.
+fn denied_results<T>(verdicts: Vec<Result<(), String>>) -> Vec<Result<T, StatementError>> {
+    verdicts
+        .into_iter()
+        .map(|verdict| match verdict {
+            Ok(()) => Err(StatementError::TransactionCascade(
+                "The query was not executed because another statement was not allowed".to_string(),
+            )),
+            Err(reason) => Err(StatementError::NotAllowed(reason)),
+        })
+        .collect()
+}
+
+fn denied_message(verdicts: Vec<Result<(), String>>) -> String {
+    verdicts
+        .into_iter()
+        .filter_map(Result::err)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 pub async fn query(
     db: &Surreal<Any>,
+    session: &Session,
     query: String,
     params: Vec<(String, Vec<u8>)>,
-) -> Result<Vec<Result<Vec<u8>, String>>, QueryError> {
-    let mut query_builder = db.query(&query);
-    let decoded = decode_params(params, |key, source| QueryError::ParamDecode {
+    timeout: Option<Duration>,
+) -> Result<Vec<Result<Vec<u8>, StatementError>>, QueryError> {
+    let results = run_statements(db, session, query, params, timeout).await?;
+    Ok(results
+        .into_iter()
+        .map(|result| encode_statement_result(result, session.wire_encoding()))
+        .collect())
+}
+
+/// Like [`query`], but splits the records each statement returns into [`RowChunk`]s that are
+/// encoded on demand, so large results never exist as a single CBOR buffer.
+///
+/// This bounds guest memory only. The SurrealDB SDK hands out whole statement results, and its
+/// `QueryStream` only carries live query notifications, so the statement set runs to completion
+/// before the first chunk is produced and the decoded results stay in host memory until the
+/// returned [`RowChunks`] is dropped. Page very large reads with `START` and `LIMIT` to bound that.
+pub async fn query_stream(
+    db: &Surreal<Any>,
+    session: &Session,
+    query: String,
+    params: Vec<(String, Vec<u8>)>,
+    timeout: Option<Duration>,
+) -> Result<RowChunks, QueryError> {
+    let kinds = statement::parse(&query, &ParamTables::new())
+        .map_err(QueryError::QueryExecution)?
+        .into_iter()
+        .map(|statement| statement.kind);
+    let results = run_statements(db, session, query, params, timeout).await?;
+    let statements = kinds
+        .chain(std::iter::repeat(StatementKind::Other))
+        .zip(results)
+        .collect();
+    Ok(RowChunks::new(statements, ROW_CHUNK_SIZE).with_encoding(session.wire_encoding()))
+}
+
+async fn run_statements(
+    db: &Surreal<Any>,
+    session: &Session,
+    query: String,
+    params: Vec<(String, Vec<u8>)>,
+    timeout: Option<Duration>,
+) -> Result<Vec<Result<Value, StatementError>>, QueryError> {
+    let decoded = decode_params(params, session, |key, source| QueryError::ParamDecode {
         key,
         source,
     })?;
+    let (query, skip) = match session.prepare(Operation::Query, &query, &param_tables(&decoded)) {
+        Ok(prepared) => prepared,
+        Err(Rejection::Parse(error)) => return Err(QueryError::QueryExecution(error)),
+        Err(Rejection::Denied(verdicts)) => return Ok(denied_results(verdicts)),
+    };
+    let mut ordered = ordered_params(decoded);
+    let query =
+        bound_by_timeout(query, timeout, &mut ordered).map_err(QueryError::QueryExecution)?;
+    // Remote engines keep a `USE` for later queries on the same session. Every clone is its own
+    // session, so the prefix never changes the selection of the connection components share.
+    let db = db.clone();
+    let query_builder = db.query(&query).bind(ordered);
 
-    let ordered = ordered_params(decoded);
-    query_builder = query_builder.bind(ordered);
+    let mut response = with_timeout(timeout, query_builder.into_future())
+        .await
+        .map_err(QueryError::Timeout)?
+        .map_err(QueryError::QueryExecution)?;
+    let mut results = Vec::with_capacity(response.num_statements());
+
+    for index in skip..response.num_statements() {
+        results.push(
+            response
+                .take::<Value>(index)
+                .map_err(|error| StatementError::from(&error)),
+        );
+    }
+
+    Ok(results)
+}
 
-    let mut response = query_builder.await.map_err(QueryError::QueryExecution)?;
+fn encode_statement_result(
+    result: Result<Value, StatementError>,
+    encoding: WireEncoding,
+) -> Result<Vec<u8>, StatementError> {
+    result.and_then(|value| {
+        surreal_to_cbor_bytes(value, encoding)
+            .map_err(|error| StatementError::Internal(error.to_string()))
+    })
+}
+
+/// Starts a transaction on a new session of `db`, selecting the session's namespace and
+/// database when it assigns them.
+pub async fn begin(
+    db: &Surreal<Any>,
+    session: &Session,
+) -> Result<Transaction<Any>, TransactionError> {
+    let db = db.clone();
+    if let (Some(namespace), Some(database)) = (session.namespace(), session.database()) {
+        db.use_ns(namespace)
+            .use_db(database)
+            .await
+            .map_err(TransactionError::Begin)?;
+    }
+    db.begin().await.map_err(TransactionError::Begin)
+}
+
+pub async fn transaction_query(
+    transaction: &Transaction<Any>,
+    session: &Session,
+    query: String,
+    params: Vec<(String, Vec<u8>)>,
+    timeout: Option<Duration>,
+) -> Result<Vec<Result<Vec<u8>, StatementError>>, QueryError> {
+    let decoded = decode_params(params, session, |key, source| QueryError::ParamDecode {
+        key,
+        source,
+    })?;
+    let (query, skip) = match session.prepare(Operation::Query, &query, &param_tables(&decoded)) {
+        Ok(prepared) => prepared,
+        Err(Rejection::Parse(error)) => return Err(QueryError::QueryExecution(error)),
+        Err(Rejection::Denied(verdicts)) => return Ok(denied_results(verdicts)),
+    };
+
+    let mut ordered = ordered_params(decoded);
+    let query =
+        bound_by_timeout(query, timeout, &mut ordered).map_err(QueryError::QueryExecution)?;
+    let query_builder = transaction.query(&query).bind(ordered);
+    let mut response = with_timeout(timeout, query_builder.into_future())
+        .await
+        .map_err(QueryError::Timeout)?
+        .map_err(QueryError::QueryExecution)?;
     let mut results = Vec::with_capacity(response.num_statements());
 
-    for index in 0..response.num_statements() {
-        match response.take::<Value>(index) {
-            Ok(value) => match surreal_to_cbor_bytes(value) {
-                Ok(bytes) => results.push(Ok(bytes)),
-                Err(error) => results.push(Err(error.to_string())),
-            },
-            Err(error) => results.push(Err(error.to_string())),
-        }
+    for index in skip..response.num_statements() {
+        results.push(encode_statement_result(
+            response
+                .take::<Value>(index)
+                .map_err(|error| StatementError::from(&error)),
+            session.wire_encoding(),
+        ));
     }
 
     Ok(results)
 }
 
+pub async fn commit(transaction: Transaction<Any>) -> Result<(), TransactionError> {
+    transaction
+        .commit()
+        .await
+        .map_err(TransactionError::Commit)?;
+    Ok(())
+}
+
+pub async fn cancel_transaction(transaction: Transaction<Any>) -> Result<(), TransactionError> {
+    transaction
+        .cancel()
+        .await
+        .map_err(TransactionError::Cancel)?;
+    Ok(())
+}
+
 pub async fn subscribe(
     db: &Surreal<Any>,
+    session: &Session,
     query: String,
     params: Vec<(String, Vec<u8>)>,
+    timeout: Option<Duration>,
 ) -> Result<QueryStream<Notification<Value>>, SubscribeError> {
-    let mut query_builder = db.query(&query);
-    let decoded = decode_params(params, |key, source| SubscribeError::ParamDecode {
+    let decoded = decode_params(params, session, |key, source| SubscribeError::ParamDecode {
         key,
         source,
     })?;
+    let (query, _) = session
+        .prepare(Operation::Subscribe, &query, &param_tables(&decoded))
+        .map_err(|rejection| match rejection {
+            Rejection::Parse(error) => SubscribeError::QueryExecution(error),
+            Rejection::Denied(verdicts) => SubscribeError::NotAllowed(denied_message(verdicts)),
+        })?;
+    let db = db.clone();
+    let mut query_builder = db.query(&query);
 
     let ordered = ordered_params(decoded);
     query_builder = query_builder.bind(ordered);
 
-    let mut response = query_builder
+    let mut response = with_timeout(timeout, query_builder.into_future())
         .await
+        .map_err(SubscribeError::Timeout)?
         .map_err(SubscribeError::QueryExecution)?;
 
     let stream = response
@@ -123,6 +487,7 @@ pub async fn subscribe(
 pub fn notification_to_live_event(
     subscription_id: u64,
     notification: Notification<Value>,
+    encoding: WireEncoding,
 ) -> Result<LiveEvent, SubscribeError> {
     let action = match notification.action {
         Action::Create => LiveAction::Create,
@@ -131,22 +496,84 @@ pub fn notification_to_live_event(
         Action::Killed | Action::Error => LiveAction::Killed,
     };
 
-    let data = surreal_to_cbor_bytes(notification.data).map_err(SubscribeError::Serialize)?;
+    let record = match &notification.data {
+        Value::Object(object) => match object.get("id") {
+            Some(Value::RecordId(id)) => Some(id.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let data =
+        surreal_to_cbor_bytes(notification.data, encoding).map_err(SubscribeError::Serialize)?;
 
     Ok(LiveEvent {
         subscription_id,
         query_id: notification.query_id.to_string(),
         action,
+        record,
         data,
     })
 }
 
+/// Final event of a subscription ended by [`OverflowPolicy::Kill`].
+pub fn lagged_live_event(
+    subscription_id: u64,
+    query_id: String,
+    encoding: WireEncoding,
+) -> Result<LiveEvent, SubscribeError> {
+    closing_live_event(
+        subscription_id,
+        query_id,
+        LiveAction::Lagged,
+        Value::None,
+        encoding,
+    )
+}
+
+/// Final event of a subscription whose live stream failed, with the error message as its data.
+pub fn killed_live_event(
+    subscription_id: u64,
+    query_id: String,
+    message: String,
+    encoding: WireEncoding,
+) -> Result<LiveEvent, SubscribeError> {
+    closing_live_event(
+        subscription_id,
+        query_id,
+        LiveAction::Killed,
+        Value::String(message),
+        encoding,
+    )
+}
+
+fn closing_live_event(
+    subscription_id: u64,
+    query_id: String,
+    action: LiveAction,
+    data: Value,
+    encoding: WireEncoding,
+) -> Result<LiveEvent, SubscribeError> {
+    Ok(LiveEvent {
+        subscription_id,
+        query_id,
+        action,
+        record: None,
+        data: surreal_to_cbor_bytes(data, encoding).map_err(SubscribeError::Serialize)?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use serde_json::json;
     use surrealdb::{Surreal, engine::any::Any};
 
-    use super::query;
+    use super::{
+        IntegerPolicy, LiveAction, QueryDetail, QueryError, Session, StatementError,
+        StatementPolicy, SubscribeError, WireEncoding, begin, cancel_transaction, commit,
+        killed_live_event, query, query_stream, subscribe, transaction_query,
+    };
 
     async fn test_db() -> Surreal<Any> {
         let db: Surreal<Any> = Surreal::init();
@@ -168,8 +595,10 @@ mod tests {
 
         let results = query(
             &db,
+            &Session::default(),
             "RETURN type::is_uuid($id); RETURN $id;".to_string(),
             vec![("id".to_string(), param)],
+            None,
         )
         .await
         .unwrap();
@@ -188,15 +617,213 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn binds_datetime_params_as_native_surreal_values() {
+        let db = test_db().await;
+        let param =
+            serde_cbor::to_vec(&json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})).unwrap();
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "RETURN type::is_datetime($at); RETURN $at;".to_string(),
+            vec![("at".to_string(), param)],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+
+        let is_datetime =
+            serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(is_datetime, json!(true));
+
+        let value =
+            serde_cbor::from_slice::<serde_json::Value>(results[1].as_ref().unwrap()).unwrap();
+        assert_eq!(
+            value,
+            json!({"$surrealdb::datetime": "2024-03-15T12:34:56Z"})
+        );
+    }
+
+    #[tokio::test]
+    async fn selects_record_id_ranges_from_bound_params() {
+        let db = test_db().await;
+        let ids = serde_cbor::to_vec(
+            &json!({"$surrealdb::record_id": {"table": "person", "key": {"$surrealdb::range": {
+                "start": {"included": 1},
+                "end": {"excluded": 5},
+            }}}}),
+        )
+        .unwrap();
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "CREATE person:1, person:2, person:4, person:5, person:6; SELECT VALUE id FROM $ids; RETURN $ids;"
+                .to_string(),
+            vec![("ids".to_string(), ids)],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 3);
+
+        let selected =
+            serde_cbor::from_slice::<serde_json::Value>(results[1].as_ref().unwrap()).unwrap();
+        assert_eq!(
+            selected,
+            json!([
+                {"$surrealdb::record_id": {"table": "person", "key": 1}},
+                {"$surrealdb::record_id": {"table": "person", "key": 2}},
+                {"$surrealdb::record_id": {"table": "person", "key": 4}},
+            ])
+        );
+
+        let echoed =
+            serde_cbor::from_slice::<serde_json::Value>(results[2].as_ref().unwrap()).unwrap();
+        assert_eq!(
+            echoed,
+            json!({"$surrealdb::record_id": {"table": "person", "key": {"$surrealdb::range": {
+                "start": {"included": 1},
+                "end": {"excluded": 5},
+            }}}})
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_none_and_sets_apart_from_null_and_arrays() {
+        let db = test_db().await;
+        let profile = serde_cbor::to_vec(&json!({
+            "nickname": {"$surrealdb::none": null},
+            "tags": {"$surrealdb::set": ["b", "a", "b"]},
+        }))
+        .unwrap();
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "DEFINE TABLE profile SCHEMAFULL;
+             DEFINE FIELD nickname ON profile TYPE option<string>;
+             DEFINE FIELD tags ON profile TYPE set<string>;
+             CREATE ONLY profile:demo CONTENT $profile RETURN nickname, tags;
+             RETURN [NONE, NULL];"
+                .to_string(),
+            vec![("profile".to_string(), profile)],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 5);
+
+        let created =
+            serde_cbor::from_slice::<serde_json::Value>(results[3].as_ref().unwrap()).unwrap();
+        assert_eq!(created, json!({"tags": {"$surrealdb::set": ["a", "b"]}}));
+
+        let returned =
+            serde_cbor::from_slice::<serde_json::Value>(results[4].as_ref().unwrap()).unwrap();
+        assert_eq!(returned, json!([{"$surrealdb::none": null}, null]));
+    }
+
+    #[tokio::test]
+    async fn binds_large_integers_by_session_integer_policy() {
+        let db = test_db().await;
+        let params = || vec![("hash".to_string(), serde_cbor::to_vec(&u64::MAX).unwrap())];
+
+        let error = query(
+            &db,
+            &Session::default(),
+            "RETURN $hash;".to_string(),
+            params(),
+            None,
+        )
+        .await
+        .unwrap_err();
+        let QueryError::ParamDecode { key, source } = error else {
+            panic!("expected a param decode error, got {error:?}");
+        };
+        assert_eq!(key, "hash");
+        assert_eq!(source.pointer, "/hash");
+        assert_eq!(source.expected, "an integer within the i64 range");
+
+        let results = query(
+            &db,
+            &Session::default().with_integer_policy(IntegerPolicy::LosslessDecimal),
+            "RETURN type::is_decimal($hash); RETURN $hash == 18446744073709551615dec;".to_string(),
+            params(),
+            None,
+        )
+        .await
+        .unwrap();
+        let decoded: Vec<serde_json::Value> = results
+            .iter()
+            .map(|result| serde_cbor::from_slice(result.as_ref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(decoded, vec![json!(true), json!(true)]);
+    }
+
+    #[tokio::test]
+    async fn binds_geometry_params_as_native_surreal_values() {
+        let db = test_db().await;
+        let point = |x: f64, y: f64| {
+            serde_cbor::to_vec(&json!({"$surrealdb::geometry": {
+                "type": "Point",
+                "coordinates": [x, y],
+            }}))
+            .unwrap()
+        };
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "RETURN type::is_point($london); RETURN geo::distance($london, $paris); RETURN $paris;"
+                .to_string(),
+            vec![
+                ("london".to_string(), point(-0.1278, 51.5074)),
+                ("paris".to_string(), point(2.3522, 48.8566)),
+            ],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.len(), 3);
+
+        let is_point =
+            serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(is_point, json!(true));
+
+        let distance = serde_cbor::from_slice::<f64>(results[1].as_ref().unwrap()).unwrap();
+        assert!(
+            (300_000.0..400_000.0).contains(&distance),
+            "unexpected distance {distance}"
+        );
+
+        let value =
+            serde_cbor::from_slice::<serde_json::Value>(results[2].as_ref().unwrap()).unwrap();
+        assert_eq!(
+            value,
+            json!({"$surrealdb::geometry": {"type": "Point", "coordinates": [2.3522, 48.8566]}})
+        );
+    }
+
     #[tokio::test]
     async fn binds_record_id_with_string_key_as_native_record() {
         let db = test_db().await;
-        let param = serde_cbor::to_vec(&json!({"table": "person", "key": "demo"})).unwrap();
+        let param = serde_cbor::to_vec(
+            &json!({"$surrealdb::record_id": {"table": "person", "key": "demo"}}),
+        )
+        .unwrap();
 
         let results = query(
             &db,
+            &Session::default(),
             "RETURN $id = person:demo; RETURN type::is_record($id);".to_string(),
             vec![("id".to_string(), param)],
+            None,
         )
         .await
         .unwrap();
@@ -215,12 +842,16 @@ mod tests {
     #[tokio::test]
     async fn binds_record_id_with_number_key_as_native_record() {
         let db = test_db().await;
-        let param = serde_cbor::to_vec(&json!({"table": "person", "key": 42})).unwrap();
+        let param =
+            serde_cbor::to_vec(&json!({"$surrealdb::record_id": {"table": "person", "key": 42}}))
+                .unwrap();
 
         let results = query(
             &db,
+            &Session::default(),
             "RETURN $id = person:42; RETURN type::is_record($id);".to_string(),
             vec![("id".to_string(), param)],
+            None,
         )
         .await
         .unwrap();
@@ -239,16 +870,18 @@ mod tests {
     #[tokio::test]
     async fn binds_record_id_with_uuid_key_as_native_record() {
         let db = test_db().await;
-        let param = serde_cbor::to_vec(&json!({
+        let param = serde_cbor::to_vec(&json!({"$surrealdb::record_id": {
             "table": "person",
             "key": {"$surrealdb::uuid": "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"}
-        }))
+        }}))
         .unwrap();
 
         let results = query(
             &db,
+            &Session::default(),
             "RETURN type::is_record($id);".to_string(),
             vec![("id".to_string(), param)],
+            None,
         )
         .await
         .unwrap();
@@ -263,16 +896,18 @@ mod tests {
     #[tokio::test]
     async fn binds_record_id_with_object_key_as_native_record() {
         let db = test_db().await;
-        let param = serde_cbor::to_vec(&json!({
+        let param = serde_cbor::to_vec(&json!({"$surrealdb::record_id": {
             "table": "person",
             "key": {"tenant": "demo", "shard": 1}
-        }))
+        }}))
         .unwrap();
 
         let results = query(
             &db,
+            &Session::default(),
             "RETURN type::is_record($id);".to_string(),
             vec![("id".to_string(), param)],
+            None,
         )
         .await
         .unwrap();
@@ -283,4 +918,482 @@ mod tests {
             serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
         assert_eq!(is_record, json!(true));
     }
+
+    #[tokio::test]
+    async fn binds_untagged_record_id_maps_as_objects() {
+        let db = test_db().await;
+        let param = serde_cbor::to_vec(&json!({"table": "person", "key": "demo"})).unwrap();
+        let results = query(
+            &db,
+            &Session::default(),
+            "RETURN type::is_record($id); RETURN $id;".to_string(),
+            vec![("id".to_string(), param)],
+            None,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|result| serde_cbor::from_slice::<serde_json::Value>(&result.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            [json!(false), json!({"table": "person", "key": "demo"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn committed_transaction_statements_are_visible() {
+        let db = test_db().await;
+        let transaction = begin(&db, &Session::default()).await.unwrap();
+
+        let results = transaction_query(
+            &transaction,
+            &Session::default(),
+            "CREATE tx_commit:demo SET value = 1;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(results[0].is_ok());
+
+        commit(transaction).await.unwrap();
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "SELECT VALUE value FROM tx_commit;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        let values =
+            serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(values, json!([1]));
+    }
+
+    #[tokio::test]
+    async fn cancelled_transaction_statements_are_discarded() {
+        let db = test_db().await;
+        let transaction = begin(&db, &Session::default()).await.unwrap();
+
+        transaction_query(
+            &transaction,
+            &Session::default(),
+            "CREATE tx_cancel:demo SET value = 1;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        cancel_transaction(transaction).await.unwrap();
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "SELECT VALUE value FROM tx_cancel;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        // The table was created by the discarded statement, so it does not exist either.
+        assert!(matches!(results[0], Err(StatementError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn classifies_thrown_errors_and_transaction_cascades() {
+        let db = test_db().await;
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "BEGIN; CREATE tx_error:demo; THROW 'boom'; COMMIT;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // BEGIN reports a result of its own.
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(StatementError::TransactionCascade(_))
+        ));
+        assert!(matches!(results[2], Err(StatementError::Thrown(_))));
+        assert!(matches!(
+            results[3],
+            Err(StatementError::TransactionCascade(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn classifies_statement_set_parse_errors() {
+        let db = test_db().await;
+
+        let error = query(
+            &db,
+            &Session::default(),
+            "SELEC * FROM person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        let QueryError::QueryExecution(source) = error else {
+            panic!("expected query execution error, got {error:?}");
+        };
+        assert!(matches!(
+            StatementError::from(&source),
+            StatementError::ParseError(_)
+        ));
+    }
+
+    #[test]
+    fn classifies_from_typed_error_details_not_messages() {
+        let timed_out = surrealdb::Error::query(
+            "Parse error".to_string(),
+            QueryDetail::TimedOut {
+                duration: Duration::from_secs(1),
+            },
+        );
+        let cancelled = surrealdb::Error::query("anything".to_string(), QueryDetail::Cancelled);
+        let untyped = surrealdb::Error::query(
+            "The query was not executed because it exceeded the timeout".to_string(),
+            None,
+        );
+
+        assert!(matches!(
+            StatementError::from(&timed_out),
+            StatementError::Timeout(_)
+        ));
+        assert!(matches!(
+            StatementError::from(&cancelled),
+            StatementError::TransactionCascade(_)
+        ));
+        assert!(matches!(
+            StatementError::from(&untyped),
+            StatementError::Internal(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn session_pins_statements_to_assigned_database() {
+        let db = test_db().await;
+        let tenant = Session::new("host_adapter_tests", "tenant_a");
+
+        query(
+            &db,
+            &tenant,
+            "CREATE session_person:demo SET name = 'tenant_a';".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let results = query(
+            &db,
+            &Session::new("host_adapter_tests", "tenant_b"),
+            "SELECT VALUE name FROM session_person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(StatementError::NotFound(_))));
+
+        let results = query(
+            &db,
+            &tenant,
+            "SELECT VALUE name FROM session_person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        let names =
+            serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(names, json!(["tenant_a"]));
+    }
+
+    #[tokio::test]
+    async fn session_prefix_does_not_change_the_shared_connection() {
+        let db = test_db().await;
+
+        query(
+            &db,
+            &Session::new("host_adapter_tests", "tenant_a"),
+            "CREATE leak_person:demo SET name = 'tenant_a';".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The next component's statements still run in the connection's own database.
+        let results = query(
+            &db,
+            &Session::default(),
+            "SELECT VALUE name FROM leak_person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(results[0], Err(StatementError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn transactions_begin_in_the_session_database() {
+        let db = test_db().await;
+        let tenant = Session::new("host_adapter_tests", "tenant_tx");
+        let transaction = begin(&db, &tenant).await.unwrap();
+
+        transaction_query(
+            &transaction,
+            &Session::default(),
+            "CREATE tx_session:demo SET name = 'tenant_tx';".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        commit(transaction).await.unwrap();
+
+        let results = query(
+            &db,
+            &tenant,
+            "SELECT VALUE name FROM tx_session;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        let names =
+            serde_cbor::from_slice::<serde_json::Value>(results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(names, json!(["tenant_tx"]));
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "SELECT VALUE name FROM tx_session;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(results[0], Err(StatementError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn session_rejects_use_statements() {
+        let db = test_db().await;
+
+        let results = query(
+            &db,
+            &Session::new("host_adapter_tests", "tenant_a"),
+            "USE DB tenant_b; SELECT * FROM session_person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(results[0], Err(StatementError::NotAllowed(_))));
+        assert!(matches!(
+            results[1],
+            Err(StatementError::TransactionCascade(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn policy_denies_statements_before_execution() {
+        let db = test_db().await;
+        let session = Session::new("host_adapter_tests", "policy")
+            .with_policy(StatementPolicy::untrusted().deny_table("policy_secret"));
+
+        let results = query(
+            &db,
+            &session,
+            "CREATE policy_person:demo; DELETE $record; INFO FOR ROOT;".to_string(),
+            vec![(
+                "record".to_string(),
+                serde_cbor::to_vec(
+                    &json!({"$surrealdb::record_id": {"table": "policy_secret", "key": "one"}}),
+                )
+                .unwrap(),
+            )],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                Err(StatementError::TransactionCascade(
+                    "The query was not executed because another statement was not allowed"
+                        .to_string()
+                )),
+                Err(StatementError::NotAllowed(
+                    "access to table policy_secret is not allowed".to_string()
+                )),
+                Err(StatementError::NotAllowed(
+                    "INFO FOR ROOT statements are not allowed".to_string()
+                )),
+            ]
+        );
+
+        let results = query(
+            &db,
+            &session,
+            "SELECT * FROM policy_person;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(results[0], Err(StatementError::NotFound(_))));
+
+        let error = subscribe(
+            &db,
+            &session,
+            "LIVE SELECT * FROM policy_secret;".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(matches!(error, SubscribeError::NotAllowed(_)));
+    }
+
+    #[tokio::test]
+    async fn timeout_drops_slow_statement_sets() {
+        let db = test_db().await;
+        let timeout = Duration::from_millis(50);
+
+        let error = query(
+            &db,
+            &Session::default(),
+            "SLEEP 5s; RETURN true;".to_string(),
+            Vec::new(),
+            Some(timeout),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, QueryError::Timeout(elapsed) if elapsed == timeout));
+    }
+
+    #[tokio::test]
+    async fn timed_out_statement_sets_write_nothing_after_the_deadline() {
+        let db = test_db().await;
+
+        let error = query(
+            &db,
+            &Session::default(),
+            "SLEEP 300ms; CREATE probe:after;".to_string(),
+            Vec::new(),
+            Some(Duration::from_millis(100)),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, QueryError::Timeout(_)));
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let results = query(
+            &db,
+            &Session::default(),
+            "RETURN record::exists(probe:after);".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &serde_cbor::to_vec(&false).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn timed_statement_sets_keep_their_results() {
+        let db = test_db().await;
+
+        let results = query(
+            &db,
+            &Session::default(),
+            "CREATE bounded:1; SELECT VALUE id FROM bounded TIMEOUT 5s; \
+             IF true THEN (UPDATE bounded:1 SET n = 1 RETURN VALUE n) END;"
+                .to_string(),
+            Vec::new(),
+            Some(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        assert_eq!(
+            results[2].as_ref().unwrap(),
+            &serde_cbor::to_vec(&[1]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn query_stream_yields_rows_per_statement() {
+        let db = test_db().await;
+
+        let chunks: Vec<_> = query_stream(
+            &db,
+            &Session::default(),
+            "SELECT VALUE n FROM [{ n: 1 }, { n: 2 }, { n: 3 }]; RETURN [1, 2, 3];".to_string(),
+            Vec::new(),
+            None,
+        )
+        .await
+        .unwrap()
+        .collect();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].statement, 0);
+        let rows = chunks[0]
+            .rows
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|row| serde_cbor::from_slice::<serde_json::Value>(row).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![json!(1), json!(2), json!(3)]);
+        assert_eq!(chunks[1].statement, 1);
+        assert_eq!(
+            chunks[1].rows,
+            Ok(vec![serde_cbor::to_vec(&[1, 2, 3]).unwrap()])
+        );
+    }
+
+    #[test]
+    fn killed_events_carry_the_stream_error() {
+        let event = killed_live_event(
+            7,
+            "query".to_string(),
+            "stream failed".to_string(),
+            WireEncoding::Legacy,
+        )
+        .unwrap();
+
+        assert_eq!(event.subscription_id, 7);
+        assert!(matches!(event.action, LiveAction::Killed));
+        assert_eq!(
+            serde_cbor::from_slice::<String>(&event.data).unwrap(),
+            "stream failed"
+        );
+    }
 }
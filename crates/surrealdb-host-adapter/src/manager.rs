@@ -3,18 +3,6 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::{Mutex, oneshot};
 use tokio::task::JoinHandle;
 
-#[cfg(feature = "debug-logs")]
-macro_rules! debug_log {
-    ($($arg:tt)*) => {
-        println!($($arg)*);
-    };
-}
-
-#[cfg(not(feature = "debug-logs"))]
-macro_rules! debug_log {
-    ($($arg:tt)*) => {{};};
-}
-
 pub struct SubscriptionTask {
     stop_tx: oneshot::Sender<()>,
     handle: JoinHandle<()>,
@@ -0,0 +1,304 @@
+use std::collections::BTreeSet;
+
+use crate::statement::{Statement, StatementKind};
+
+/// Host call a statement set was submitted through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Query,
+    Subscribe,
+}
+
+/// Decides which statements a component may run.
+///
+/// Every top level statement of a statement set is checked before anything executes. When any
+/// statement is rejected the whole set is skipped and each rejected statement reports its reason
+/// as a not allowed error.
+pub trait QueryPolicy: Send + Sync {
+    fn check(&self, operation: Operation, statement: &Statement) -> Result<(), String>;
+}
+
+pub(crate) const UNCLASSIFIED: &str =
+    "statements the query analyzer cannot classify are not allowed";
+
+/// Allows every statement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+impl QueryPolicy for AllowAll {
+    fn check(&self, _operation: Operation, _statement: &Statement) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Allows or denies statements by kind, resource, and table.
+///
+/// Statements nested in blocks and subqueries are checked as well, and statements the analyzer
+/// cannot classify are always denied. `LIVE` statements are only
+/// denied through [`Operation::Query`], so [`StatementPolicy::deny`] with [`StatementKind::Live`]
+/// still lets components subscribe.
+#[derive(Debug, Clone, Default)]
+pub struct StatementPolicy {
+    denied: Vec<(StatementKind, Option<String>)>,
+    allowed_tables: Option<BTreeSet<String>>,
+    denied_tables: BTreeSet<String>,
+}
+
+impl StatementPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Denies statements that reach outside a component's database or manage credentials.
+    pub fn untrusted() -> Self {
+        Self::new()
+            .deny(StatementKind::Kill)
+            .deny(StatementKind::Live)
+            .deny(StatementKind::Use)
+            .deny(StatementKind::Access)
+            .deny(StatementKind::Option)
+            .deny_resource(StatementKind::Define, "USER")
+            .deny_resource(StatementKind::Define, "ACCESS")
+            .deny_resource(StatementKind::Define, "NAMESPACE")
+            .deny_resource(StatementKind::Define, "NS")
+            .deny_resource(StatementKind::Define, "DATABASE")
+            .deny_resource(StatementKind::Define, "DB")
+            .deny_resource(StatementKind::Alter, "USER")
+            .deny_resource(StatementKind::Alter, "ACCESS")
+            .deny_resource(StatementKind::Remove, "USER")
+            .deny_resource(StatementKind::Remove, "ACCESS")
+            .deny_resource(StatementKind::Remove, "NAMESPACE")
+            .deny_resource(StatementKind::Remove, "NS")
+            .deny_resource(StatementKind::Remove, "DATABASE")
+            .deny_resource(StatementKind::Remove, "DB")
+            .deny_resource(StatementKind::Info, "ROOT")
+            .deny_resource(StatementKind::Info, "NAMESPACE")
+            .deny_resource(StatementKind::Info, "NS")
+    }
+
+    pub fn deny(mut self, kind: StatementKind) -> Self {
+        self.denied.push((kind, None));
+        self
+    }
+
+    /// Denies `kind` only for `resource`, such as `DEFINE USER` or `INFO FOR ROOT`.
+    pub fn deny_resource(mut self, kind: StatementKind, resource: impl Into<String>) -> Self {
+        self.denied
+            .push((kind, Some(resource.into().to_ascii_uppercase())));
+        self
+    }
+
+    /// Restricts statements to `tables`. Statements that reach tables only known at runtime,
+    /// see [`Statement::dynamic_tables`], are denied once an allowlist is set.
+    pub fn allow_tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_tables
+            .get_or_insert_with(BTreeSet::new)
+            .extend(tables.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn deny_table(mut self, table: impl Into<String>) -> Self {
+        self.denied_tables.insert(table.into());
+        self
+    }
+
+    fn is_denied(&self, operation: Operation, kind: StatementKind, resource: Option<&str>) -> bool {
+        if kind == StatementKind::Live && operation == Operation::Subscribe {
+            return false;
+        }
+
+        self.denied.iter().any(|(denied, denied_resource)| {
+            *denied == kind
+                && denied_resource
+                    .as_deref()
+                    .is_none_or(|denied_resource| Some(denied_resource) == resource)
+        })
+    }
+}
+
+impl QueryPolicy for StatementPolicy {
+    fn check(&self, operation: Operation, statement: &Statement) -> Result<(), String> {
+        if !statement.classified {
+            return Err(UNCLASSIFIED.to_string());
+        }
+
+        for (kind, resource) in statement.kinds() {
+            if self.is_denied(operation, kind, resource) {
+                let statement = match (kind, resource) {
+                    (StatementKind::Info, Some(resource)) => format!("INFO FOR {resource}"),
+                    (kind, Some(resource)) => format!("{kind} {resource}"),
+                    (kind, None) => kind.to_string(),
+                };
+                return Err(format!("{statement} statements are not allowed"));
+            }
+        }
+
+        for table in &statement.tables {
+            let allowed = self
+                .allowed_tables
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(table));
+            if !allowed || self.denied_tables.contains(table) {
+                return Err(format!("access to table {table} is not allowed"));
+            }
+        }
+
+        if statement.dynamic_tables && self.allowed_tables.is_some() {
+            return Err("statements with runtime table targets are not allowed".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{Operation, QueryPolicy, StatementPolicy, UNCLASSIFIED};
+    use crate::statement::{ParamTables, parse};
+
+    fn check(
+        policy: &StatementPolicy,
+        operation: Operation,
+        query: &str,
+    ) -> Vec<Result<(), String>> {
+        parse(query, &ParamTables::new())
+            .unwrap()
+            .iter()
+            .map(|statement| policy.check(operation, statement))
+            .collect()
+    }
+
+    #[test]
+    fn untrusted_policy_denies_administrative_statements() {
+        let policy = StatementPolicy::untrusted();
+
+        for query in [
+            "REMOVE DATABASE app",
+            "DEFINE USER admin ON ROOT PASSWORD 'secret' ROLES OWNER",
+            "INFO FOR ROOT",
+            "KILL u'0189d6e3-8eac-703a-9a48-d9faa78b44b9'",
+            "LIVE SELECT * FROM person",
+            "IF true { REMOVE DATABASE app }",
+            "LET $x = REMOVE DATABASE app",
+            "RETURN (REMOVE DATABASE app)",
+            "LET $x = DEFINE USER admin ON ROOT PASSWORD 'x'",
+        ] {
+            assert!(
+                check(&policy, Operation::Query, query)[0].is_err(),
+                "expected {query:?} to be denied"
+            );
+        }
+
+        assert_eq!(
+            check(
+                &policy,
+                Operation::Query,
+                "DEFINE TABLE person; INFO FOR DB; SELECT * FROM person"
+            ),
+            vec![Ok(()), Ok(()), Ok(())]
+        );
+        assert_eq!(
+            check(&policy, Operation::Subscribe, "LIVE SELECT * FROM person"),
+            vec![Ok(())]
+        );
+    }
+
+    #[test]
+    fn table_rules_cover_targets_records_and_runtime_tables() {
+        let policy = StatementPolicy::new()
+            .allow_tables(["person", "post"])
+            .deny_table("post");
+
+        assert_eq!(
+            check(
+                &policy,
+                Operation::Query,
+                "SELECT * FROM person; SELECT * FROM post; RETURN secret:1.*; SELECT * FROM type::table($t)"
+            ),
+            vec![
+                Ok(()),
+                Err("access to table post is not allowed".to_string()),
+                Err("access to table secret is not allowed".to_string()),
+                Err("statements with runtime table targets are not allowed".to_string()),
+            ]
+        );
+        assert_eq!(
+            check(
+                &policy,
+                Operation::Query,
+                "LET $x = DELETE secret; RETURN DELETE post; LET $y = UPDATE person SET a = 1"
+            ),
+            vec![
+                Err("access to table secret is not allowed".to_string()),
+                Err("access to table post is not allowed".to_string()),
+                Ok(()),
+            ]
+        );
+    }
+
+    #[test]
+    fn allowlists_deny_records_built_or_fetched_at_runtime() {
+        let policy = StatementPolicy::new().allow_tables(["person"]);
+        let mut param_tables = ParamTables::new();
+        param_tables.insert("id".to_string(), BTreeSet::from(["secret".to_string()]));
+        param_tables.insert("me".to_string(), BTreeSet::from(["person".to_string()]));
+        param_tables.insert("name".to_string(), BTreeSet::new());
+        let check = |query: &str| {
+            parse(query, &param_tables)
+                .unwrap()
+                .iter()
+                .map(|statement| policy.check(Operation::Query, statement))
+                .collect::<Vec<_>>()
+        };
+
+        for query in [
+            "RETURN $id.*",
+            "RETURN record::exists($id)",
+            "RETURN (<record>'secret:1').*",
+            "RETURN type::record('secret', 1).*",
+            "RETURN record::exists(type::record('secret', 1))",
+            "RETURN <option<record>> $name",
+            "LET $r = (SELECT VALUE id FROM person); RETURN $r.*",
+            "RETURN person:1->knows->?",
+            "RETURN person:1<~secret",
+        ] {
+            assert!(
+                check(query).iter().any(Result::is_err),
+                "expected {query:?} to be denied"
+            );
+        }
+
+        for query in [
+            "RETURN $me.*",
+            "RETURN person:1.*",
+            "RETURN record::exists(person:1)",
+            "SELECT * FROM person WHERE name = <string> $name",
+            "SELECT * FROM person WHERE age < 3",
+            "DEFINE FIELD friend ON person TYPE option<record<person>>",
+        ] {
+            assert_eq!(
+                check(query),
+                vec![Ok(())],
+                "expected {query:?} to be allowed"
+            );
+        }
+    }
+
+    #[test]
+    fn denies_unclassified_statements() {
+        assert_eq!(
+            check(
+                &StatementPolicy::new(),
+                Operation::Query,
+                "RETURN function() { return 1; }"
+            ),
+            vec![Err(UNCLASSIFIED.to_string())]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use surrealdb_types::Value;
+
+use crate::convert::{WireEncoding, surreal_to_cbor_bytes};
+use crate::{StatementError, StatementKind};
+
+/// Rows encoded per chunk when streaming statement results.
+pub const ROW_CHUNK_SIZE: usize = 256;
+
+/// Consecutive rows of one statement result, each encoded as normalized CBOR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChunk {
+    pub statement: u32,
+    pub rows: Result<Vec<Vec<u8>>, StatementError>,
+}
+
+/// Splits statement results into [`RowChunk`]s, encoding rows only as chunks are requested.
+///
+/// The results are already materialised, so only their CBOR encoding is deferred.
+///
+/// Every statement yields at least one chunk. Array results of statements that return records,
+/// such as `SELECT` and `CREATE`, are split into rows. Any other result, such as the array of
+/// `RETURN [1, 2, 3]`, is a single row, and a failed statement yields one error chunk.
+pub struct RowChunks {
+    statements:
+        std::iter::Enumerate<std::vec::IntoIter<(StatementKind, Result<Value, StatementError>)>>,
+    current: Option<(u32, std::vec::IntoIter<Value>)>,
+    chunk_size: usize,
+    encoding: WireEncoding,
+}
+
+impl RowChunks {
+    pub fn new(
+        statements: Vec<(StatementKind, Result<Value, StatementError>)>,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            statements: statements.into_iter().enumerate(),
+            current: None,
+            chunk_size: chunk_size.max(1),
+            encoding: WireEncoding::default(),
+        }
+    }
+
+    /// Encodes rows in `encoding` instead of [`WireEncoding::Legacy`].
+    pub fn with_encoding(mut self, encoding: WireEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+}
+
+impl Iterator for RowChunks {
+    type Item = RowChunk;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((statement, rows)) = &mut self.current {
+                let statement = *statement;
+                let encoding = self.encoding;
+                let chunk = rows
+                    .by_ref()
+                    .take(self.chunk_size)
+                    .map(|row| encode_row(row, encoding))
+                    .collect::<Result<Vec<_>, _>>();
+                match chunk {
+                    Ok(chunk) if chunk.is_empty() => self.current = None,
+                    Ok(chunk) => {
+                        return Some(RowChunk {
+                            statement,
+                            rows: Ok(chunk),
+                        });
+                    }
+                    Err(error) => {
+                        self.current = None;
+                        return Some(RowChunk {
+                            statement,
+                            rows: Err(error),
+                        });
+                    }
+                }
+            }
+
+            let (index, (kind, result)) = self.statements.next()?;
+            let statement = index as u32;
+            match result {
+                Err(error) => {
+                    return Some(RowChunk {
+                        statement,
+                        rows: Err(error),
+                    });
+                }
+                Ok(Value::Array(rows)) if returns_rows(kind) => {
+                    let rows: Vec<Value> = rows.into_iter().collect();
+                    if rows.is_empty() {
+                        return Some(RowChunk {
+                            statement,
+                            rows: Ok(Vec::new()),
+                        });
+                    }
+                    self.current = Some((statement, rows.into_iter()));
+                }
+                Ok(value) => {
+                    return Some(RowChunk {
+                        statement,
+                        rows: encode_row(value, self.encoding).map(|row| vec![row]),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Whether `kind` returns its records as an array of rows.
+fn returns_rows(kind: StatementKind) -> bool {
+    matches!(
+        kind,
+        StatementKind::Select
+            | StatementKind::Create
+            | StatementKind::Update
+            | StatementKind::Upsert
+            | StatementKind::Delete
+            | StatementKind::Insert
+            | StatementKind::Relate
+    )
+}
+
+fn encode_row(value: Value, encoding: WireEncoding) -> Result<Vec<u8>, StatementError> {
+    surreal_to_cbor_bytes(value, encoding)
+        .map_err(|error| StatementError::Internal(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use surrealdb_types::{Array, Number, Value};
+
+    use super::{RowChunk, RowChunks};
+    use crate::{StatementError, StatementKind};
+
+    fn int(value: i64) -> Value {
+        Value::Number(Number::Int(value))
+    }
+
+    fn rows(values: &[i64]) -> Vec<Vec<u8>> {
+        values
+            .iter()
+            .map(|value| serde_cbor::to_vec(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn splits_statement_results_into_chunks() {
+        let chunks: Vec<_> = RowChunks::new(
+            vec![
+                (
+                    StatementKind::Select,
+                    Ok(Value::Array((1..=5).map(int).collect::<Array>())),
+                ),
+                (StatementKind::Select, Ok(Value::Array(Array::new()))),
+                (
+                    StatementKind::Select,
+                    Err(StatementError::Thrown("boom".to_string())),
+                ),
+                (StatementKind::Return, Ok(int(7))),
+                (
+                    StatementKind::Return,
+                    Ok(Value::Array([1, 2].map(int).into_iter().collect::<Array>())),
+                ),
+            ],
+            2,
+        )
+        .collect();
+
+        assert_eq!(
+            chunks,
+            vec![
+                RowChunk {
+                    statement: 0,
+                    rows: Ok(rows(&[1, 2]))
+                },
+                RowChunk {
+                    statement: 0,
+                    rows: Ok(rows(&[3, 4]))
+                },
+                RowChunk {
+                    statement: 0,
+                    rows: Ok(rows(&[5]))
+                },
+                RowChunk {
+                    statement: 1,
+                    rows: Ok(Vec::new())
+                },
+                RowChunk {
+                    statement: 2,
+                    rows: Err(StatementError::Thrown("boom".to_string()))
+                },
+                RowChunk {
+                    statement: 3,
+                    rows: Ok(rows(&[7]))
+                },
+                RowChunk {
+                    statement: 4,
+                    rows: Ok(vec![serde_cbor::to_vec(&[1, 2]).unwrap()])
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::convert::{IntegerPolicy, WireEncoding};
+use crate::policy::{AllowAll, Operation, QueryPolicy, UNCLASSIFIED};
+use crate::statement::{self, ParamTables, StatementKind};
+
+/// Namespace, database, and policy a component's statements run under.
+///
+/// When a namespace and database are assigned, every statement set is prefixed with a `USE`
+/// statement so components sharing one `Surreal<Any>` connection cannot observe each other's
+/// selection. `USE` and `DEFINE NAMESPACE` statements from the guest, and statements the analyzer
+/// cannot classify, are rejected unless [`Session::allow_use`] is enabled, and every statement is
+/// checked against the session's [`QueryPolicy`].
+///
+/// A session does not authenticate. For record level access, sign a dedicated `Surreal<Any>` in
+/// with the record access method and hand that client to the component instead.
+#[derive(Clone)]
+pub struct Session {
+    target: Option<(String, String)>,
+    allow_use: bool,
+    policy: Arc<dyn QueryPolicy>,
+    integers: IntegerPolicy,
+    encoding: WireEncoding,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            target: None,
+            allow_use: false,
+            policy: Arc::new(AllowAll),
+            integers: IntegerPolicy::default(),
+            encoding: WireEncoding::default(),
+        }
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("target", &self.target)
+            .field("allow_use", &self.allow_use)
+            .field("integers", &self.integers)
+            .field("encoding", &self.encoding)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Session {
+    pub fn new(namespace: impl Into<String>, database: impl Into<String>) -> Self {
+        Self {
+            target: Some((namespace.into(), database.into())),
+            ..Self::default()
+        }
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.target
+            .as_ref()
+            .map(|(namespace, _)| namespace.as_str())
+    }
+
+    pub fn database(&self) -> Option<&str> {
+        self.target.as_ref().map(|(_, database)| database.as_str())
+    }
+
+    pub fn allow_use(mut self, allow: bool) -> Self {
+        self.allow_use = allow;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: impl QueryPolicy + 'static) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Decides how param integers outside the `i64` range decode. Defaults to
+    /// [`IntegerPolicy::Strict`].
+    pub fn with_integer_policy(mut self, integers: IntegerPolicy) -> Self {
+        self.integers = integers;
+        self
+    }
+
+    pub fn integer_policy(&self) -> IntegerPolicy {
+        self.integers
+    }
+
+    /// Decides the CBOR form params decode from and results encode to. The component chooses it
+    /// per call, and it defaults to [`WireEncoding::Legacy`].
+    pub fn with_wire_encoding(mut self, encoding: WireEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn wire_encoding(&self) -> WireEncoding {
+        self.encoding
+    }
+
+    /// Returns the statement set to execute and how many leading results belong to the session
+    /// prefix.
+    pub(crate) fn prepare(
+        &self,
+        operation: Operation,
+        query: &str,
+        param_tables: &ParamTables,
+    ) -> Result<(String, usize), Rejection> {
+        let verdicts: Vec<_> = statement::parse(query, param_tables)
+            .map_err(Rejection::Parse)?
+            .iter()
+            .map(|statement| {
+                if !self.allow_use {
+                    if !statement.classified {
+                        return Err(UNCLASSIFIED.to_string());
+                    }
+                    if let Some(keyword) = statement.kinds().find_map(session_statement) {
+                        return Err(format!(
+                            "{keyword} statements are not allowed in a component session"
+                        ));
+                    }
+                }
+                self.policy.check(operation, statement)
+            })
+            .collect();
+        if verdicts.iter().any(Result::is_err) {
+            return Err(Rejection::Denied(verdicts));
+        }
+
+        let Some((namespace, database)) = &self.target else {
+            return Ok((query.to_string(), 0));
+        };
+
+        Ok((
+            format!(
+                "USE NS {} DB {};\n{query}",
+                escape_ident(namespace),
+                escape_ident(database)
+            ),
+            1,
+        ))
+    }
+}
+
+/// Why [`Session::prepare`] refused a statement set.
+#[derive(Debug)]
+pub(crate) enum Rejection {
+    /// SurrealDB's parser does not accept the statement set.
+    Parse(surrealdb::Error),
+    /// One verdict per statement, at least one of them a denial.
+    Denied(Vec<Result<(), String>>),
+}
+
+fn escape_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+fn session_statement((kind, resource): (StatementKind, Option<&str>)) -> Option<&'static str> {
+    match (kind, resource) {
+        (StatementKind::Use, _) => Some("USE"),
+        (StatementKind::Define, Some("NAMESPACE" | "NS")) => Some("DEFINE NAMESPACE"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rejection, Session};
+    use crate::policy::{Operation, StatementPolicy};
+    use crate::statement::ParamTables;
+
+    fn prepare(session: &Session, query: &str) -> Result<(String, usize), Rejection> {
+        session.prepare(Operation::Query, query, &ParamTables::new())
+    }
+
+    #[test]
+    fn prefixes_assigned_namespace_and_database() {
+        let session = Session::new("tenant", "app");
+
+        let (query, skip) = prepare(&session, "SELECT * FROM person").unwrap();
+
+        assert_eq!(query, "USE NS `tenant` DB `app`;\nSELECT * FROM person");
+        assert_eq!(skip, 1);
+    }
+
+    #[test]
+    fn rejects_use_and_define_namespace_by_default() {
+        for query in [
+            "USE NS other",
+            "SELECT * FROM person; use db other",
+            "DEFINE NAMESPACE other",
+            "define ns other",
+            "LET $x = USE NS other",
+            "RETURN (DEFINE NAMESPACE other)",
+            "RETURN function() { return 1; }",
+        ] {
+            assert!(
+                prepare(&Session::default(), query).is_err(),
+                "expected {query:?} to be rejected"
+            );
+        }
+
+        assert!(prepare(&Session::default().allow_use(true), "USE NS other").is_ok());
+    }
+
+    #[test]
+    fn ignores_keywords_in_strings_comments_and_expressions() {
+        for query in [
+            "SELECT * FROM person WHERE name = 'USE NS other'",
+            "-- USE NS other\nSELECT * FROM person",
+            "/* DEFINE NAMESPACE other; */ SELECT * FROM person",
+            "SELECT `use` FROM person",
+            "CREATE person SET use = true",
+            "DEFINE TABLE namespace",
+        ] {
+            assert!(
+                prepare(&Session::default(), query).is_ok(),
+                "expected {query:?} to be allowed"
+            );
+        }
+    }
+
+    #[test]
+    fn reports_a_verdict_for_every_statement_when_one_is_denied() {
+        let session = Session::default().with_policy(StatementPolicy::untrusted());
+
+        let Err(Rejection::Denied(verdicts)) =
+            prepare(&session, "SELECT * FROM person; REMOVE DATABASE app")
+        else {
+            panic!("expected the statement set to be denied");
+        };
+
+        assert_eq!(
+            verdicts,
+            vec![
+                Ok(()),
+                Err("REMOVE DATABASE statements are not allowed".to_string())
+            ]
+        );
+    }
+}
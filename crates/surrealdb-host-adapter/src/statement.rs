@@ -0,0 +1,959 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::ops::Range;
+
+use surrealdb_types::{ToSql, ValidationError};
+
+/// Leading keyword of a SurrealQL statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Access,
+    Alter,
+    Begin,
+    Cancel,
+    Commit,
+    Create,
+    Define,
+    Delete,
+    For,
+    If,
+    Info,
+    Insert,
+    Kill,
+    Let,
+    Live,
+    Option,
+    Rebuild,
+    Relate,
+    Remove,
+    Return,
+    Select,
+    Show,
+    Sleep,
+    Throw,
+    Update,
+    Upsert,
+    Use,
+    /// Expressions and anything else that does not start with a statement keyword.
+    Other,
+}
+
+impl StatementKind {
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "ACCESS" => Self::Access,
+            "ALTER" => Self::Alter,
+            "BEGIN" => Self::Begin,
+            "CANCEL" => Self::Cancel,
+            "COMMIT" => Self::Commit,
+            "CREATE" => Self::Create,
+            "DEFINE" => Self::Define,
+            "DELETE" => Self::Delete,
+            "FOR" => Self::For,
+            "IF" => Self::If,
+            "INFO" => Self::Info,
+            "INSERT" => Self::Insert,
+            "KILL" => Self::Kill,
+            "LET" => Self::Let,
+            "LIVE" => Self::Live,
+            "OPTION" => Self::Option,
+            "REBUILD" => Self::Rebuild,
+            "RELATE" => Self::Relate,
+            "REMOVE" => Self::Remove,
+            "RETURN" => Self::Return,
+            "SELECT" => Self::Select,
+            "SHOW" => Self::Show,
+            "SLEEP" => Self::Sleep,
+            "THROW" => Self::Throw,
+            "UPDATE" => Self::Update,
+            "UPSERT" => Self::Upsert,
+            "USE" => Self::Use,
+            _ => return None,
+        })
+    }
+
+    fn has_resource(self) -> bool {
+        matches!(self, Self::Define | Self::Remove | Self::Alter | Self::Info)
+    }
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            Self::Access => "ACCESS",
+            Self::Alter => "ALTER",
+            Self::Begin => "BEGIN",
+            Self::Cancel => "CANCEL",
+            Self::Commit => "COMMIT",
+            Self::Create => "CREATE",
+            Self::Define => "DEFINE",
+            Self::Delete => "DELETE",
+            Self::For => "FOR",
+            Self::If => "IF",
+            Self::Info => "INFO",
+            Self::Insert => "INSERT",
+            Self::Kill => "KILL",
+            Self::Let => "LET",
+            Self::Live => "LIVE",
+            Self::Option => "OPTION",
+            Self::Rebuild => "REBUILD",
+            Self::Relate => "RELATE",
+            Self::Remove => "REMOVE",
+            Self::Return => "RETURN",
+            Self::Select => "SELECT",
+            Self::Show => "SHOW",
+            Self::Sleep => "SLEEP",
+            Self::Throw => "THROW",
+            Self::Update => "UPDATE",
+            Self::Upsert => "UPSERT",
+            Self::Use => "USE",
+            Self::Other => "expression",
+        };
+        f.write_str(keyword)
+    }
+}
+
+/// One top level statement of a statement set, as seen by a [`crate::QueryPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    /// Resource keyword for `DEFINE`, `REMOVE`, and `ALTER`, such as `USER` or `DATABASE`, and
+    /// the level of `INFO FOR`, such as `ROOT`.
+    pub resource: Option<String>,
+    /// Statements nested in blocks and subqueries, such as the body of an `IF`.
+    pub nested: Vec<(StatementKind, Option<String>)>,
+    /// Tables read or written, including tables of record id literals and bound record ids.
+    pub tables: BTreeSet<String>,
+    /// True when a table is only known at runtime, so `tables` may be incomplete. Computed
+    /// targets, records built by `type::record` or a `<record>` cast, `.*` fetches of values the
+    /// analyzer cannot trace, and `->?` edges all count.
+    pub dynamic_tables: bool,
+    /// False when the analyzer cannot account for every statement keyword, such as inside an
+    /// embedded script, so `kind`, `nested`, and `tables` may be incomplete.
+    pub classified: bool,
+}
+
+impl Statement {
+    fn unclassified() -> Self {
+        Self {
+            kind: StatementKind::Other,
+            resource: None,
+            nested: Vec::new(),
+            tables: BTreeSet::new(),
+            dynamic_tables: false,
+            classified: false,
+        }
+    }
+
+    /// The statement itself followed by every nested statement.
+    pub fn kinds(&self) -> impl Iterator<Item = (StatementKind, Option<&str>)> {
+        std::iter::once((self.kind, self.resource.as_deref())).chain(
+            self.nested
+                .iter()
+                .map(|(kind, resource)| (*kind, resource.as_deref())),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    /// Identifier written between backticks or angle brackets, never a keyword.
+    Escaped(String),
+    Param(String),
+    Literal,
+    Open(char),
+    Close,
+    Comma,
+    Colon,
+    PathSeparator,
+    Dot,
+    Star,
+    Arrow,
+    /// Type a value is cast to, such as `record` in `<record> $id`.
+    Cast(String),
+    Semicolon,
+    /// Text the lexer cannot follow, such as an embedded script.
+    Opaque,
+    Other,
+}
+
+/// Tables of the record ids and tables bound through each parameter, used to resolve `$param`
+/// targets and reads.
+pub(crate) type ParamTables = HashMap<String, BTreeSet<String>>;
+
+/// Splits a statement set into top level statements once SurrealDB's parser accepts it.
+///
+/// The statements are read from the parser's canonical rendering of the set, which drops
+/// comments, uppercases keywords, escapes identifiers that collide with keywords, and wraps
+/// statements nested in expressions in parentheses. A statement keyword the analyzer cannot place
+/// leaves the statement unclassified.
+pub(crate) fn parse(
+    query: &str,
+    param_tables: &ParamTables,
+) -> Result<Vec<Statement>, surrealdb::Error> {
+    let ast = surrealdb_core::syn::parse(query)
+        .map_err(|error| surrealdb::Error::validation(error.to_string(), ValidationError::Parse))?;
+    let tokens = tokenize(&ast.to_sql());
+    let statements = split(&tokens);
+
+    // Once the lexer loses track, statement boundaries cannot be trusted either.
+    if statements.len() != ast.num_statements() || tokens.contains(&Token::Opaque) {
+        return Ok(vec![Statement::unclassified(); ast.num_statements()]);
+    }
+
+    Ok(statements
+        .into_iter()
+        .map(|tokens| analyze(tokens, param_tables))
+        .collect())
+}
+
+/// Rewrites a statement set so every statement that takes a `TIMEOUT` clause, nested ones
+/// included, times out at the deadline bound to `$param` in nanoseconds since the Unix epoch. A
+/// statement that already has a shorter `TIMEOUT` keeps it.
+///
+/// Statements inside `DEFINE`, `ALTER`, and `LIVE` statements are stored and run later, so they
+/// are left alone. A set whose statements the analyzer cannot place is refused rather than run
+/// without the bound.
+pub(crate) fn bound_by_deadline(query: &str, param: &str) -> Result<String, surrealdb::Error> {
+    let ast = surrealdb_core::syn::parse(query)
+        .map_err(|error| surrealdb::Error::validation(error.to_string(), ValidationError::Parse))?;
+    let canonical = ast.to_sql();
+    let (tokens, spans): (Vec<_>, Vec<_>) = lex(&canonical).into_iter().unzip();
+    if split(&tokens).len() != ast.num_statements() || tokens.contains(&Token::Opaque) {
+        return Err(surrealdb::Error::validation(
+            "the statement set cannot be bounded by a timeout".to_string(),
+            ValidationError::InvalidRequest,
+        ));
+    }
+
+    let remaining = format!("math::max([${param} - time::nano(), 0])");
+    let mut inserts = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if starts_statement(&tokens, index)
+            && let Some((kind, _)) = statement_at(&tokens, index)
+        {
+            match kind {
+                StatementKind::Define | StatementKind::Alter | StatementKind::Live => {
+                    index = statement_end(&tokens, index);
+                    continue;
+                }
+                StatementKind::Create
+                | StatementKind::Delete
+                | StatementKind::Insert
+                | StatementKind::Relate
+                | StatementKind::Select
+                | StatementKind::Update
+                | StatementKind::Upsert => {
+                    let end = statement_end(&tokens, index);
+                    let (mut timeout, mut explain) = (None, None);
+                    let mut depth = 0usize;
+                    for (clause, token) in tokens.iter().enumerate().take(end).skip(index) {
+                        match token {
+                            Token::Open(_) => depth += 1,
+                            Token::Close => depth = depth.saturating_sub(1),
+                            token if depth == 0 => match keyword(Some(token)) {
+                                Some("TIMEOUT") => timeout = Some(clause),
+                                Some("EXPLAIN") => explain = Some(clause),
+                                _ => {}
+                            },
+                            _ => {}
+                        }
+                    }
+
+                    let clause_end = spans[explain.unwrap_or(end) - 1].end;
+                    match timeout {
+                        Some(timeout) => {
+                            inserts.push((
+                                spans[timeout + 1].start,
+                                "duration::from_nanos(math::min([duration::nanos(".to_string(),
+                            ));
+                            inserts.push((clause_end, format!("), {remaining}]))")));
+                        }
+                        None => inserts.push((
+                            clause_end,
+                            format!(" TIMEOUT duration::from_nanos({remaining})"),
+                        )),
+                    }
+                }
+                _ => {}
+            }
+        }
+        index += 1;
+    }
+
+    inserts.sort_by_key(|(offset, _)| *offset);
+    let mut bounded = String::with_capacity(canonical.len());
+    let mut copied = 0;
+    for (offset, text) in inserts {
+        bounded.push_str(&canonical[copied..offset]);
+        bounded.push_str(&text);
+        copied = offset;
+    }
+    bounded.push_str(&canonical[copied..]);
+    Ok(bounded)
+}
+
+/// Index of the token that ends the statement starting at `start`: the `;` or closing bracket
+/// after it, or the `ELSE` or `END` of the branch it forms.
+fn statement_end(tokens: &[Token], start: usize) -> usize {
+    let is_branch = start
+        .checked_sub(1)
+        .is_some_and(|index| matches!(keyword(tokens.get(index)), Some("THEN" | "ELSE")));
+    let mut depth = 0usize;
+
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Open(_) => depth += 1,
+            Token::Close | Token::Semicolon if depth == 0 => return index,
+            Token::Close => depth -= 1,
+            token if depth == 0 && is_branch => {
+                if matches!(keyword(Some(token)), Some("ELSE" | "END")) {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tokens.len()
+}
+
+/// Keywords after which SurrealQL expects a value, so a `/` starts a regex.
+const VALUE_KEYWORDS: &[&str] = &[
+    "RETURN",
+    "THEN",
+    "ELSE",
+    "WHERE",
+    "AND",
+    "OR",
+    "NOT",
+    "IN",
+    "IS",
+    "CONTAINS",
+    "CONTAINSNOT",
+    "CONTAINSALL",
+    "CONTAINSANY",
+    "CONTAINSNONE",
+    "INSIDE",
+    "NOTINSIDE",
+    "ALLINSIDE",
+    "ANYINSIDE",
+    "NONEINSIDE",
+    "OUTSIDE",
+    "INTERSECTS",
+    "VALUE",
+    "THROW",
+    "IF",
+    "WHEN",
+    "ASSERT",
+    "DEFAULT",
+    "CONTENT",
+    "MERGE",
+    "PATCH",
+    "REPLACE",
+];
+
+/// Tokens a canonical rendering. It has no comments, so only strings, escaped identifiers,
+/// regexes, and scripts need care.
+fn tokenize(query: &str) -> Vec<Token> {
+    lex(query).into_iter().map(|(token, _)| token).collect()
+}
+
+/// Like [`tokenize`], but pairs each token with its byte range in `query`.
+fn lex(query: &str) -> Vec<(Token, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut spans = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' | '⟨' => {
+                let close = if c == '⟨' { '⟩' } else { c };
+                let mut text = String::new();
+                while let Some((_, next)) = chars.next() {
+                    if next == '\\' {
+                        text.extend(chars.next().map(|(_, c)| c));
+                    } else if next == close {
+                        break;
+                    } else {
+                        text.push(next);
+                    }
+                }
+                tokens.push(if matches!(c, '`' | '⟨') {
+                    Token::Escaped(text)
+                } else {
+                    Token::Literal
+                });
+            }
+            '/' => match follows_operand(&tokens) {
+                Some(true) => tokens.push(Token::Other),
+                Some(false) => {
+                    while let Some((_, next)) = chars.next() {
+                        if next == '\\' {
+                            chars.next();
+                        } else if next == '/' {
+                            break;
+                        }
+                    }
+                    tokens.push(Token::Literal);
+                }
+                None => tokens.push(Token::Opaque),
+            },
+            '-' if matches!(chars.peek(), Some((_, '>'))) => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            '<' if matches!(chars.peek(), Some((_, '-'))) => {
+                chars.next();
+                if matches!(chars.peek(), Some((_, '>'))) {
+                    chars.next();
+                }
+                tokens.push(Token::Arrow);
+            }
+            '<' if matches!(chars.peek(), Some((_, '~'))) => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            // The canonical rendering spaces comparisons and glues type arguments to their type,
+            // so only a cast opens with `<` directly before a letter.
+            '<' if matches!(chars.peek(), Some((_, next)) if next.is_alphabetic())
+                && !query[..start]
+                    .ends_with(|previous: char| previous.is_alphanumeric() || previous == '_') =>
+            {
+                let mut kind = String::new();
+                let mut depth = 1usize;
+                for (_, next) in chars.by_ref() {
+                    match next {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                    kind.push(next);
+                }
+                tokens.push(Token::Cast(kind));
+            }
+            ':' if matches!(chars.peek(), Some((_, ':'))) => {
+                chars.next();
+                tokens.push(Token::PathSeparator);
+            }
+            ':' => tokens.push(Token::Colon),
+            '.' => tokens.push(Token::Dot),
+            '*' => tokens.push(Token::Star),
+            '{' | '(' | '[' => tokens.push(Token::Open(c)),
+            '}' | ')' | ']' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            ';' => tokens.push(Token::Semicolon),
+            '$' => {
+                let mut name = String::new();
+                while let Some(&(_, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Param(name));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(&(_, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                // Script bodies are JavaScript, which this lexer does not follow.
+                let is_script = word == "function" && matches!(chars.peek(), Some((_, '(')));
+                tokens.push(Token::Word(word));
+                if is_script {
+                    tokens.push(Token::Opaque);
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => tokens.push(Token::Other),
+        }
+
+        let end = chars.peek().map_or(query.len(), |&(index, _)| index);
+        spans.resize(tokens.len(), start..end);
+    }
+
+    tokens.into_iter().zip(spans).collect()
+}
+
+/// Whether a `/` after `tokens` divides rather than starts a regex, or `None` when that depends
+/// on more context than the previous token.
+fn follows_operand(tokens: &[Token]) -> Option<bool> {
+    let Some(previous) = tokens.last() else {
+        return Some(false);
+    };
+    let Token::Word(word) = previous else {
+        return Some(matches!(
+            previous,
+            Token::Param(_) | Token::Literal | Token::Escaped(_) | Token::Close
+        ));
+    };
+
+    let is_path = matches!(
+        tokens.iter().rev().nth(1),
+        Some(Token::Dot | Token::PathSeparator | Token::Arrow)
+    );
+    if is_path
+        || word.starts_with(|c: char| c.is_ascii_digit())
+        || word.chars().any(char::is_lowercase)
+        || matches!(word.as_str(), "NONE" | "NULL")
+    {
+        Some(true)
+    } else if VALUE_KEYWORDS.contains(&word.as_str()) {
+        Some(false)
+    } else if surrealdb_core::syn::could_be_reserved_keyword(word) {
+        None
+    } else {
+        Some(true)
+    }
+}
+
+fn split(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open(_) => depth += 1,
+            Token::Close => depth = depth.saturating_sub(1),
+            Token::Semicolon if depth == 0 => {
+                if index > start {
+                    statements.push(&tokens[start..index]);
+                }
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if tokens.len() > start {
+        statements.push(&tokens[start..]);
+    }
+
+    statements
+}
+
+/// Keyword of a word token. The canonical rendering writes keywords in uppercase, so words with
+/// lowercase letters are identifiers.
+fn keyword(token: Option<&Token>) -> Option<&str> {
+    match token {
+        Some(Token::Word(word)) if !word.chars().any(char::is_lowercase) => Some(word),
+        _ => None,
+    }
+}
+
+fn statement_at(tokens: &[Token], index: usize) -> Option<(StatementKind, Option<String>)> {
+    let kind = StatementKind::from_keyword(keyword(tokens.get(index))?)?;
+    // Fields, functions, graph edges, and object keys, such as `a.DELETE` or `{ DELETE: 1 }`.
+    let previous = index.checked_sub(1).map(|index| &tokens[index]);
+    if matches!(
+        previous,
+        Some(Token::Dot | Token::PathSeparator | Token::Arrow)
+    ) || tokens.get(index + 1) == Some(&Token::Colon)
+    {
+        return None;
+    }
+
+    let resource = if kind == StatementKind::Info {
+        // INFO FOR <level>
+        keyword(tokens.get(index + 2))
+    } else if kind.has_resource() {
+        keyword(tokens.get(index + 1))
+    } else {
+        None
+    };
+
+    Some((kind, resource.map(str::to_string)))
+}
+
+/// Whether a statement can start at `index`. Statements nested in expressions are always wrapped
+/// in parentheses or blocks by the canonical rendering.
+fn starts_statement(tokens: &[Token], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|index| &tokens[index]) else {
+        return true;
+    };
+
+    matches!(previous, Token::Open('{' | '(') | Token::Semicolon)
+        || matches!(keyword(Some(previous)), Some("THEN" | "ELSE" | "AS"))
+}
+
+/// Whether the statement keyword at `index` belongs to a clause, such as `RETURN NONE`,
+/// `ON DELETE CASCADE`, or `DEFINE ACCESS`, rather than starting a statement.
+fn is_clause(tokens: &[Token], index: usize, kind: StatementKind) -> bool {
+    let previous = keyword(tokens.get(index - 1));
+    match kind {
+        StatementKind::Return | StatementKind::For => true,
+        StatementKind::If => matches!(keyword(tokens.get(index + 1)), Some("NOT" | "EXISTS")),
+        StatementKind::Delete | StatementKind::Update => matches!(previous, Some("ON" | "KEY")),
+        StatementKind::Select => previous == Some("LIVE"),
+        StatementKind::Show => tokens[..index]
+            .iter()
+            .any(|token| keyword(Some(token)) == Some("ACCESS")),
+        _ => matches!(previous, Some("DEFINE" | "REMOVE" | "ALTER")),
+    }
+}
+
+fn analyze(tokens: &[Token], param_tables: &ParamTables) -> Statement {
+    let (kind, resource) = statement_at(tokens, 0).unwrap_or((StatementKind::Other, None));
+    let mut statement = Statement {
+        kind,
+        resource,
+        nested: Vec::new(),
+        tables: BTreeSet::new(),
+        dynamic_tables: false,
+        classified: true,
+    };
+    let mut groups: Vec<char> = Vec::new();
+
+    for index in 0..tokens.len() {
+        let previous = index.checked_sub(1).map(|index| &tokens[index]);
+        let starts_statement = starts_statement(tokens, index);
+        if index > 0
+            && let Some((kind, resource)) = statement_at(tokens, index)
+        {
+            if starts_statement {
+                statement.nested.push((kind, resource));
+            } else if !is_clause(tokens, index, kind) {
+                statement.classified = false;
+            }
+        }
+
+        match &tokens[index] {
+            Token::Open(c) => groups.push(*c),
+            Token::Close => {
+                groups.pop();
+            }
+            Token::Word(word) | Token::Escaped(word) => {
+                let is_target_keyword = match keyword(Some(&tokens[index])) {
+                    Some("FROM" | "INTO" | "TABLE") => true,
+                    Some("CREATE" | "UPDATE" | "UPSERT" | "DELETE") => starts_statement,
+                    Some("ON") => !matches!(
+                        keyword(tokens.get(index + 1)),
+                        Some("ROOT" | "NAMESPACE" | "NS" | "DATABASE" | "DB" | "DUPLICATE")
+                    ),
+                    _ => false,
+                };
+                if is_target_keyword {
+                    collect_targets(tokens, index + 1, param_tables, &mut statement);
+                }
+
+                // `table:key` is a record id unless it is a key of an object literal.
+                let is_object_key = groups.last() == Some(&'{')
+                    && matches!(previous, Some(Token::Open('{') | Token::Comma));
+                if !is_object_key && tokens.get(index + 1) == Some(&Token::Colon) {
+                    statement.tables.insert(word.clone());
+                }
+
+                // Records built at runtime, such as `type::record('secret', 1)`.
+                if word == "type"
+                    && tokens.get(index + 1) == Some(&Token::PathSeparator)
+                    && matches!(tokens.get(index + 2), Some(Token::Word(function)) if function == "record" || function == "thing")
+                {
+                    statement.dynamic_tables = true;
+                }
+            }
+            Token::Param(name) => {
+                if let Some(tables) = param_tables.get(name) {
+                    statement.tables.extend(tables.iter().cloned());
+                }
+            }
+            Token::Cast(kind)
+                if kind
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| word == "record") =>
+            {
+                statement.dynamic_tables = true;
+            }
+            Token::Dot
+                if tokens.get(index + 1) == Some(&Token::Star)
+                    && !fetches_known_records(tokens, index, param_tables) =>
+            {
+                statement.dynamic_tables = true;
+            }
+            Token::Arrow => {
+                let edge = match tokens.get(index + 1) {
+                    Some(Token::Open('(')) => tokens.get(index + 2),
+                    edge => edge,
+                };
+                match edge {
+                    Some(Token::Word(table) | Token::Escaped(table)) => {
+                        statement.tables.insert(table.clone());
+                    }
+                    // Any table, such as `->?`.
+                    _ => statement.dynamic_tables = true,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    statement
+}
+
+/// Whether the `.*` at `dot` fetches records of tables the analyzer has already collected: those
+/// of a bound parameter, a record id literal such as `(person:1).*`, or a graph edge.
+fn fetches_known_records(tokens: &[Token], dot: usize, param_tables: &ParamTables) -> bool {
+    match dot.checked_sub(1).map(|index| &tokens[index]) {
+        Some(Token::Param(name)) => param_tables.contains_key(name),
+        Some(Token::Word(_) | Token::Escaped(_)) => dot >= 2 && tokens[dot - 2] == Token::Arrow,
+        Some(Token::Close) => matches!(
+            tokens[dot.saturating_sub(5)..dot],
+            [
+                Token::Open('('),
+                Token::Word(_) | Token::Escaped(_),
+                Token::Colon,
+                Token::Word(_) | Token::Escaped(_) | Token::Literal,
+                Token::Close,
+            ]
+        ),
+        _ => false,
+    }
+}
+
+const TARGET_MODIFIERS: &[&str] = &["ONLY", "TABLE", "IF", "NOT", "EXISTS", "RELATION", "IGNORE"];
+
+fn collect_targets(
+    tokens: &[Token],
+    mut index: usize,
+    param_tables: &ParamTables,
+    statement: &mut Statement,
+) {
+    while let Some(keyword) = keyword(tokens.get(index)) {
+        if !TARGET_MODIFIERS.contains(&keyword) {
+            break;
+        }
+        index += 1;
+    }
+
+    loop {
+        match tokens.get(index) {
+            Some(Token::Word(word)) => {
+                if tokens.get(index + 1) == Some(&Token::PathSeparator) {
+                    statement.dynamic_tables = true;
+                } else if StatementKind::from_keyword(&word.to_ascii_uppercase()).is_none() {
+                    statement.tables.insert(word.clone());
+                }
+            }
+            Some(Token::Escaped(table)) => {
+                statement.tables.insert(table.clone());
+            }
+            // The tables of bound records are collected wherever the parameter appears.
+            Some(Token::Param(name)) => {
+                if param_tables.get(name).is_none_or(BTreeSet::is_empty) {
+                    statement.dynamic_tables = true;
+                }
+            }
+            _ => return,
+        }
+
+        index += 1;
+        // Skip the rest of the target, such as a record key or a function call, up to the
+        // next comma at the same depth.
+        let mut depth = 0usize;
+        loop {
+            match tokens.get(index) {
+                Some(Token::Open(_)) => depth += 1,
+                Some(Token::Close) if depth > 0 => depth -= 1,
+                Some(Token::Comma) if depth == 0 => {
+                    index += 1;
+                    break;
+                }
+                Some(Token::Colon | Token::PathSeparator | Token::Literal) => {}
+                Some(Token::Word(_) | Token::Escaped(_))
+                    if tokens.get(index - 1) == Some(&Token::Colon) => {}
+                Some(_) if depth > 0 => {}
+                _ => return,
+            }
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{ParamTables, StatementKind, bound_by_deadline, parse};
+
+    fn tables(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_statements_and_reads_kinds_and_resources() {
+        let statements = parse(
+            "REMOVE DATABASE app; define user admin ON ROOT PASSWORD 'x'; INFO FOR ROOT; KILL $id",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        let kinds: Vec<_> = statements
+            .iter()
+            .map(|statement| (statement.kind, statement.resource.as_deref()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (StatementKind::Remove, Some("DATABASE")),
+                (StatementKind::Define, Some("USER")),
+                (StatementKind::Info, Some("ROOT")),
+                (StatementKind::Kill, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_semicolons_in_strings_comments_and_blocks() {
+        let statements = parse(
+            "SELECT * FROM person WHERE name = 'a; REMOVE DATABASE app'; -- KILL x;\nIF true { CREATE a; CREATE b }",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].kind, StatementKind::Select);
+        assert_eq!(statements[1].kind, StatementKind::If);
+    }
+
+    #[test]
+    fn records_nested_statements() {
+        let statements = parse(
+            "IF true THEN (REMOVE TABLE secret) ELSE { DEFINE USER x ON DATABASE PASSWORD 'x' } END; \
+             LET $x = DELETE secret; \
+             RETURN DELETE secret; \
+             UPDATE person SET audit = fn::log(REMOVE TABLE log)",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        let nested = &statements[0].nested;
+        assert!(nested.contains(&(StatementKind::Remove, Some("TABLE".to_string()))));
+        assert!(nested.contains(&(StatementKind::Define, Some("USER".to_string()))));
+        for statement in &statements[1..3] {
+            assert_eq!(statement.nested, vec![(StatementKind::Delete, None)]);
+            assert_eq!(statement.tables, tables(&["secret"]));
+        }
+        assert_eq!(
+            statements[3].nested,
+            vec![(StatementKind::Remove, Some("TABLE".to_string()))]
+        );
+        assert!(statements.iter().all(|statement| statement.classified));
+    }
+
+    #[test]
+    fn tells_clauses_fields_and_keys_from_statements() {
+        let statements = parse(
+            "CREATE person SET name = 'x' RETURN NONE; \
+             DEFINE TABLE IF NOT EXISTS post PERMISSIONS FOR select, delete WHERE true; \
+             DEFINE FIELD owner ON post TYPE record<person> REFERENCE ON DELETE CASCADE; \
+             INSERT INTO log { n: 1 } ON DUPLICATE KEY UPDATE n += 1; \
+             SELECT a.DELETE, ->DELETE->x FROM person WHERE note = { DELETE: 1 }; \
+             DEFINE ACCESS token ON DATABASE TYPE RECORD; \
+             LIVE SELECT * FROM person; \
+             RETURN /DELETE (secret)/; \
+             RETURN 4 / 2",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        assert!(statements.iter().all(|statement| statement.classified));
+        assert!(
+            statements
+                .iter()
+                .all(|statement| statement.nested.is_empty())
+        );
+        assert_eq!(statements[3].tables, tables(&["log"]));
+        assert_eq!(statements[5].resource.as_deref(), Some("ACCESS"));
+        assert_eq!(statements[6].kind, StatementKind::Live);
+    }
+
+    #[test]
+    fn leaves_scripts_unclassified() {
+        let statements = parse(
+            "RETURN function() { return '/'; }; SELECT * FROM person",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        assert_eq!(statements.len(), 2);
+        assert!(statements.iter().all(|statement| !statement.classified));
+    }
+
+    #[test]
+    fn collects_tables_from_targets_records_and_graph_edges() {
+        let mut param_tables = ParamTables::new();
+        param_tables.insert("id".to_string(), tables(&["account"]));
+
+        let statements = parse(
+            "SELECT *, ->likes->post AS liked FROM person, audit:1 WHERE team = team:core; \
+             UPDATE $id SET name = 'x'; \
+             INSERT INTO log { note: 'x' }; \
+             DEFINE FIELD name ON TABLE profile TYPE string; \
+             CREATE ONLY invoice:⟨2024-01⟩ CONTENT { owner: user:1 }; \
+             RELATE person:1->follows->person:2; \
+             DEFINE USER reader ON DATABASE PASSWORD 'x'; \
+             SELECT * FROM `secret`, ⟨vault⟩:1",
+            &param_tables,
+        )
+        .unwrap();
+
+        assert_eq!(
+            statements[0].tables,
+            tables(&["audit", "likes", "person", "post", "team"])
+        );
+        assert_eq!(statements[1].tables, tables(&["account"]));
+        assert_eq!(statements[2].tables, tables(&["log"]));
+        assert_eq!(statements[3].tables, tables(&["profile"]));
+        assert_eq!(statements[4].tables, tables(&["invoice", "user"]));
+        assert_eq!(statements[5].tables, tables(&["follows", "person"]));
+        assert_eq!(statements[6].tables, tables(&[]));
+        assert_eq!(statements[7].tables, tables(&["secret", "vault"]));
+        assert!(statements.iter().all(|statement| !statement.dynamic_tables));
+    }
+
+    #[test]
+    fn marks_runtime_targets_as_dynamic() {
+        let statements = parse(
+            "SELECT * FROM type::table($table); DELETE $unknown",
+            &ParamTables::new(),
+        )
+        .unwrap();
+
+        assert!(statements[0].dynamic_tables);
+        assert!(statements[1].dynamic_tables);
+    }
+
+    #[test]
+    fn bounds_statements_that_take_a_timeout_by_the_deadline() {
+        let bounded = bound_by_deadline(
+            "SLEEP 1s; CREATE a; LET $x = (SELECT * FROM b TIMEOUT 5s EXPLAIN); \
+             IF true THEN UPDATE c ELSE DELETE d END; \
+             DEFINE FUNCTION fn::f() { CREATE e }",
+            "deadline",
+        )
+        .unwrap();
+
+        let bound = "math::max([$deadline - time::nano(), 0])";
+        assert_eq!(
+            bounded,
+            format!(
+                "SLEEP 1s;\n\
+                 CREATE a TIMEOUT duration::from_nanos({bound});\n\
+                 LET $x = (SELECT * FROM b TIMEOUT duration::from_nanos(math::min([duration::nanos(5s), {bound}])) EXPLAIN);\n\
+                 IF true THEN UPDATE c TIMEOUT duration::from_nanos({bound}) ELSE (DELETE d TIMEOUT duration::from_nanos({bound})) END;\n\
+                 DEFINE FUNCTION fn::f() {{ CREATE e }} PERMISSIONS FULL;"
+            )
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::trace::{
+    Span, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId, TraceState,
+    Tracer,
+};
+use opentelemetry::{Context, KeyValue};
+
+use crate::bindings::wasmcloud::observability::propagation::TraceContext;
+use crate::{LiveAction, error_chain};
+
+const TRACER_NAME: &str = "surrealdb-host-adapter";
+
+pub(crate) const QUERY_SPAN: &str = "surrealdb.query";
+pub(crate) const QUERY_STREAM_SPAN: &str = "surrealdb.query_stream";
+pub(crate) const SUBSCRIBE_SPAN: &str = "surrealdb.subscribe";
+pub(crate) const CANCEL_SPAN: &str = "surrealdb.cancel";
+pub(crate) const TRANSACTION_BEGIN_SPAN: &str = "surrealdb.transaction.begin";
+pub(crate) const TRANSACTION_QUERY_SPAN: &str = "surrealdb.transaction.query";
+pub(crate) const TRANSACTION_COMMIT_SPAN: &str = "surrealdb.transaction.commit";
+pub(crate) const TRANSACTION_CANCEL_SPAN: &str = "surrealdb.transaction.cancel";
+pub(crate) const NOTIFICATION_SPAN: &str = "surrealdb.live.notification";
+
+pub(crate) fn parent_context(trace_context: Option<&TraceContext>) -> Context {
+    trace_context
+        .and_then(|context| {
+            remote_span_context(&context.traceparent, context.tracestate.as_deref())
+        })
+        .map(|span_context| Context::new().with_remote_span_context(span_context))
+        .unwrap_or_default()
+}
+
+fn remote_span_context(traceparent: &str, tracestate: Option<&str>) -> Option<SpanContext> {
+    let mut parts = traceparent.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    if version.len() != 2 || trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+        return None;
+    }
+
+    let version = u8::from_str_radix(version, 16).ok()?;
+    if version == 0xff || (version == 0 && parts.next().is_some()) {
+        return None;
+    }
+
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    if trace_id == TraceId::INVALID || span_id == SpanId::INVALID {
+        return None;
+    }
+
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+    let trace_state = tracestate
+        .and_then(|tracestate| TraceState::from_str(tracestate).ok())
+        .unwrap_or_default();
+
+    Some(SpanContext::new(
+        trace_id,
+        span_id,
+        TraceFlags::new(flags & TraceFlags::SAMPLED.to_u8()),
+        true,
+        trace_state,
+    ))
+}
+
+pub(crate) fn start_span(
+    name: &'static str,
+    parent: &Context,
+    attributes: Vec<KeyValue>,
+) -> BoxedSpan {
+    let tracer = global::tracer(TRACER_NAME);
+    tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, parent)
+}
+
+pub(crate) fn call_attributes(
+    operation: &'static str,
+    query: &str,
+    param_count: usize,
+) -> Vec<KeyValue> {
+    let mut attributes = operation_attributes(operation);
+    attributes.push(KeyValue::new("db.query.text", query.to_string()));
+    attributes.push(KeyValue::new("surrealdb.param.count", param_count as i64));
+    attributes
+}
+
+pub(crate) fn record_statement_results<S: Span, E>(span: &mut S, results: &[Result<Vec<u8>, E>]) {
+    let result_bytes: usize = results
+        .iter()
+        .filter_map(|result| result.as_ref().ok())
+        .map(Vec::len)
+        .sum();
+    let error_count = results.iter().filter(|result| result.is_err()).count();
+
+    span.set_attribute(KeyValue::new(
+        "surrealdb.statement.count",
+        results.len() as i64,
+    ));
+    span.set_attribute(KeyValue::new("surrealdb.result.bytes", result_bytes as i64));
+    span.set_attribute(KeyValue::new(
+        "surrealdb.statement.error_count",
+        error_count as i64,
+    ));
+    if error_count > 0 {
+        span.set_status(Status::error(format!(
+            "{error_count} of {} statements failed",
+            results.len()
+        )));
+    }
+}
+
+pub(crate) fn record_error<S: Span>(span: &mut S, error: &(dyn std::error::Error + 'static)) {
+    span.set_status(Status::error(error_chain(error)));
+}
+
+pub(crate) fn operation_attributes(operation: &'static str) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("db.system.name", "surrealdb"),
+        KeyValue::new("db.operation.name", operation),
+    ]
+}
+
+pub(crate) fn notification_attributes(
+    query_id: &str,
+    action: LiveAction,
+    data_len: usize,
+) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("surrealdb.live.query_id", query_id.to_string()),
+        KeyValue::new("surrealdb.live.action", live_action_name(action)),
+        KeyValue::new("surrealdb.result.bytes", data_len as i64),
+    ]
+}
+
+fn live_action_name(action: LiveAction) -> &'static str {
+    match action {
+        LiveAction::Create => "create",
+        LiveAction::Update => "update",
+        LiveAction::Delete => "delete",
+        LiveAction::Killed => "killed",
+        LiveAction::Lagged => "lagged",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::{SpanId, TraceContextExt, TraceId};
+
+    use super::{parent_context, remote_span_context};
+    use crate::bindings::wasmcloud::observability::propagation::TraceContext;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parses_w3c_traceparent_and_tracestate() {
+        let span_context = remote_span_context(TRACEPARENT, Some("vendor=value")).unwrap();
+
+        assert_eq!(
+            span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+        assert_eq!(
+            span_context.span_id(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap()
+        );
+        assert!(span_context.is_sampled());
+        assert!(span_context.is_remote());
+        assert_eq!(span_context.trace_state().get("vendor"), Some("value"));
+    }
+
+    #[test]
+    fn rejects_malformed_traceparents() {
+        for traceparent in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-not-hex-01",
+        ] {
+            assert!(
+                remote_span_context(traceparent, None).is_none(),
+                "expected {traceparent:?} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn absent_or_invalid_trace_context_yields_empty_parent() {
+        assert!(!parent_context(None).has_active_span());
+
+        let invalid = TraceContext {
+            traceparent: "invalid".to_string(),
+            tracestate: None,
+        };
+        assert!(!parent_context(Some(&invalid)).has_active_span());
+    }
+
+    #[test]
+    fn valid_trace_context_yields_remote_parent() {
+        let context = TraceContext {
+            traceparent: TRACEPARENT.to_string(),
+            tracestate: None,
+        };
+
+        let parent = parent_context(Some(&context));
+        assert!(parent.has_active_span());
+        assert!(parent.span().span_context().is_remote());
+    }
+}
@@ -1,6 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
+use futures_util::{StreamExt, future};
 use serde::Deserialize;
-use surrealdb_component_sdk::{LiveAction, RecordId, query, subscribe};
+use surrealdb_component_sdk::{LiveAction, RecordId, TypedLiveEvent, query, subscribe};
 
 #[derive(Debug, Deserialize)]
 struct Person {
@@ -64,5 +65,27 @@ pub async fn run() -> Result<()> {
 
     subscription.cancel().await?;
 
+    let mut creates = subscribe("LIVE SELECT * FROM person_live")
+        .typed::<Person>()
+        .execute()
+        .await?
+        .filter(|event| future::ready(matches!(event, Ok(TypedLiveEvent::Created(_)))));
+
+    query("CREATE person_live:stream CONTENT { name: 'stream', age: 7 }")
+        .execute()
+        .await?;
+
+    let stream_event = creates
+        .next()
+        .await
+        .context("live stream ended before create event")??;
+    let TypedLiveEvent::Created(stream_payload) = stream_event else {
+        return Err(anyhow!("expected a created event, got {stream_event:?}"));
+    };
+    assert_eq!(stream_payload.id, RecordId::new("person_live", "stream"));
+    assert_eq!(stream_payload.name, "stream");
+
+    drop(creates);
+
     Ok(())
 }
@@ -1,12 +1,20 @@
 pub mod basic_query;
 pub mod live_query;
+pub mod query_errors;
+pub mod row_stream;
 pub mod special_types;
+pub mod table_model;
+pub mod transaction;
 
 use anyhow::Result;
 
 pub async fn run_all() -> Result<()> {
     basic_query::run().await?;
     live_query::run().await?;
+    query_errors::run().await?;
+    row_stream::run().await?;
     special_types::run().await?;
+    table_model::run().await?;
+    transaction::run().await?;
     Ok(())
 }
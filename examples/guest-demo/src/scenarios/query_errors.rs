@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use surrealdb_component_sdk::{QueryError, query};
+
+pub async fn run() -> Result<()> {
+    let result = query("BEGIN; CREATE person_errors:demo; THROW 'boom'; COMMIT;")
+        .execute()
+        .await?;
+
+    let Some(error) = result.find_user_error() else {
+        return Err(anyhow!("expected a thrown statement error"));
+    };
+    assert!(matches!(error, QueryError::Thrown(_)));
+
+    let created = result.parse_result::<serde_json::Value>(0)?;
+    assert!(matches!(created, Err(QueryError::Thrown(_))));
+
+    let result = query("SELEC * FROM person_errors").execute().await?;
+    let Some(error) = result.find_user_error() else {
+        return Err(anyhow!("expected a parse error"));
+    };
+    assert!(matches!(error, QueryError::ParseError(_)));
+
+    let result = query("SELECT * FROM person_errors; REMOVE DATABASE demo;")
+        .execute()
+        .await?;
+    assert!(matches!(
+        result.parse_result::<serde_json::Value>(1)?,
+        Err(QueryError::NotAllowed(_))
+    ));
+    assert!(matches!(
+        result.find_user_error(),
+        Some(QueryError::NotAllowed(_))
+    ));
+
+    let result = query("SLEEP 5s; RETURN true;")
+        .timeout(Duration::from_millis(50))
+        .execute()
+        .await?;
+    assert!(matches!(
+        result.find_user_error(),
+        Some(QueryError::Timeout(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anyhow::{Result, ensure};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use surrealdb_component_sdk::{QueryError, query};
+
+const ROWS: i64 = 600;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reading {
+    seq: i64,
+}
+
+pub async fn run() -> Result<()> {
+    let readings: Vec<Reading> = (0..ROWS).map(|seq| Reading { seq }).collect();
+    query("INSERT INTO reading_stream $readings")
+        .bind("readings", readings)
+        .execute()
+        .await?;
+
+    let mut rows = query("SELECT seq FROM reading_stream ORDER BY seq")
+        .stream::<Reading>()
+        .await?;
+    let mut expected = 0;
+    while let Some(row) = rows.next_row().await? {
+        ensure!(
+            row.seq == expected,
+            "expected seq {expected}, got {}",
+            row.seq
+        );
+        expected += 1;
+    }
+    ensure!(
+        expected == ROWS,
+        "expected {ROWS} streamed rows, got {expected}"
+    );
+
+    let mut rows = query("THROW 'stream failure'; RETURN 1")
+        .stream::<i64>()
+        .await?;
+    let error = rows
+        .next()
+        .await
+        .transpose()
+        .err()
+        .and_then(|error| error.downcast::<QueryError>().ok());
+    ensure!(
+        matches!(error, Some(QueryError::Thrown(_))),
+        "expected thrown statement error, got {error:?}"
+    );
+    ensure!(
+        rows.statement() == Some(0),
+        "expected error from statement 0"
+    );
+    ensure!(
+        rows.next_row().await? == Some(1),
+        "expected row from statement 1"
+    );
+    ensure!(rows.next_row().await?.is_none(), "expected stream to end");
+
+    Ok(())
+}
@@ -1,9 +1,9 @@
 use anyhow::{Context, Result, anyhow, ensure};
 use chrono::{TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use surrealdb_component_sdk::{
-    Bytes, Datetime, Decimal, Geometry, RecordId, RecordIdKey, RecordIdValue, Regex, Uuid, query,
+    Bytes, Datetime, Decimal, FileRef, Geometry, MaybeNone, Point, Range, RecordId, RecordIdKey,
+    RecordIdRange, RecordIdValue, Regex, Set, Uuid, Value, query,
 };
 
 type SurrealDuration = surrealdb_component_sdk::Duration;
@@ -30,6 +30,13 @@ struct StoredSpecialTypesDocument {
     document: SpecialTypesDocument,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Profile {
+    #[serde(default)]
+    nickname: MaybeNone<String>,
+    tags: Set<String>,
+}
+
 pub async fn run() -> Result<()> {
     query("DEFINE TABLE special_types SCHEMALESS")
         .execute()
@@ -55,6 +62,113 @@ pub async fn run() -> Result<()> {
         "expected binary payload to survive round trip"
     );
 
+    let Some(dynamic): Option<Value> = result.take(0)? else {
+        return Err(anyhow!("expected 1 dynamic special_types row, got 0"));
+    };
+    ensure!(
+        dynamic["id"].as_record_id() == Some(&row.id),
+        "expected dynamic row id to decode as a record id"
+    );
+    ensure!(
+        dynamic["uuid"] == Value::Uuid(expected.uuid),
+        "expected dynamic uuid field to keep its tag"
+    );
+    ensure!(
+        matches!(dynamic.pointer("/seen_at"), Some(Value::Datetime(_))),
+        "expected dynamic datetime field to keep its tag"
+    );
+
+    let distance = query("RETURN geo::distance($location, (4.9, 52.37))")
+        .bind("location", &expected.location)
+        .execute()
+        .await?;
+    ensure!(
+        distance.parse::<f64>(0)? < 1_000.0,
+        "expected geometry param to bind as a native point"
+    );
+
+    let extended = query("RETURN $duration + 30m")
+        .bind("duration", expected.duration)
+        .execute()
+        .await?;
+    ensure!(
+        extended.parse::<SurrealDuration>(0)? == SurrealDuration::from_secs(2 * 60 * 60),
+        "expected duration param to bind as a native duration"
+    );
+
+    let generated = Uuid::new_v7();
+    let version = query("RETURN type::is_uuid($id)")
+        .bind("id", generated.cbor_tagged())
+        .execute()
+        .await?;
+    ensure!(
+        generated.version() == 7 && version.parse::<bool>(0)?,
+        "expected a generated v7 uuid to bind as a native uuid through CBOR tag 37"
+    );
+
+    let is_datetime = query("RETURN type::is_datetime($at)")
+        .bind("at", &expected.seen_at)
+        .execute()
+        .await?;
+    ensure!(
+        is_datetime.parse::<bool>(0)?,
+        "expected datetime param to bind as a native datetime"
+    );
+
+    let in_range = query(
+        "CREATE special_types:1, special_types:2, special_types:3; SELECT VALUE id FROM $ids",
+    )
+    .bind("ids", RecordIdRange::new("special_types", 1_i64..3))
+    .execute()
+    .await?;
+    ensure!(
+        in_range.parse::<Vec<RecordId>>(1)?
+            == vec![
+                RecordId::new("special_types", 1_i64),
+                RecordId::new("special_types", 2_i64),
+            ],
+        "expected a record id range param to select special_types:1..3"
+    );
+
+    let file = FileRef::new("avatars", "demo.png");
+    let echoed = query("RETURN [$file, $span]")
+        .bind("file", &file)
+        .bind("span", Range::from(1_i64..=5))
+        .execute()
+        .await?;
+    ensure!(
+        echoed.parse::<(FileRef, Range<i64>)>(0)? == (file, Range::from(1_i64..=5)),
+        "expected file and range params to round trip as native values"
+    );
+
+    let profile = query(
+        "DEFINE TABLE profile SCHEMAFULL;
+         DEFINE FIELD nickname ON profile TYPE option<string>;
+         DEFINE FIELD tags ON profile TYPE set<string>;
+         CREATE ONLY profile:demo CONTENT $profile RETURN nickname, tags;",
+    )
+    .bind(
+        "profile",
+        Profile {
+            nickname: MaybeNone::None,
+            tags: Set::from(vec![
+                "wasm".to_string(),
+                "rust".to_string(),
+                "wasm".to_string(),
+            ]),
+        },
+    )
+    .execute()
+    .await?;
+    ensure!(
+        profile.parse::<Profile>(3)?
+            == Profile {
+                nickname: MaybeNone::None,
+                tags: Set::from(vec!["rust".to_string(), "wasm".to_string()]),
+            },
+        "expected NONE to satisfy option<string> and the set to come back deduplicated"
+    );
+
     Ok(())
 }
 
@@ -65,16 +179,13 @@ fn expected_document() -> Result<SpecialTypesDocument> {
         .context("failed to build test datetime")?;
 
     Ok(SpecialTypesDocument {
-        uuid: Uuid::from("018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d"),
+        uuid: "018f6b5b-f4b4-7f28-8b34-9b46ef4f2f4d".parse()?,
         decimal: Decimal::from("12.34"),
-        duration: SurrealDuration::from("1h30m"),
+        duration: "1h30m".parse()?,
         regex: Regex::from("^[a-z]+$"),
         seen_at: Datetime::from(seen_at),
         payload: Bytes::from(vec![1, 0, 255, 42]),
-        location: Geometry::from(json!({
-            "type": "Point",
-            "coordinates": [4.895168, 52.370216]
-        })),
+        location: Geometry::Point(Point::new(4.895168, 52.370216)),
         string_record: RecordId::new("person", "demo"),
         number_record: RecordId::new("person", 42_i64),
         uuid_record: RecordId::new(
@@ -0,0 +1,50 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use surrealdb_component_sdk::{RecordId, Table, query};
+
+#[derive(Debug, Serialize, Deserialize, Table)]
+#[table = "person_table"]
+struct Person {
+    id: RecordId,
+    name: String,
+    age: i64,
+}
+
+pub async fn run() -> Result<()> {
+    query("DEFINE TABLE person_table SCHEMALESS")
+        .execute()
+        .await?;
+
+    let mut person = Person {
+        id: Person::record_id("demo"),
+        name: "demo".to_string(),
+        age: 42,
+    }
+    .create()
+    .await?;
+    assert_eq!(person.id, RecordId::new("person_table", "demo"));
+
+    person.age = 43;
+    let Some(updated) = person.update().await? else {
+        return Err(anyhow!("expected person_table:demo to be updated"));
+    };
+    assert_eq!(updated.age, 43);
+
+    let Some(merged) = Person::merge("demo", json!({ "name": "merged" })).await? else {
+        return Err(anyhow!("expected person_table:demo to be merged"));
+    };
+    assert_eq!(merged.name, "merged");
+    assert_eq!(merged.age, 43);
+
+    let Some(selected) = Person::select("demo").await? else {
+        return Err(anyhow!("expected person_table:demo to exist"));
+    };
+    assert_eq!(selected.name, "merged");
+    assert_eq!(Person::select_all().await?.len(), 1);
+
+    Person::delete("demo").await?;
+    assert!(Person::select("demo").await?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use surrealdb_component_sdk::{begin, query};
+
+pub async fn run() -> Result<()> {
+    query("DEFINE TABLE person_transaction SCHEMALESS")
+        .execute()
+        .await?;
+
+    let committed = begin().await?;
+    committed
+        .query("CREATE person_transaction:committed CONTENT { name: $name }")
+        .bind("name", "committed")
+        .execute()
+        .await?;
+    committed.commit().await?;
+
+    let dropped = begin().await?;
+    dropped
+        .query("CREATE person_transaction:dropped CONTENT { name: 'dropped' }")
+        .execute()
+        .await?;
+    drop(dropped);
+
+    let result = query("SELECT VALUE name FROM person_transaction ORDER BY name")
+        .execute()
+        .await?;
+    let names: Vec<String> = result.take(0)?;
+    assert_eq!(names, vec!["committed".to_string()]);
+
+    Ok(())
+}